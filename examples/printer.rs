@@ -1,16 +1,21 @@
 use anyhow::Result;
+use openapi::OpenApi;
 
 fn main() -> Result<()> {
     if let Some(path) = std::env::args().nth(1) {
         let spec = openapi::from_path(path)?;
-        /*for (path, op) in spec.paths {
-            println!("{}", path);
-            println!("{:#?}", op);
+        match &spec {
+            OpenApi::V2(spec) => {
+                for (path, method, operation) in spec.operations() {
+                    println!("{} {} {:?}", method, path, operation.operation_id);
+                }
+            }
+            OpenApi::V3_0(spec) => {
+                for (path, method, operation) in spec.operations_with_callbacks() {
+                    println!("{} {} {:?}", method, path, operation.operation_id);
+                }
+            }
         }
-        for (name, definition) in spec.definitions {
-            println!("{}", name);
-            println!("{:#?}", definition);
-        }*/
         println!("{}", openapi::to_json(&spec)?);
     }
     Ok(())
@@ -0,0 +1,267 @@
+//! Parsing of the serde attributes which affect the serialized form of a type.
+
+use syn::{
+    meta::ParseNestedMeta, parenthesized, token, Attribute, Expr, Lit, LitStr, Meta, Result,
+};
+
+/// Attributes of a struct or enum.
+#[derive(Default)]
+pub(crate) struct Container {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+    pub tag: Option<String>,
+    pub content: Option<String>,
+    pub untagged: bool,
+    pub transparent: bool,
+    pub default: bool,
+}
+
+/// Attributes of an enum variant.
+#[derive(Default)]
+pub(crate) struct Variant {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+    pub skip: bool,
+}
+
+/// Attributes of a field.
+#[derive(Default)]
+pub(crate) struct Field {
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub flatten: bool,
+    pub default: bool,
+    pub skip_serializing_if: bool,
+}
+
+impl Container {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut container = Container::default();
+        each_serde(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                container.rename = serialized_name(&meta)?;
+            } else if meta.path.is_ident("rename_all") {
+                container.rename_all = rename_rule(&meta)?;
+            } else if meta.path.is_ident("tag") {
+                container.tag = Some(string(&meta)?.value());
+            } else if meta.path.is_ident("content") {
+                container.content = Some(string(&meta)?.value());
+            } else if meta.path.is_ident("untagged") {
+                container.untagged = true;
+            } else if meta.path.is_ident("transparent") {
+                container.transparent = true;
+            } else if meta.path.is_ident("default") {
+                container.default = true;
+                skip(&meta)?;
+            } else {
+                skip(&meta)?;
+            }
+            Ok(())
+        })?;
+        Ok(container)
+    }
+}
+
+impl Variant {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut variant = Variant::default();
+        each_serde(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                variant.rename = serialized_name(&meta)?;
+            } else if meta.path.is_ident("rename_all") {
+                variant.rename_all = rename_rule(&meta)?;
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                variant.skip = true;
+            } else {
+                skip(&meta)?;
+            }
+            Ok(())
+        })?;
+        Ok(variant)
+    }
+}
+
+impl Field {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut field = Field::default();
+        each_serde(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                field.rename = serialized_name(&meta)?;
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                field.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                field.flatten = true;
+            } else if meta.path.is_ident("default") {
+                field.default = true;
+                skip(&meta)?;
+            } else if meta.path.is_ident("skip_serializing_if") {
+                field.skip_serializing_if = true;
+                skip(&meta)?;
+            } else {
+                skip(&meta)?;
+            }
+            Ok(())
+        })?;
+        Ok(field)
+    }
+}
+
+/// The lines of the doc comments, joined by newlines.
+pub(crate) fn docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(doc) => match &doc.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(line) => Some(line.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let docs = lines.join("\n").trim().to_string();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
+fn each_serde(
+    attrs: &[Attribute],
+    mut parse: impl FnMut(ParseNestedMeta) -> Result<()>,
+) -> Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(&mut parse)?;
+    }
+    Ok(())
+}
+
+fn string(meta: &ParseNestedMeta) -> Result<LitStr> {
+    meta.value()?.parse()
+}
+
+/// The name of `rename = "..."` or of `rename(serialize = "...")`.
+fn serialized_name(meta: &ParseNestedMeta) -> Result<Option<String>> {
+    if meta.input.peek(token::Paren) {
+        let mut name = None;
+        meta.parse_nested_meta(|inner| {
+            let value = string(&inner)?;
+            if inner.path.is_ident("serialize") {
+                name = Some(value.value());
+            }
+            Ok(())
+        })?;
+        Ok(name)
+    } else {
+        Ok(Some(string(meta)?.value()))
+    }
+}
+
+fn rename_rule(meta: &ParseNestedMeta) -> Result<Option<RenameRule>> {
+    let rule = match serialized_name(meta)? {
+        Some(rule) => rule,
+        None => return Ok(None),
+    };
+    RenameRule::from_str(&rule)
+        .map(Some)
+        .ok_or_else(|| meta.error(format!("unknown rename rule `{}`", rule)))
+}
+
+/// Consumes the value or arguments of an attribute which does not affect schemas.
+fn skip(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        content.parse::<proc_macro2::TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// The case conversions of serde's `rename_all`.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a `PascalCase` variant.
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            RenameRule::Snake
+            | RenameRule::ScreamingSnake
+            | RenameRule::Kebab
+            | RenameRule::ScreamingKebab => {
+                let mut snake = String::new();
+                for (index, ch) in variant.char_indices() {
+                    if index > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                self.apply_to_field(&snake)
+            }
+        }
+    }
+
+    /// Renames a `snake_case` field.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal | RenameRule::Camel => {
+                let mut renamed = String::new();
+                let mut capitalize = matches!(self, RenameRule::Pascal);
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        renamed.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        renamed.push(ch);
+                    }
+                }
+                renamed
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! `#[derive(ToSchema)]`, which describes the serialized form of a struct or enum as an
+//! [openapi](https://docs.rs/openapi) `v3_0::Schema`.
+//!
+//! The serde attributes `rename`, `rename_all`, `skip`, `skip_serializing`,
+//! `skip_serializing_if`, `default`, `flatten`, `transparent`, `tag`, `content` and
+//! `untagged` are honoured. Doc comments become descriptions.
+//!
+//! Types without generic parameters are registered in `components.schemas` under their
+//! (renamed) name and referred to with a `$ref`; generic types are inlined, so they may not
+//! refer to themselves.
+//!
+//! ```
+//! use openapi::v3_0::Components;
+//! use openapi_derive::ToSchema;
+//!
+//! /// A pet
+//! #[derive(ToSchema)]
+//! #[serde(rename_all = "camelCase")]
+//! struct Pet {
+//!     pet_id: u64,
+//!     #[serde(skip_serializing_if = "Option::is_none")]
+//!     tag: Option<String>,
+//! }
+//!
+//! let mut components = Components::default();
+//! let schema = components.add_schema::<Pet>();
+//! assert_eq!(schema.ref_path.as_deref(), Some("#/components/schemas/Pet"));
+//! ```
+
+extern crate proc_macro;
+
+mod attr;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Ident, Result,
+    Type,
+};
+
+#[proc_macro_derive(ToSchema, attributes(serde))]
+pub fn derive_to_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream> {
+    let container = attr::Container::parse(&input.attrs)?;
+    let mut schema = match &input.data {
+        Data::Struct(data) => struct_schema(&container, &data.fields)?,
+        Data::Enum(data) => enum_schema(&container, data.variants.iter())?,
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ToSchema cannot be derived for unions",
+            ))
+        }
+    };
+    if let Some(docs) = attr::docs(&input.attrs) {
+        schema = describe(schema, &docs);
+    }
+    let schema_name = if input.generics.params.is_empty() {
+        let name = container
+            .rename
+            .clone()
+            .unwrap_or_else(|| unraw(&input.ident));
+        quote!(::std::option::Option::Some(#name.to_string()))
+    } else {
+        quote!(::std::option::Option::None)
+    };
+    for param in &mut input.generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(::openapi::v3_0::ToSchema));
+        }
+    }
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::openapi::v3_0::ToSchema for #ident #type_generics #where_clause {
+            fn schema_name() -> ::std::option::Option<::std::string::String> {
+                #schema_name
+            }
+
+            fn schema(
+                components: &mut ::openapi::v3_0::Components,
+            ) -> ::openapi::v3_0::Schema {
+                #schema
+            }
+        }
+    })
+}
+
+/// An expression for the schema of the serialized fields of a struct or variant.
+fn struct_schema(container: &attr::Container, fields: &Fields) -> Result<TokenStream> {
+    match fields {
+        Fields::Named(named) => {
+            if container.transparent {
+                let field = named
+                    .named
+                    .iter()
+                    .find(|field| attr::Field::parse(&field.attrs).is_ok_and(|attrs| !attrs.skip))
+                    .ok_or_else(|| Error::new_spanned(named, "transparent struct has no field"))?;
+                return Ok(schema_ref(&field.ty));
+            }
+            object_schema(
+                container.rename_all,
+                container.default,
+                named
+                    .named
+                    .iter()
+                    .map(|field| (field.ident.as_ref().unwrap(), field)),
+            )
+        }
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            Ok(schema_ref(&unnamed.unnamed[0].ty))
+        }
+        Fields::Unnamed(unnamed) => {
+            let len = unnamed.unnamed.len() as u32;
+            Ok(quote! {
+                ::openapi::v3_0::Schema {
+                    schema_type: ::std::option::Option::Some("array".to_string()),
+                    min_items: ::std::option::Option::Some(#len),
+                    max_items: ::std::option::Option::Some(#len),
+                    ..::std::default::Default::default()
+                }
+            })
+        }
+        Fields::Unit => Ok(null()),
+    }
+}
+
+/// An expression for the schema of an object with named fields, none of them required if
+/// missing ones take their `default`.
+fn object_schema<'a>(
+    rename_all: Option<attr::RenameRule>,
+    default: bool,
+    fields: impl Iterator<Item = (&'a Ident, &'a syn::Field)>,
+) -> Result<TokenStream> {
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    let mut flattened = Vec::new();
+    for (ident, field) in fields {
+        let attrs = attr::Field::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        if attrs.flatten {
+            flattened.push(schema_ref(&field.ty));
+            continue;
+        }
+        let name = attrs.rename.unwrap_or_else(|| {
+            let name = unraw(ident);
+            match rename_all {
+                Some(rule) => rule.apply_to_field(&name),
+                None => name,
+            }
+        });
+        let mut schema = schema_ref(&field.ty);
+        if let Some(docs) = attr::docs(&field.attrs) {
+            schema = describe(schema, &docs);
+        }
+        if !default && !attrs.default && !attrs.skip_serializing_if && !is_option(&field.ty) {
+            required.push(name.clone());
+        }
+        properties.push(quote!(properties.insert(#name.to_string(), #schema);));
+    }
+    let required = if required.is_empty() {
+        quote!(::std::option::Option::None)
+    } else {
+        quote!(::std::option::Option::Some(
+            vec![#(#required.to_string()),*]
+        ))
+    };
+    let object = quote! {
+        {
+            #[allow(unused_mut)]
+            let mut properties = ::std::collections::BTreeMap::new();
+            #(#properties)*
+            ::openapi::v3_0::Schema {
+                schema_type: ::std::option::Option::Some("object".to_string()),
+                properties: ::std::option::Option::Some(properties),
+                required: #required,
+                ..::std::default::Default::default()
+            }
+        }
+    };
+    if flattened.is_empty() {
+        Ok(object)
+    } else {
+        Ok(all_of(std::iter::once(object).chain(flattened)))
+    }
+}
+
+/// An expression for the schema of an enum.
+fn enum_schema<'a>(
+    container: &attr::Container,
+    variants: impl Iterator<Item = &'a syn::Variant>,
+) -> Result<TokenStream> {
+    let mut schemas = Vec::new();
+    let mut names = Vec::new();
+    let mut all_unit = true;
+    for variant in variants {
+        let attrs = attr::Variant::parse(&variant.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let name = attrs.rename.clone().unwrap_or_else(|| {
+            let name = unraw(&variant.ident);
+            match container.rename_all {
+                Some(rule) => rule.apply_to_variant(&name),
+                None => name,
+            }
+        });
+        let content = match &variant.fields {
+            Fields::Unit => None,
+            Fields::Named(named) => Some(object_schema(
+                attrs.rename_all,
+                false,
+                named
+                    .named
+                    .iter()
+                    .map(|field| (field.ident.as_ref().unwrap(), field)),
+            )?),
+            fields => Some(struct_schema(&attr::Container::default(), fields)?),
+        };
+        all_unit &= content.is_none();
+        let schema = match (&container.tag, &container.content, container.untagged) {
+            (_, _, true) => content.unwrap_or_else(null),
+            (Some(tag), Some(content_name), false) => {
+                let mut properties = vec![(tag.clone(), string_enum(std::slice::from_ref(&name)))];
+                if let Some(content) = content {
+                    properties.push((content_name.clone(), content));
+                }
+                object(properties)
+            }
+            (Some(tag), None, false) => {
+                let tag_object = object(vec![(
+                    tag.clone(),
+                    string_enum(std::slice::from_ref(&name)),
+                )]);
+                match (&variant.fields, content) {
+                    (_, None) => tag_object,
+                    (Fields::Named(_), Some(content)) => all_of(vec![tag_object, content]),
+                    (Fields::Unnamed(unnamed), Some(content)) if unnamed.unnamed.len() == 1 => {
+                        all_of(vec![tag_object, content])
+                    }
+                    _ => {
+                        return Err(Error::new_spanned(
+                            variant,
+                            "internally tagged enums cannot hold tuple variants",
+                        ))
+                    }
+                }
+            }
+            (None, _, false) => match content {
+                None => string_enum(std::slice::from_ref(&name)),
+                Some(content) => object(vec![(name.clone(), content)]),
+            },
+        };
+        names.push(name);
+        schemas.push(schema);
+    }
+    if all_unit && container.tag.is_none() && !container.untagged {
+        return Ok(string_enum(&names));
+    }
+    Ok(quote! {
+        ::openapi::v3_0::Schema {
+            one_of: ::std::option::Option::Some(vec![
+                #(::openapi::v3_0::ObjectOrReference::Object(#schemas)),*
+            ]),
+            ..::std::default::Default::default()
+        }
+    })
+}
+
+/// An expression for the schema of `null`, the serialized form of units. OpenAPI 3.0 has no
+/// `null` type, so this is a `nullable` schema without a `type`, which accepts any value.
+fn null() -> TokenStream {
+    quote! {
+        ::openapi::v3_0::Schema {
+            nullable: ::std::option::Option::Some(true),
+            ..::std::default::Default::default()
+        }
+    }
+}
+
+/// An expression for the schema to refer to a type with.
+fn schema_ref(ty: &Type) -> TokenStream {
+    quote!(<#ty as ::openapi::v3_0::ToSchema>::schema_ref(components))
+}
+
+/// An expression for a string schema allowing only the given values.
+fn string_enum(values: &[String]) -> TokenStream {
+    quote! {
+        ::openapi::v3_0::Schema {
+            schema_type: ::std::option::Option::Some("string".to_string()),
+            enum_values: ::std::option::Option::Some(vec![#(#values.to_string()),*]),
+            ..::std::default::Default::default()
+        }
+    }
+}
+
+/// An expression for the schema of an object whose properties are all required.
+fn object(properties: Vec<(String, TokenStream)>) -> TokenStream {
+    let (names, schemas): (Vec<_>, Vec<_>) = properties.into_iter().unzip();
+    quote! {
+        {
+            let mut properties = ::std::collections::BTreeMap::new();
+            #(properties.insert(#names.to_string(), #schemas);)*
+            ::openapi::v3_0::Schema {
+                schema_type: ::std::option::Option::Some("object".to_string()),
+                properties: ::std::option::Option::Some(properties),
+                required: ::std::option::Option::Some(vec![#(#names.to_string()),*]),
+                ..::std::default::Default::default()
+            }
+        }
+    }
+}
+
+fn all_of(schemas: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let schemas = schemas.into_iter();
+    quote! {
+        ::openapi::v3_0::Schema {
+            all_of: ::std::option::Option::Some(vec![
+                #(::openapi::v3_0::ObjectOrReference::Object(#schemas)),*
+            ]),
+            ..::std::default::Default::default()
+        }
+    }
+}
+
+/// Adds a description to a schema, unless it is a `$ref`, which may not have siblings.
+fn describe(schema: TokenStream, docs: &str) -> TokenStream {
+    quote! {
+        {
+            let mut schema = #schema;
+            if schema.ref_path.is_none() {
+                schema.description = ::std::option::Option::Some(#docs.to_string());
+            }
+            schema
+        }
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// The name of an identifier, without the `r#` of raw identifiers.
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.strip_prefix("r#").map(String::from).unwrap_or(name)
+}
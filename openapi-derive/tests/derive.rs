@@ -0,0 +1,277 @@
+use openapi::v3_0::{validation::SchemaValidator, Components, ObjectOrReference};
+use openapi_derive::ToSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+fn registered<T: openapi::v3_0::ToSchema>() -> (Value, BTreeMap<String, Value>) {
+    let mut components = Components::default();
+    let schema = components.add_schema::<T>();
+    let schemas = components
+        .schemas
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, schema)| match schema {
+            ObjectOrReference::Object(schema) => (name, serde_json::to_value(schema).unwrap()),
+            other => panic!("unexpected schema {:?}", other),
+        })
+        .collect();
+    (serde_json::to_value(schema).unwrap(), schemas)
+}
+
+/// A pet
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct Pet {
+    pet_id: u64,
+    /// The name of the pet
+    #[serde(rename = "petName")]
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    #[serde(default)]
+    owners: Vec<Owner>,
+    #[serde(skip)]
+    secret: String,
+    #[serde(flatten)]
+    audit: Audit,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename = "PetOwner")]
+struct Owner {
+    r#type: Kind,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Audit {
+    created: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(dead_code)]
+enum Kind {
+    Person,
+    #[serde(rename = "org")]
+    Organization,
+    SoleTrader,
+}
+
+#[test]
+fn derives_struct_schemas() {
+    let (schema, schemas) = registered::<Pet>();
+    assert_eq!(schema, json!({"$ref": "#/components/schemas/Pet"}));
+    assert_eq!(
+        schemas["Pet"],
+        json!({
+            "description": "A pet",
+            "allOf": [
+                {
+                    "type": "object",
+                    "required": ["petId", "petName"],
+                    "properties": {
+                        "petId": {"type": "integer", "format": "int64"},
+                        "petName": {"type": "string", "description": "The name of the pet"},
+                        "tag": {"type": "string", "nullable": true},
+                        "owners": {
+                            "type": "array",
+                            "items": {"$ref": "#/components/schemas/PetOwner"}
+                        }
+                    }
+                },
+                {"$ref": "#/components/schemas/Audit"}
+            ]
+        })
+    );
+    assert_eq!(
+        schemas["PetOwner"]["properties"]["type"],
+        json!({"$ref": "#/components/schemas/Kind"})
+    );
+    assert_eq!(
+        schemas["Kind"],
+        json!({"type": "string", "enum": ["PERSON", "org", "SOLE_TRADER"]})
+    );
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Shape {
+    Circle { radius: f64 },
+    Square(Side),
+    Empty,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Side {
+    length: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "t", content = "c")]
+#[allow(dead_code)]
+enum Message {
+    Ping,
+    Text(String),
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum Id {
+    Number(u32),
+    Name(String),
+}
+
+#[test]
+fn derives_tagged_enum_schemas() {
+    let (_, schemas) = registered::<Shape>();
+    let kind = |name: &str| {
+        json!({
+            "type": "object",
+            "required": ["kind"],
+            "properties": {"kind": {"type": "string", "enum": [name]}}
+        })
+    };
+    assert_eq!(
+        schemas["Shape"],
+        json!({
+            "oneOf": [
+                {"allOf": [
+                    kind("circle"),
+                    {
+                        "type": "object",
+                        "required": ["radius"],
+                        "properties": {"radius": {"type": "number", "format": "double"}}
+                    }
+                ]},
+                {"allOf": [kind("square"), {"$ref": "#/components/schemas/Side"}]},
+                kind("empty")
+            ]
+        })
+    );
+
+    let (_, schemas) = registered::<Message>();
+    assert_eq!(
+        schemas["Message"]["oneOf"][1],
+        json!({
+            "type": "object",
+            "required": ["t", "c"],
+            "properties": {
+                "t": {"type": "string", "enum": ["Text"]},
+                "c": {"type": "string"}
+            }
+        })
+    );
+
+    let (_, schemas) = registered::<Id>();
+    assert_eq!(
+        schemas["Id"],
+        json!({
+            "oneOf": [
+                {"type": "integer", "format": "int64"},
+                {"type": "string"}
+            ]
+        })
+    );
+}
+
+#[derive(Serialize, ToSchema)]
+struct Page<T> {
+    items: Vec<T>,
+    total: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Tree {
+    children: Vec<Tree>,
+}
+
+#[test]
+fn inlines_generic_types_and_refers_to_recursive_ones() {
+    let mut components = Components::default();
+    let schema = serde_json::to_value(components.add_schema::<Page<Side>>()).unwrap();
+    assert_eq!(
+        schema["properties"]["items"]["items"],
+        json!({"$ref": "#/components/schemas/Side"})
+    );
+    assert_eq!(schema["required"], json!(["items"]));
+
+    let (_, schemas) = registered::<Tree>();
+    assert_eq!(
+        schemas["Tree"]["properties"]["children"]["items"],
+        json!({"$ref": "#/components/schemas/Tree"})
+    );
+}
+
+#[derive(Serialize, ToSchema)]
+struct Listing {
+    pet: Option<Owner>,
+    note: Option<String>,
+    unit: Unit,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Unit;
+
+#[test]
+fn describes_its_own_serialized_values() {
+    let mut components = Components::default();
+    let schema = components.add_schema::<Listing>();
+    let validator = SchemaValidator::new(Some(&components));
+    for listing in [
+        Listing {
+            pet: None,
+            note: None,
+            unit: Unit,
+        },
+        Listing {
+            pet: Some(Owner {
+                r#type: Kind::Person,
+            }),
+            note: Some("good".into()),
+            unit: Unit,
+        },
+    ] {
+        let value = serde_json::to_value(&listing).unwrap();
+        assert_eq!(
+            validator.validate(&schema, &value).unwrap(),
+            Vec::new(),
+            "{}",
+            value
+        );
+    }
+    assert_eq!(
+        serde_json::to_value(&components.schemas.unwrap()["Listing"]).unwrap()["properties"]["pet"],
+        json!({"nullable": true, "allOf": [{"$ref": "#/components/schemas/PetOwner"}]})
+    );
+}
+
+#[derive(Default, Serialize, ToSchema)]
+#[serde(default)]
+struct Settings {
+    verbose: bool,
+    level: u8,
+}
+
+// serde's own derive does not rename these, so the schema is derived alone
+#[derive(ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+enum Accent {
+    Élan,
+    Plain,
+}
+
+#[test]
+fn follows_container_defaults_and_renames_non_ascii_variants() {
+    let (_, schemas) = registered::<Settings>();
+    assert_eq!(schemas["Settings"].get("required"), None);
+    let (_, schemas) = registered::<Accent>();
+    assert_eq!(
+        schemas["Accent"],
+        json!({"type": "string", "enum": ["Élan", "plain"]})
+    );
+}
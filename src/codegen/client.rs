@@ -0,0 +1,510 @@
+//! A typed HTTP client for the operations of a spec.
+
+use crate::{
+    codegen::{
+        doc_comment,
+        models::Models,
+        operations::{signatures, Argument, Encoding, Signature, Status, FORM_HELPERS},
+    },
+    template::Part,
+    v3_0::Spec,
+    Result,
+};
+
+const CLIENT_ERROR: &str = r#"/// An error sending a request or reading its response.
+#[derive(Debug)]
+pub enum ClientError {
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Http(error) => write!(f, "HTTP error: {}", error),
+            ClientError::Json(error) => write!(f, "JSON error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Http(error) => Some(error),
+            ClientError::Json(error) => Some(error),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Json(error)
+    }
+}
+"#;
+
+const HELPERS: &str = r#"/// Serializes a parameter value in its `style`, as the text substituted for its template
+/// expression in a path, a query string fragment, a header value or a cookie.
+#[allow(dead_code)]
+fn encode_parameter<T: serde::Serialize + ?Sized>(
+    value: &T,
+    name: &str,
+    location: &str,
+    style: &str,
+    explode: bool,
+    allow_reserved: bool,
+) -> Result<String, serde_json::Error> {
+    let invalid = |reason: &str| {
+        <serde_json::Error as serde::ser::Error>::custom(format!(
+            "invalid value for parameter `{}`: {}",
+            name, reason
+        ))
+    };
+    let encode = |text: &str| match location {
+        "query" | "path" => percent_encode(text, location == "query" && allow_reserved),
+        _ => text.to_string(),
+    };
+    let scalar = |value: &serde_json::Value| match value {
+        serde_json::Value::Null => Ok(String::new()),
+        serde_json::Value::String(value) => Ok(encode(value)),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            Err(invalid("nested arrays and objects can not be serialized"))
+        }
+        value => Ok(encode(&value.to_string())),
+    };
+    let name = encode(name);
+    let pairs = if location == "cookie" { "; " } else { "&" };
+    let style = match style {
+        "spaceDelimited" | "pipeDelimited" if explode => "form",
+        style => style,
+    };
+    let pair = |(key, value): &(String, String)| format!("{}={}", key, value);
+    let flatten = |entries: &[(String, String)], delimiter: &str| {
+        entries
+            .iter()
+            .flat_map(|(key, value)| [key.as_str(), value.as_str()])
+            .collect::<Vec<_>>()
+            .join(delimiter)
+    };
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::Array(items) => {
+            let items = items.iter().map(scalar).collect::<Result<Vec<_>, _>>()?;
+            match (style, explode) {
+                ("matrix", true) => items
+                    .iter()
+                    .map(|item| format!(";{}={}", name, item))
+                    .collect(),
+                ("matrix", false) => format!(";{}={}", name, items.join(",")),
+                ("label", true) => format!(".{}", items.join(".")),
+                ("label", false) => format!(".{}", items.join(",")),
+                ("form", true) => items
+                    .iter()
+                    .map(|item| format!("{}={}", name, item))
+                    .collect::<Vec<_>>()
+                    .join(pairs),
+                ("form", false) => format!("{}={}", name, items.join(",")),
+                ("simple", _) => items.join(","),
+                ("spaceDelimited", _) => format!("{}={}", name, items.join("%20")),
+                ("pipeDelimited", _) => format!("{}={}", name, items.join("|")),
+                _ => return Err(invalid("`deepObject` style only applies to objects")),
+            }
+        }
+        serde_json::Value::Object(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| Ok((encode(key), scalar(value)?)))
+                .collect::<Result<Vec<_>, _>>()?;
+            match (style, explode) {
+                ("matrix", true) => entries
+                    .iter()
+                    .map(|entry| format!(";{}", pair(entry)))
+                    .collect(),
+                ("matrix", false) => format!(";{}={}", name, flatten(&entries, ",")),
+                ("label", true) => entries
+                    .iter()
+                    .map(|entry| format!(".{}", pair(entry)))
+                    .collect(),
+                ("label", false) => format!(".{}", flatten(&entries, ",")),
+                ("form", true) => entries.iter().map(pair).collect::<Vec<_>>().join(pairs),
+                ("form", false) => format!("{}={}", name, flatten(&entries, ",")),
+                ("simple", true) => entries.iter().map(pair).collect::<Vec<_>>().join(","),
+                ("simple", false) => flatten(&entries, ","),
+                ("spaceDelimited", _) => format!("{}={}", name, flatten(&entries, "%20")),
+                ("pipeDelimited", _) => format!("{}={}", name, flatten(&entries, "|")),
+                _ => entries
+                    .iter()
+                    .map(|(key, value)| format!("{}[{}]={}", name, key, value))
+                    .collect::<Vec<_>>()
+                    .join("&"),
+            }
+        }
+        value => {
+            let null = value.is_null();
+            let value = scalar(&value)?;
+            match style {
+                "matrix" if null => format!(";{}", name),
+                "matrix" => format!(";{}={}", name, value),
+                "label" => format!(".{}", value),
+                "form" => format!("{}={}", name, value),
+                "simple" => value,
+                "deepObject" => return Err(invalid("`deepObject` style only applies to objects")),
+                style => {
+                    return Err(invalid(&format!(
+                        "`{}` style only applies to arrays and objects",
+                        style
+                    )))
+                }
+            }
+        }
+    })
+}
+
+/// Percent-encodes everything but unreserved characters, and with `reserved` reserved
+/// characters too.
+#[allow(dead_code)]
+fn percent_encode(text: &str, reserved: bool) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric()
+            || b"-._~".contains(&byte)
+            || (reserved && b":/?#[]@!$&'()*+,;=".contains(&byte))
+        {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+"#;
+
+/// Rust source for an async client of the operations of a spec, built on `reqwest`.
+///
+/// The source defines the types of [`rust_models`](crate::codegen::rust_models), a
+/// response enum per operation with a variant per documented status, a `ClientError`,
+/// and a `Client` with one method per operation, named after its `operationId`. Path, query,
+/// header and cookie parameters become arguments, serialized according to their `style`,
+/// `explode` and `allowReserved`, followed by the request body if there is one. Generated
+/// code needs `reqwest` with its `json` feature.
+pub fn rust_client(spec: &Spec) -> Result<String> {
+    let mut models = Models::new(spec.components.as_ref(), &["Client", "ClientError"]);
+    models.define_all()?;
+    let signatures = signatures(spec, &mut models)?;
+    let mut items = vec![models.source()];
+    for signature in &signatures {
+        let extra = if signature
+            .outcomes
+            .iter()
+            .any(|outcome| outcome.status == Status::Default)
+        {
+            ""
+        } else {
+            "    /// A response the spec does not describe.\n    Unexpected(reqwest::Response),\n"
+        };
+        items.push(signature.response_enum("Debug", extra));
+    }
+    items.push(CLIENT_ERROR.into());
+
+    let mut client = String::new();
+    doc_comment(
+        &mut client,
+        "",
+        Some(&format!("A client for the {} API.", spec.info.title)),
+    );
+    client.push_str(
+        "#[derive(Clone, Debug)]\npub struct Client {\n    base_url: String,\n    \
+         http: reqwest::Client,\n}\n\nimpl Client {\n",
+    );
+    client.push_str(
+        "    /// A client for the API at `base_url`.\n    \
+         pub fn new(base_url: impl Into<String>) -> Self {\n        \
+         Self::with_http_client(base_url, reqwest::Client::new())\n    }\n\n    \
+         /// A client for the API at `base_url`, sending requests with `http`.\n    \
+         pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {\n        \
+         let base_url = base_url.into().trim_end_matches('/').to_string();\n        \
+         Client { base_url, http }\n    }\n",
+    );
+    for signature in &signatures {
+        client.push('\n');
+        method(&mut client, signature);
+    }
+    client.push_str("}\n");
+    items.push(client);
+    items.push(HELPERS.into());
+    items.push(FORM_HELPERS.into());
+    Ok(items
+        .into_iter()
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Writes the client method of an operation.
+fn method(out: &mut String, signature: &Signature) {
+    signature.doc_comment(out, "    ");
+    if signature.operation.deprecated == Some(true) {
+        out.push_str("    #[deprecated]\n");
+    }
+    let mut arguments = vec!["&self".to_string()];
+    for argument in &signature.arguments {
+        arguments.push(format!("{}: {}", argument.ident, argument.argument_type()));
+    }
+    let body_ident = signature.body_ident();
+    if let Some((body, required)) = &signature.body {
+        let ty = match body.encoding {
+            Encoding::Json | Encoding::Form => format!("&{}", body.ty),
+            Encoding::Text | Encoding::Bytes => body.ty.clone(),
+        };
+        arguments.push(format!(
+            "{}: {}",
+            body_ident,
+            if *required {
+                ty
+            } else {
+                format!("Option<{}>", ty)
+            }
+        ));
+    }
+    out.push_str(&format!(
+        "    pub async fn {}(\n        {},\n    ) -> Result<{}, ClientError> {{\n",
+        signature.ident,
+        arguments.join(",\n        "),
+        signature.response
+    ));
+
+    let mut format = "{}".to_string();
+    let mut format_arguments = vec!["self.base_url".to_string()];
+    for part in &signature.parts {
+        match part {
+            Part::Literal(literal) => format.push_str(literal),
+            Part::Variable(name) => {
+                let argument = signature
+                    .arguments
+                    .iter()
+                    .find(|argument| {
+                        argument.parameter.location == "path" && argument.parameter.name == *name
+                    })
+                    .expect("path parameters are resolved with the signature");
+                format.push_str("{}");
+                format_arguments.push(encode_parameter(argument));
+            }
+        }
+    }
+    let query = signature
+        .arguments
+        .iter()
+        .any(|argument| argument.parameter.location == "query");
+    out.push_str(&format!(
+        "        let {}url = format!({:?}, {});\n",
+        if query { "mut " } else { "" },
+        format,
+        format_arguments.join(", ")
+    ));
+    if query {
+        out.push_str("        let mut query = Vec::new();\n");
+        for argument in &signature.arguments {
+            if argument.parameter.location == "query" {
+                optionally(
+                    out,
+                    argument,
+                    &format!("query.push({});", encode_parameter(argument)),
+                );
+            }
+        }
+        out.push_str(
+            "        query.retain(|pair: &String| !pair.is_empty());\n        \
+             if !query.is_empty() {\n            url.push('?');\n            \
+             url.push_str(&query.join(\"&\"));\n        }\n",
+        );
+    }
+    out.push_str(&format!(
+        "        #[allow(unused_mut)]\n        let mut request = \
+         self.http.request(reqwest::Method::{}, url);\n",
+        signature.method.as_str().to_ascii_uppercase()
+    ));
+    let cookies = signature
+        .arguments
+        .iter()
+        .any(|argument| argument.parameter.location == "cookie");
+    if cookies {
+        out.push_str("        let mut cookies = Vec::new();\n");
+    }
+    for argument in &signature.arguments {
+        let statement = match argument.parameter.location.as_str() {
+            "header" => format!(
+                "request = request.header({:?}, {});",
+                argument.parameter.name,
+                encode_parameter(argument)
+            ),
+            "cookie" => format!("cookies.push({});", encode_parameter(argument)),
+            _ => continue,
+        };
+        optionally(out, argument, &statement);
+    }
+    if cookies {
+        out.push_str(
+            "        if !cookies.is_empty() {\n            \
+             request = request.header(\"Cookie\", cookies.join(\"; \"));\n        }\n",
+        );
+    }
+    if let Some((body, required)) = &signature.body {
+        let statement = match body.encoding {
+            Encoding::Json => format!("request = request.json({});", body_ident),
+            Encoding::Form => format!("request = request.form({});", body_ident),
+            Encoding::Text | Encoding::Bytes => format!(
+                "request = request.header(\"Content-Type\", {:?}).body({});",
+                body.media_type, body_ident
+            ),
+        };
+        if *required {
+            out.push_str(&format!("        {}\n", statement));
+        } else {
+            out.push_str(&format!(
+                "        if let Some({ident}) = {ident} {{\n            {}\n        }}\n",
+                statement,
+                ident = body_ident
+            ));
+        }
+    }
+    out.push_str(
+        "        let response = request.send().await?;\n        \
+         let status = response.status().as_u16();\n        Ok(match status {\n",
+    );
+    let mut fallback = false;
+    for outcome in &signature.outcomes {
+        let body = outcome.body.as_ref().map(|body| match body.encoding {
+            Encoding::Json => "response.json().await?",
+            Encoding::Form => "from_form(&response.bytes().await?)?",
+            Encoding::Text => "response.text().await?",
+            Encoding::Bytes => "response.bytes().await?.to_vec()",
+        });
+        let value = match (body, outcome.has_status()) {
+            (None, false) => outcome.variant.clone(),
+            (Some(body), false) => format!("{}({})", outcome.variant, body),
+            (None, true) => format!("{} {{ status }}", outcome.variant),
+            (Some(body), true) => format!("{} {{ status, body: {} }}", outcome.variant, body),
+        };
+        fallback |= outcome.status == Status::Default;
+        out.push_str(&format!(
+            "            {} => {}::{},\n",
+            outcome.status.pattern(),
+            signature.response,
+            value
+        ));
+    }
+    if !fallback {
+        out.push_str(&format!(
+            "            _ => {}::Unexpected(response),\n",
+            signature.response
+        ));
+    }
+    out.push_str("        })\n    }\n");
+}
+
+/// The expression serializing the value of an argument's parameter.
+fn encode_parameter(argument: &Argument) -> String {
+    let parameter = argument.parameter;
+    format!(
+        "encode_parameter(&{}, {:?}, {:?}, {:?}, {}, {})?",
+        argument.ident,
+        parameter.name,
+        parameter.location,
+        parameter
+            .effective_style()
+            .map(|style| style.to_string())
+            .unwrap_or_default(),
+        parameter.effective_explode(),
+        parameter.effective_allow_reserved()
+    )
+}
+
+/// Writes `statement`, run only if the argument has a value if it is optional.
+fn optionally(out: &mut String, argument: &Argument, statement: &str) {
+    if argument.required {
+        out.push_str(&format!("        {}\n", statement));
+    } else {
+        out.push_str(&format!(
+            "        if let Some({ident}) = {ident} {{\n            {}\n        }}\n",
+            statement,
+            ident = argument.ident
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn petstore() -> String {
+        match from_path("data/v3.0/petstore.yaml").unwrap() {
+            OpenApi::V3_0(spec) => rust_client(&spec).unwrap(),
+            other => panic!("unexpected spec {:?}", other),
+        }
+    }
+
+    #[test]
+    fn generates_response_enums() {
+        let source = petstore();
+        assert!(source.contains(
+            "pub enum ShowPetByIdResponse {\n    /// Expected response to a valid request\n    \
+             Ok(Pets),\n    /// unexpected error\n    Default { status: u16, body: Error },\n}\n"
+        ));
+        assert!(source.contains("pub enum CreatePetsResponse {\n"));
+        assert!(source.contains("    Created,\n"));
+    }
+
+    #[test]
+    fn generates_a_method_per_operation() {
+        let source = petstore();
+        assert!(source.contains(
+            "    pub async fn list_pets(\n        &self,\n        limit: Option<i32>,\n    ) \
+             -> Result<ListPetsResponse, ClientError> {\n        \
+             let mut url = format!(\"{}/pets\", self.base_url);\n"
+        ));
+        assert!(source.contains(
+            "        if let Some(limit) = limit {\n            \
+             query.push(encode_parameter(&limit, \"limit\", \"query\", \"form\", true, false)?);\n        \
+             }\n"
+        ));
+        assert!(source.contains(
+            "let url = format!(\"{}/pets/{}\", self.base_url, \
+             encode_parameter(&pet_id, \"petId\", \"path\", \"simple\", false, false)?);\n"
+        ));
+        assert!(source.contains(
+            "            200 => ShowPetByIdResponse::Ok(response.json().await?),\n            \
+             _ => ShowPetByIdResponse::Default { status, body: response.json().await? },\n"
+        ));
+    }
+
+    #[test]
+    fn names_arguments_clear_of_locals() {
+        let spec: Spec = serde_yaml::from_str(
+            "openapi: 3.0.1
+info: {title: Search, version: '1'}
+paths:
+  /search:
+    get:
+      operationId: search
+      parameters:
+        - {name: query, in: query, required: true, schema: {type: string}}
+        - {name: url, in: query, schema: {type: string}}
+      responses:
+        '204': {description: Searched}
+",
+        )
+        .unwrap();
+        let source = rust_client(&spec).unwrap();
+        assert!(source.contains("        query2: &str,\n        url2: Option<&str>,\n"));
+        assert!(source.contains(
+            "        query.push(encode_parameter(&query2, \"query\", \"query\", \"form\", true, \
+             false)?);\n        if let Some(url2) = url2 {\n"
+        ));
+    }
+}
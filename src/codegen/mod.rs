@@ -0,0 +1,144 @@
+//! Generation of Rust source code from specs, meant to be called from build scripts.
+//!
+//! Generated code refers to `serde` and `serde_json` by their crate names, so crates
+//! including it need both as dependencies.
+//!
+//! ```no_run
+//! // build.rs
+//! use openapi::{codegen, OpenApi};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let source = match openapi::from_path("petstore.yaml")? {
+//!         OpenApi::V2(spec) => codegen::rust_models_v2(&spec)?,
+//!         OpenApi::V3_0(spec) => codegen::rust_models(&spec)?,
+//!     };
+//!     let out_dir = std::env::var("OUT_DIR")?;
+//!     std::fs::write(format!("{}/models.rs", out_dir), source)?;
+//!     Ok(())
+//! }
+//! ```
+
+mod client;
+mod models;
+mod operations;
+mod server;
+
+pub use crate::codegen::{
+    client::rust_client,
+    models::{rust_models, rust_models_v2},
+    server::rust_server,
+};
+
+use std::collections::BTreeSet;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// A `PascalCase` identifier for a type or variant named `name`.
+pub(crate) fn type_name(name: &str) -> String {
+    let mut ident = String::new();
+    let mut capitalize = true;
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            ident.push(if capitalize {
+                ch.to_ascii_uppercase()
+            } else {
+                ch
+            });
+            capitalize = false;
+        } else {
+            capitalize = true;
+        }
+    }
+    if ident.is_empty() || ident.starts_with(|ch: char| ch.is_ascii_digit()) || ident == "Self" {
+        ident.insert_str(0, "Value");
+    }
+    ident
+}
+
+/// A `snake_case` identifier for a field, argument or method named `name`.
+pub(crate) fn field_name(name: &str) -> String {
+    let mut ident = String::new();
+    let mut previous = None;
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            let boundary = ch.is_ascii_uppercase()
+                && previous.is_some_and(|previous: char| {
+                    previous.is_ascii_lowercase() || previous.is_ascii_digit()
+                });
+            if boundary && !ident.ends_with('_') {
+                ident.push('_');
+            }
+            ident.push(ch.to_ascii_lowercase());
+            previous = Some(ch);
+        } else {
+            if !ident.is_empty() && !ident.ends_with('_') {
+                ident.push('_');
+            }
+            previous = None;
+        }
+    }
+    let mut ident = ident.trim_end_matches('_').to_string();
+    if ident.is_empty() {
+        ident.push_str("value");
+    } else if ident.starts_with(|ch: char| ch.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    match ident.as_str() {
+        "crate" | "self" | "super" => ident + "_",
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+/// `ident`, or `ident` with the first free numeric suffix if it is already in `used`.
+pub(crate) fn unique(ident: String, used: &mut BTreeSet<String>) -> String {
+    let mut candidate = ident.clone();
+    let mut suffix = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{}{}", ident, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Writes `description` as a doc comment indented by `indent`.
+pub(crate) fn doc_comment(out: &mut String, indent: &str, description: Option<&str>) {
+    for line in description
+        .iter()
+        .flat_map(|description| description.trim().lines())
+    {
+        let line = line.trim_end();
+        out.push_str(indent);
+        out.push_str("///");
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_identifiers() {
+        assert_eq!(type_name("io.k8s.api.core.v1.Pod"), "IoK8sApiCoreV1Pod");
+        assert_eq!(type_name("pet_owner"), "PetOwner");
+        assert_eq!(type_name("404"), "Value404");
+        assert_eq!(field_name("apiVersion"), "api_version");
+        assert_eq!(field_name("$ref"), "r#ref");
+        assert_eq!(field_name("x-rate-limit"), "x_rate_limit");
+        assert_eq!(field_name("self"), "self_");
+        let mut used = BTreeSet::new();
+        assert_eq!(unique("id".into(), &mut used), "id");
+        assert_eq!(unique("id".into(), &mut used), "id2");
+    }
+}
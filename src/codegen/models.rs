@@ -0,0 +1,690 @@
+//! Serde-compatible Rust types for the schemas of a spec.
+
+use crate::{
+    codegen::{doc_comment, field_name, type_name, unique},
+    pointer, v2,
+    v3_0::{self, BooleanObjectOrReference, Components, ObjectOrReference, Schema},
+    Error, Result,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+/// Names std types which generated code refers to unqualified.
+const PRELUDE: &[&str] = &[
+    "Box", "Err", "None", "Ok", "Option", "Result", "Some", "String", "Vec",
+];
+
+/// Rust source defining a struct, enum or type alias for every schema of
+/// `components.schemas`.
+///
+/// Required properties become plain fields and the others `Option`s, left out when `None`;
+/// nullable required properties are `Option`s too, serialized as `null` when `None`. `allOf`
+/// compositions flatten their parts into one struct, `oneOf` and `anyOf` become untagged
+/// enums, deserialized as the variant their `discriminator` property names if there is
+/// one, and `additionalProperties` become maps. References which make a type contain
+/// itself are boxed.
+pub fn rust_models(spec: &v3_0::Spec) -> Result<String> {
+    let mut models = Models::new(spec.components.as_ref(), &[]);
+    models.define_all()?;
+    Ok(models.source())
+}
+
+/// Rust source defining a struct, enum or type alias for every schema of `definitions`.
+///
+/// See [`rust_models`].
+pub fn rust_models_v2(spec: &v2::Spec) -> Result<String> {
+    let components = Components {
+        schemas: spec.definitions.as_ref().map(|definitions| {
+            definitions
+                .iter()
+                .map(|(name, schema)| (name.clone(), ObjectOrReference::Object(from_v2(schema))))
+                .collect()
+        }),
+        ..Components::default()
+    };
+    let mut models = Models::new(Some(&components), &[]);
+    models.define_all()?;
+    Ok(models.source())
+}
+
+/// The v3 equivalent of a v2 schema, as far as types are concerned.
+fn from_v2(schema: &v2::Schema) -> Schema {
+    Schema {
+        ref_path: schema
+            .ref_path
+            .as_ref()
+            .map(|reference| reference.replacen("#/definitions/", SCHEMAS, 1)),
+        description: schema.description.clone(),
+        schema_type: schema.schema_type.clone(),
+        format: schema.format.clone(),
+        enum_values: schema.enum_values.clone(),
+        required: schema.required.clone(),
+        items: schema.items.as_ref().map(|items| Box::new(from_v2(items))),
+        properties: schema.properties.as_ref().map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| (name.clone(), from_v2(property)))
+                .collect()
+        }),
+        all_of: schema.all_of.as_ref().map(|parts| {
+            parts
+                .iter()
+                .map(|part| ObjectOrReference::Object(from_v2(part)))
+                .collect()
+        }),
+        additional_properties: schema
+            .other
+            .get("additionalProperties")
+            .and_then(|additional| match additional {
+                serde_json::Value::Bool(allowed) => {
+                    Some(BooleanObjectOrReference::Boolean(*allowed))
+                }
+                additional => serde_json::from_value::<v2::Schema>(additional.clone())
+                    .ok()
+                    .map(|additional| {
+                        BooleanObjectOrReference::Object(Box::new(from_v2(&additional)))
+                    }),
+            }),
+        ..Schema::default()
+    }
+}
+
+/// A schema, whether inline or referenced.
+fn inline(schema: &ObjectOrReference<Schema>) -> Cow<'_, Schema> {
+    match schema {
+        ObjectOrReference::Object(schema) => Cow::Borrowed(schema),
+        ObjectOrReference::Ref { ref_path } => Cow::Owned(Schema {
+            ref_path: Some(ref_path.clone()),
+            ..Schema::default()
+        }),
+    }
+}
+
+/// The name of the component a reference points to.
+fn component_name(reference: &str) -> Option<String> {
+    reference
+        .strip_prefix(SCHEMAS)
+        .map(|name| pointer::unescape(name).into_owned())
+}
+
+/// The components a value of a schema contains without indirection.
+fn direct_references(schema: &Schema, references: &mut Vec<String>) {
+    references.extend(schema.ref_path.as_deref().and_then(component_name));
+    for property in schema.properties.iter().flat_map(BTreeMap::values) {
+        direct_references(property, references);
+    }
+    let parts = schema
+        .all_of
+        .iter()
+        .chain(&schema.one_of)
+        .chain(&schema.any_of);
+    for part in parts.flatten() {
+        direct_references(&inline(part), references);
+    }
+}
+
+fn is_string_enum(schema: &Schema) -> bool {
+    schema.enum_values.is_some() && matches!(schema.schema_type.as_deref(), None | Some("string"))
+}
+
+fn is_union(schema: &Schema) -> bool {
+    schema.one_of.is_some() || schema.any_of.is_some()
+}
+
+fn is_struct(schema: &Schema) -> bool {
+    schema.properties.is_some() || schema.all_of.is_some()
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    /// Whether the field may be missing, and is left out when `None`.
+    optional: bool,
+    /// Whether the field may be `null`, and is kept when `None`.
+    nullable: bool,
+    flatten: bool,
+    description: Option<String>,
+}
+
+/// The types generated for the schemas of a spec.
+pub(crate) struct Models {
+    schemas: BTreeMap<String, Schema>,
+    /// The type name of each component.
+    names: BTreeMap<String, String>,
+    used: BTreeSet<String>,
+    items: Vec<String>,
+}
+
+impl Models {
+    /// Types for the schemas of `components`, which keep clear of the `reserved` names.
+    pub(crate) fn new(components: Option<&Components>, reserved: &[&str]) -> Self {
+        let schemas: BTreeMap<_, _> = components
+            .and_then(|components| components.schemas.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|(name, schema)| (name.clone(), inline(schema).into_owned()))
+            .collect();
+        let mut used = PRELUDE
+            .iter()
+            .chain(reserved)
+            .map(|name| name.to_string())
+            .collect();
+        let names = schemas
+            .keys()
+            .map(|name| (name.clone(), unique(type_name(name), &mut used)))
+            .collect();
+        Models {
+            schemas,
+            names,
+            used,
+            items: Vec::new(),
+        }
+    }
+
+    /// Defines a type for every component.
+    pub(crate) fn define_all(&mut self) -> Result<()> {
+        for (name, schema) in self.schemas.clone() {
+            let ident = self.names[&name].clone();
+            self.define(&ident, &schema, Some(&name))?;
+        }
+        Ok(())
+    }
+
+    /// The generated items, separated by blank lines.
+    pub(crate) fn source(&self) -> String {
+        self.items.join("\n")
+    }
+
+    /// A type name based on `name` which no other generated type has.
+    pub(crate) fn fresh_name(&mut self, name: &str) -> String {
+        unique(type_name(name), &mut self.used)
+    }
+
+    /// The Rust type for values of `schema`, defining a new type named after `context` if
+    /// the schema needs one.
+    ///
+    /// `root` is the component whose definition the type is part of, if the type is
+    /// contained without indirection, so references back to it are boxed.
+    pub(crate) fn rust_type(
+        &mut self,
+        schema: &Schema,
+        root: Option<&str>,
+        context: &str,
+    ) -> Result<String> {
+        if let Some(reference) = &schema.ref_path {
+            let (component, ident) = self.referenced(reference)?;
+            return Ok(if root.is_some_and(|root| self.reaches(&component, root)) {
+                format!("Box<{}>", ident)
+            } else {
+                ident
+            });
+        }
+        if is_string_enum(schema) || is_union(schema) || is_struct(schema) {
+            let ident = self.fresh_name(context);
+            self.define(&ident, schema, root)?;
+            return Ok(ident);
+        }
+        Ok(match schema.schema_type.as_deref() {
+            Some("string") => "String".into(),
+            Some("integer") => match schema.format.as_deref() {
+                Some("int32") => "i32".into(),
+                _ => "i64".into(),
+            },
+            Some("number") => match schema.format.as_deref() {
+                Some("float") => "f32".into(),
+                _ => "f64".into(),
+            },
+            Some("boolean") => "bool".into(),
+            Some("array") => match &schema.items {
+                Some(items) => format!(
+                    "Vec<{}>",
+                    self.rust_type(items, None, &format!("{}Item", context))?
+                ),
+                None => "Vec<serde_json::Value>".into(),
+            },
+            Some("object") => self
+                .map_type(schema, context)?
+                .unwrap_or_else(|| "std::collections::BTreeMap<String, serde_json::Value>".into()),
+            _ => match self.map_type(schema, context)? {
+                Some(map) => map,
+                None => "serde_json::Value".into(),
+            },
+        })
+    }
+
+    /// The map type for the `additionalProperties` of `schema`, if they are allowed.
+    fn map_type(&mut self, schema: &Schema, context: &str) -> Result<Option<String>> {
+        let value = match &schema.additional_properties {
+            Some(BooleanObjectOrReference::Object(additional)) => {
+                self.rust_type(additional, None, &format!("{}Value", context))?
+            }
+            Some(BooleanObjectOrReference::Ref { ref_path }) => self.referenced(ref_path)?.1,
+            Some(BooleanObjectOrReference::Boolean(true)) => "serde_json::Value".into(),
+            Some(BooleanObjectOrReference::Boolean(false)) | None => return Ok(None),
+        };
+        Ok(Some(format!(
+            "std::collections::BTreeMap<String, {}>",
+            value
+        )))
+    }
+
+    /// The name and type name of the component `reference` points to.
+    fn referenced(&self, reference: &str) -> Result<(String, String)> {
+        component_name(reference)
+            .and_then(|component| {
+                let ident = self.names.get(&component)?.clone();
+                Some((component, ident))
+            })
+            .ok_or_else(|| Error::UnresolvedReference(reference.into()))
+    }
+
+    /// The schema `schema` refers to, following references to components.
+    pub(crate) fn resolve<'s>(&'s self, schema: &'s Schema) -> Result<&'s Schema> {
+        let mut schema = schema;
+        let mut visited = BTreeSet::new();
+        while let Some(reference) = &schema.ref_path {
+            let (component, _) = self.referenced(reference)?;
+            if !visited.insert(component.clone()) {
+                return Err(Error::UnresolvedReference(reference.clone()));
+            }
+            schema = &self.schemas[&component];
+        }
+        Ok(schema)
+    }
+
+    /// Whether values of the component `from` contain a value of `to` without indirection.
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![from.to_string()];
+        while let Some(component) = pending.pop() {
+            if component == to {
+                return true;
+            }
+            if let Some(schema) = self.schemas.get(&component) {
+                if visited.insert(component) {
+                    direct_references(schema, &mut pending);
+                }
+            }
+        }
+        false
+    }
+
+    /// Defines a type named `ident` for values of `schema`.
+    fn define(&mut self, ident: &str, schema: &Schema, root: Option<&str>) -> Result<()> {
+        // reserving the slot keeps types ahead of the types nested in them
+        let slot = self.items.len();
+        self.items.push(String::new());
+        let mut item = String::new();
+        doc_comment(&mut item, "", schema.description.as_deref());
+        if schema.ref_path.is_some() {
+            let target = self.rust_type(schema, None, ident)?;
+            item.push_str(&format!("pub type {} = {};\n", ident, target));
+        } else if is_string_enum(schema) {
+            self.string_enum(&mut item, ident, schema);
+        } else if is_union(schema) {
+            self.union(&mut item, ident, schema, root)?;
+        } else if is_struct(schema) {
+            self.structure(&mut item, ident, schema, root)?;
+        } else {
+            let target = self.rust_type(schema, root, ident)?;
+            item.push_str(&format!("pub type {} = {};\n", ident, target));
+        }
+        self.items[slot] = item;
+        Ok(())
+    }
+
+    fn string_enum(&mut self, item: &mut String, ident: &str, schema: &Schema) {
+        item.push_str(
+            "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]\n",
+        );
+        item.push_str(&format!("pub enum {} {{\n", ident));
+        let mut variants = BTreeSet::new();
+        for value in schema.enum_values.iter().flatten() {
+            let variant = unique(type_name(value), &mut variants);
+            if &variant != value {
+                item.push_str(&format!("    #[serde(rename = {:?})]\n", value));
+            }
+            item.push_str(&format!("    {},\n", variant));
+        }
+        item.push_str("}\n");
+    }
+
+    fn union(
+        &mut self,
+        item: &mut String,
+        ident: &str,
+        schema: &Schema,
+        root: Option<&str>,
+    ) -> Result<()> {
+        let mut variants = BTreeSet::new();
+        let mut members = Vec::new();
+        for (index, member) in schema
+            .one_of
+            .iter()
+            .chain(&schema.any_of)
+            .flatten()
+            .enumerate()
+        {
+            let member = inline(member);
+            let component = member.ref_path.as_deref().and_then(component_name);
+            let variant = match (&component, &member.title) {
+                (Some(component), _) => self.names.get(component).cloned(),
+                (None, Some(title)) => Some(type_name(title)),
+                (None, None) => member.schema_type.as_deref().map(type_name),
+            };
+            let variant = unique(
+                variant.unwrap_or_else(|| format!("Variant{}", index)),
+                &mut variants,
+            );
+            let ty = self.rust_type(&member, root, &format!("{}{}", ident, variant))?;
+            let tags = match &schema.discriminator {
+                Some(discriminator) => {
+                    let reference = member.ref_path.as_deref();
+                    let mut tags = discriminator
+                        .mapping
+                        .iter()
+                        .flatten()
+                        .filter(|(_, target)| {
+                            Some(target.as_str()) == reference
+                                || Some(target.as_str()) == component.as_deref()
+                        })
+                        .map(|(tag, _)| tag.clone())
+                        .collect::<Vec<_>>();
+                    if tags.is_empty() {
+                        tags.push(component.clone().unwrap_or_else(|| variant.clone()));
+                    }
+                    tags
+                }
+                None => Vec::new(),
+            };
+            members.push((member, variant, ty, tags));
+        }
+
+        // the variants keep the discriminator property, so serde's internal tagging, which
+        // takes it away from them, does not fit: values are serialized as the variant's and
+        // deserialized as the variant the property names
+        match &schema.discriminator {
+            Some(_) => item.push_str(
+                "#[derive(Clone, Debug, PartialEq, serde::Serialize)]\n#[serde(untagged)]\n",
+            ),
+            None => item.push_str(
+                "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]\n\
+                 #[serde(untagged)]\n",
+            ),
+        }
+        item.push_str(&format!("pub enum {} {{\n", ident));
+        for (member, variant, ty, _) in &members {
+            doc_comment(item, "    ", member.description.as_deref());
+            item.push_str(&format!("    {}({}),\n", variant, ty));
+        }
+        item.push_str("}\n");
+
+        if let Some(discriminator) = &schema.discriminator {
+            let tags = members
+                .iter()
+                .flat_map(|(_, _, _, tags)| tags)
+                .map(|tag| format!("{:?}", tag))
+                .collect::<Vec<_>>();
+            item.push_str(&format!(
+                "\nimpl<'de> serde::Deserialize<'de> for {ident} {{\n    \
+                 fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{\n        \
+                 use serde::de::Error as _;\n        \
+                 let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;\n        \
+                 match value.get({property:?}).and_then(serde_json::Value::as_str) {{\n",
+                ident = ident,
+                property = discriminator.property_name,
+            ));
+            for (_, variant, _, tags) in &members {
+                let patterns = tags
+                    .iter()
+                    .map(|tag| format!("Some({:?})", tag))
+                    .collect::<Vec<_>>();
+                item.push_str(&format!(
+                    "            {} => serde_json::from_value(value).map({}::{}),\n",
+                    patterns.join(" | "),
+                    ident,
+                    variant
+                ));
+            }
+            item.push_str(&format!(
+                "            Some(tag) => return Err(D::Error::unknown_variant(tag, &[{}])),\n            \
+                 None => return Err(D::Error::missing_field({:?})),\n        \
+                 }}\n        .map_err(D::Error::custom)\n    }}\n}}\n",
+                tags.join(", "),
+                discriminator.property_name
+            ));
+        }
+        Ok(())
+    }
+
+    fn structure(
+        &mut self,
+        item: &mut String,
+        ident: &str,
+        schema: &Schema,
+        root: Option<&str>,
+    ) -> Result<()> {
+        let mut fields = Vec::new();
+        self.fields(ident, schema, root, &BTreeSet::new(), &mut fields)?;
+        if schema.properties.is_some() {
+            if let Some(map) = self.map_type(schema, &format!("{}Additional", ident))? {
+                fields.push(Field {
+                    name: "additional_properties".into(),
+                    ty: map,
+                    optional: false,
+                    nullable: false,
+                    flatten: true,
+                    description: None,
+                });
+            }
+        }
+        item.push_str("#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]\n");
+        item.push_str(&format!("pub struct {} {{\n", ident));
+        let mut used = BTreeSet::new();
+        for field in fields {
+            let field_ident = unique(field_name(&field.name), &mut used);
+            doc_comment(item, "    ", field.description.as_deref());
+            let mut attributes = Vec::new();
+            if field.flatten {
+                attributes.push("flatten".to_string());
+            } else if field_ident.trim_start_matches("r#") != field.name {
+                attributes.push(format!("rename = {:?}", field.name));
+            }
+            let ty = if field.optional {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".into());
+                format!("Option<{}>", field.ty)
+            } else if field.nullable {
+                format!("Option<{}>", field.ty)
+            } else {
+                field.ty
+            };
+            if !attributes.is_empty() {
+                item.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+            }
+            item.push_str(&format!("    pub {}: {},\n", field_ident, ty));
+        }
+        item.push_str("}\n");
+        Ok(())
+    }
+
+    /// Collects the fields of the properties of `schema` and of its `allOf` parts.
+    fn fields(
+        &mut self,
+        ident: &str,
+        schema: &Schema,
+        root: Option<&str>,
+        required: &BTreeSet<String>,
+        fields: &mut Vec<Field>,
+    ) -> Result<()> {
+        let mut required = required.clone();
+        required.extend(schema.required.iter().flatten().cloned());
+        for (name, property) in schema.properties.iter().flatten() {
+            let ty = self.rust_type(property, root, &format!("{}{}", ident, type_name(name)))?;
+            fields.push(Field {
+                name: name.clone(),
+                ty,
+                optional: !required.contains(name),
+                nullable: property.nullable == Some(true),
+                flatten: false,
+                description: property.description.clone(),
+            });
+        }
+        for part in schema.all_of.iter().flatten() {
+            let part = inline(part);
+            if let Some(reference) = &part.ref_path {
+                let (_, part_ident) = self.referenced(reference)?;
+                fields.push(Field {
+                    name: part_ident.clone(),
+                    ty: self.rust_type(&part, root, ident)?,
+                    optional: false,
+                    nullable: false,
+                    flatten: true,
+                    description: part.description.clone(),
+                });
+            } else if is_struct(&part) {
+                self.fields(ident, &part, root, &required, fields)?;
+            } else if is_union(&part) {
+                let ty = self.rust_type(&part, root, &format!("{}Variant", ident))?;
+                fields.push(Field {
+                    name: ty.clone(),
+                    ty,
+                    optional: false,
+                    nullable: false,
+                    flatten: true,
+                    description: part.description.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn v3(yaml: &str) -> v3_0::Spec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn generates_structs_for_objects() {
+        let source = match from_path("data/v3.0/petstore.yaml").unwrap() {
+            OpenApi::V3_0(spec) => rust_models(&spec).unwrap(),
+            other => panic!("unexpected spec {:?}", other),
+        };
+        assert!(source.contains(
+            "pub struct Pet {\n    pub id: i64,\n    pub name: String,\n    \
+             #[serde(skip_serializing_if = \"Option::is_none\")]\n    \
+             pub tag: Option<String>,\n}\n"
+        ));
+        assert!(source.contains("pub type Pets = Vec<Pet>;\n"));
+    }
+
+    #[test]
+    fn generates_enums_compositions_and_boxes() {
+        let spec = v3(r#"
+openapi: 3.0.1
+info: {title: Shapes, version: "1"}
+paths: {}
+components:
+  schemas:
+    Kind:
+      type: string
+      enum: [circle, square-ish]
+    Circle:
+      allOf:
+        - $ref: '#/components/schemas/Base'
+        - type: object
+          required: [radius]
+          properties:
+            radius: {type: number}
+    Base:
+      type: object
+      properties:
+        labels:
+          type: object
+          additionalProperties: {type: string}
+    Shape:
+      oneOf:
+        - $ref: '#/components/schemas/Circle'
+        - $ref: '#/components/schemas/Base'
+      discriminator:
+        propertyName: kind
+        mapping:
+          round: '#/components/schemas/Circle'
+    Node:
+      type: object
+      required: [value]
+      properties:
+        value: {type: integer, format: int32}
+        next: {$ref: '#/components/schemas/Node'}
+        children:
+          type: array
+          items: {$ref: '#/components/schemas/Node'}
+"#);
+        let source = rust_models(&spec).unwrap();
+        assert!(source.contains(
+            "pub enum Kind {\n    #[serde(rename = \"circle\")]\n    Circle,\n    #[serde(rename = \"square-ish\")]\n    SquareIsh,\n}\n"
+        ));
+        assert!(source.contains(
+            "pub struct Circle {\n    #[serde(flatten)]\n    pub base: Base,\n    \
+             pub radius: f64,\n}\n"
+        ));
+        assert!(
+            source.contains("pub labels: Option<std::collections::BTreeMap<String, String>>,\n")
+        );
+        assert!(source.contains(
+            "#[serde(untagged)]\npub enum Shape {\n    Circle(Circle),\n    Base(Base),\n}\n"
+        ));
+        assert!(source.contains(
+            "            Some(\"round\") => serde_json::from_value(value).map(Shape::Circle),\n            \
+             Some(\"Base\") => serde_json::from_value(value).map(Shape::Base),\n            \
+             Some(tag) => return Err(D::Error::unknown_variant(tag, &[\"round\", \"Base\"])),\n            \
+             None => return Err(D::Error::missing_field(\"kind\")),\n"
+        ));
+        assert!(source.contains("pub next: Option<Box<Node>>,\n"));
+        assert!(source.contains("pub children: Option<Vec<Node>>,\n"));
+    }
+
+    #[test]
+    fn generates_v2_definitions() {
+        let source = match from_path("data/v2/k8s.json").unwrap() {
+            OpenApi::V2(spec) => rust_models_v2(&spec).unwrap(),
+            other => panic!("unexpected spec {:?}", other),
+        };
+        assert!(source.contains("pub struct IoK8sApimachineryPkgApisMetaV1ObjectMeta {\n"));
+        assert!(source.contains(
+            "    #[serde(rename = \"apiVersion\", skip_serializing_if = \"Option::is_none\")]\n    \
+             pub api_version: Option<String>,\n"
+        ));
+        assert!(source
+            .contains("pub annotations: Option<std::collections::BTreeMap<String, String>>,\n"));
+    }
+
+    #[test]
+    fn fails_on_unresolved_references() {
+        let spec = v3(r#"
+openapi: 3.0.1
+info: {title: Broken, version: "1"}
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner: {$ref: '#/components/schemas/Owner'}
+"#);
+        match rust_models(&spec) {
+            Err(Error::UnresolvedReference(reference)) => {
+                assert_eq!(reference, "#/components/schemas/Owner")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! The Rust signatures of the operations of a spec, shared by the client and server
+//! generators.
+
+use crate::{
+    codegen::{doc_comment, field_name, models::Models, unique},
+    template::{self, Part},
+    v3_0::{MediaType, ObjectOrReference, Operation, Parameter, Schema, Spec},
+    Error, Method, Result,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The locals and helpers generated methods use, which arguments are named clear of.
+const RESERVED: &[&str] = &[
+    "cookies",
+    "encode_parameter",
+    "from_form",
+    "query",
+    "request",
+    "response",
+    "status",
+    "url",
+];
+
+/// Helpers of generated code which decode forms and query strings.
+pub(crate) const FORM_HELPERS: &str = r#"/// Percent-decodes `input`, and with `plus` also decodes `+` as a space.
+#[allow(dead_code)]
+fn decode(input: &[u8], plus: bool) -> String {
+    let mut bytes = Vec::new();
+    let mut index = 0;
+    while index < input.len() {
+        let escaped = input
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (input[index], escaped) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                index += 3;
+                continue;
+            }
+            (b'+', _) if plus => bytes.push(b' '),
+            (byte, _) => bytes.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The decoded pairs of a query string or form.
+#[allow(dead_code)]
+fn form_pairs(input: &[u8]) -> Vec<(String, String)> {
+    input
+        .split(|byte| *byte == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut split = pair.splitn(2, |byte| *byte == b'=');
+            let key = decode(split.next().unwrap_or_default(), true);
+            let value = decode(split.next().unwrap_or_default(), true);
+            (key, value)
+        })
+        .collect()
+}
+
+/// Deserializes strings as such if possible, and otherwise as JSON.
+#[allow(dead_code)]
+fn from_strings<T: serde::de::DeserializeOwned>(
+    value: impl Fn(&dyn Fn(&str) -> serde_json::Value) -> serde_json::Value,
+) -> Result<T, serde_json::Error> {
+    serde_json::from_value(value(&|item| serde_json::Value::String(item.into()))).or_else(|_| {
+        serde_json::from_value(value(&|item| {
+            serde_json::from_str(item).unwrap_or_else(|_| serde_json::Value::String(item.into()))
+        }))
+    })
+}
+
+/// Deserializes the pairs of a form as the properties of an object.
+#[allow(dead_code)]
+fn from_form<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, serde_json::Error> {
+    let pairs = form_pairs(body);
+    from_strings(|item| {
+        serde_json::Value::Object(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), item(value)))
+                .collect(),
+        )
+    })
+}
+"#;
+
+/// How a body is encoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Encoding {
+    Json,
+    Form,
+    Text,
+    Bytes,
+}
+
+impl Encoding {
+    fn of(media_type: &str) -> Self {
+        if media_type == "application/json" || media_type.ends_with("+json") {
+            Encoding::Json
+        } else if media_type == "application/x-www-form-urlencoded" {
+            Encoding::Form
+        } else if media_type.starts_with("text/") {
+            Encoding::Text
+        } else {
+            Encoding::Bytes
+        }
+    }
+}
+
+/// A body of a request or response.
+pub(crate) struct Body {
+    pub media_type: String,
+    pub encoding: Encoding,
+    /// The Rust type of the body.
+    pub ty: String,
+}
+
+/// A parameter of an operation.
+pub(crate) struct Argument<'a> {
+    pub parameter: &'a Parameter,
+    pub ident: String,
+    /// The Rust type of the parameter's value.
+    pub ty: String,
+    pub required: bool,
+}
+
+impl Argument<'_> {
+    /// The type of the argument holding the parameter's value: strings and slices are
+    /// borrowed, numbers and booleans passed by value and other types by reference.
+    pub fn argument_type(&self) -> String {
+        let ty = match self.ty.as_str() {
+            "String" => "&str".to_string(),
+            "bool" | "i32" | "i64" | "f32" | "f64" => self.ty.clone(),
+            ty => match ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+                Some(item) => format!("&[{}]", item),
+                None => format!("&{}", ty),
+            },
+        };
+        if self.required {
+            ty
+        } else {
+            format!("Option<{}>", ty)
+        }
+    }
+}
+
+/// Which statuses a response applies to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Status {
+    Code(u16),
+    /// Any status with the given first digit, as in `2XX`.
+    Range(u16),
+    Default,
+}
+
+impl Status {
+    fn parse(status: &str) -> Result<Self> {
+        let unsupported = || Error::UnsupportedStatusCode(status.into());
+        if status == "default" {
+            return Ok(Status::Default);
+        }
+        if status.len() != 3 {
+            return Err(unsupported());
+        }
+        if status[1..].eq_ignore_ascii_case("XX") {
+            return status[..1]
+                .parse()
+                .ok()
+                .filter(|class| (1..=5).contains(class))
+                .map(Status::Range)
+                .ok_or_else(unsupported);
+        }
+        status
+            .parse()
+            .ok()
+            .filter(|code| (100..=599).contains(code))
+            .map(Status::Code)
+            .ok_or_else(unsupported)
+    }
+
+    /// A pattern matching the statuses.
+    pub fn pattern(self) -> String {
+        match self {
+            Status::Code(code) => code.to_string(),
+            Status::Range(class) => format!("{}..={}", class * 100, class * 100 + 99),
+            Status::Default => "_".into(),
+        }
+    }
+}
+
+/// A documented response of an operation, which becomes a variant of its response enum.
+pub(crate) struct Outcome {
+    pub status: Status,
+    pub variant: String,
+    pub description: Option<String>,
+    pub body: Option<Body>,
+}
+
+impl Outcome {
+    /// Whether the variant records the status, which it does unless the status is fixed.
+    pub fn has_status(&self) -> bool {
+        matches!(self.status, Status::Range(_) | Status::Default)
+    }
+}
+
+/// An operation, with the Rust types of its inputs and outputs.
+pub(crate) struct Signature<'a> {
+    pub method: Method,
+    pub path: &'a str,
+    pub operation: &'a Operation,
+    pub ident: String,
+    /// The template of the path.
+    pub parts: Vec<Part<'a>>,
+    pub arguments: Vec<Argument<'a>>,
+    pub body: Option<(Body, bool)>,
+    pub response: String,
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Signature<'_> {
+    /// Documents the operation with its summary and description.
+    pub fn doc_comment(&self, out: &mut String, indent: &str) {
+        let docs = [&self.operation.summary, &self.operation.description]
+            .iter()
+            .filter_map(|docs| docs.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        doc_comment(out, indent, Some(&docs));
+    }
+
+    /// The name of the body argument, which no parameter has.
+    pub fn body_ident(&self) -> String {
+        let mut ident = "body".to_string();
+        while self
+            .arguments
+            .iter()
+            .any(|argument| argument.ident == ident)
+        {
+            ident.insert(0, '_');
+        }
+        ident
+    }
+
+    /// The `pub enum` of the responses of the operation, with `extra` variants.
+    pub fn response_enum(&self, derives: &str, extra: &str) -> String {
+        let mut item = String::new();
+        doc_comment(
+            &mut item,
+            "",
+            Some(&format!("The responses of `{}`.", self.ident)),
+        );
+        item.push_str(&format!("#[derive({})]\n", derives));
+        item.push_str(&format!("pub enum {} {{\n", self.response));
+        for outcome in &self.outcomes {
+            doc_comment(&mut item, "    ", outcome.description.as_deref());
+            let variant = match (&outcome.body, outcome.has_status()) {
+                (None, false) => outcome.variant.clone(),
+                (Some(body), false) => format!("{}({})", outcome.variant, body.ty),
+                (None, true) => format!("{} {{ status: u16 }}", outcome.variant),
+                (Some(body), true) => {
+                    format!("{} {{ status: u16, body: {} }}", outcome.variant, body.ty)
+                }
+            };
+            item.push_str(&format!("    {},\n", variant));
+        }
+        item.push_str(extra);
+        item.push_str("}\n");
+        item
+    }
+}
+
+/// The signatures of the operations of `spec`, defining the types they need in `models`.
+pub(crate) fn signatures<'a>(spec: &'a Spec, models: &mut Models) -> Result<Vec<Signature<'a>>> {
+    let mut idents = BTreeSet::new();
+    let mut signatures = Vec::new();
+    for (path, path_item) in &spec.paths {
+        for (method, operation) in path_item.operations() {
+            let ident = unique(
+                field_name(
+                    match &operation.operation_id {
+                        Some(operation_id) => operation_id.clone(),
+                        None => format!("{} {}", method.as_str(), path),
+                    }
+                    .as_str(),
+                ),
+                &mut idents,
+            );
+            let context = ident.trim_start_matches("r#").to_string();
+            let parts =
+                template::parse(path).ok_or_else(|| Error::MalformedTemplate(path.clone()))?;
+            let parameters = spec.effective_parameters(path_item, operation)?;
+            let mut arguments = Vec::new();
+            let mut used = RESERVED.iter().map(|name| name.to_string()).collect();
+            for name in template::variables(&parts) {
+                let parameter = parameters
+                    .iter()
+                    .find(|parameter| parameter.location == "path" && parameter.name == name)
+                    .ok_or_else(|| Error::UndeclaredPathParameter {
+                        method,
+                        path: path.clone(),
+                        name: name.into(),
+                    })?;
+                arguments.push(argument(models, &context, parameter, &mut used)?);
+            }
+            for parameter in &parameters {
+                if parameter.location != "path" {
+                    arguments.push(argument(models, &context, parameter, &mut used)?);
+                }
+            }
+            let body = match &operation.request_body {
+                Some(request_body) => {
+                    let request_body = spec.resolve_request_body(request_body)?;
+                    preferred_body(models, &format!("{} body", context), &request_body.content)?
+                        .map(|body| (body, request_body.required == Some(true)))
+                }
+                None => None,
+            };
+            let response = models.fresh_name(&format!("{} response", context));
+            let mut outcomes = Vec::new();
+            let mut variants = BTreeSet::new();
+            for (status, response_object) in &operation.responses {
+                let status = Status::parse(status)?;
+                let variant = unique(status_variant(status), &mut variants);
+                let body = preferred_body(
+                    models,
+                    &format!("{} {}", response, variant),
+                    response_object.content.as_ref().unwrap_or(&BTreeMap::new()),
+                )?;
+                outcomes.push(Outcome {
+                    status,
+                    variant,
+                    description: response_object.description.clone(),
+                    body,
+                });
+            }
+            outcomes.sort_by_key(|outcome| match outcome.status {
+                Status::Code(_) => 0,
+                Status::Range(_) => 1,
+                Status::Default => 2,
+            });
+            signatures.push(Signature {
+                method,
+                path,
+                operation,
+                ident,
+                parts,
+                arguments,
+                body,
+                response,
+                outcomes,
+            });
+        }
+    }
+    Ok(signatures)
+}
+
+fn argument<'a>(
+    models: &mut Models,
+    context: &str,
+    parameter: &'a Parameter,
+    used: &mut BTreeSet<String>,
+) -> Result<Argument<'a>> {
+    let schema = parameter.schema.clone().unwrap_or_else(|| Schema {
+        schema_type: parameter.param_type.clone(),
+        format: parameter.format.clone(),
+        ..Schema::default()
+    });
+    if let Some(style) = parameter.effective_style() {
+        if !style.supports(&parameter.location) {
+            return Err(Error::UnsupportedParameterStyle {
+                style,
+                location: parameter.location.clone(),
+            });
+        }
+    }
+    let ty = models.rust_type(&schema, None, &format!("{} {}", context, parameter.name))?;
+    Ok(Argument {
+        parameter,
+        ident: unique(field_name(&parameter.name), used),
+        ty,
+        required: parameter.required == Some(true) || parameter.location == "path",
+    })
+}
+
+/// The body of the preferred media type of `content`: JSON if possible, then forms, then
+/// text, then anything else.
+fn preferred_body(
+    models: &mut Models,
+    context: &str,
+    content: &BTreeMap<String, MediaType>,
+) -> Result<Option<Body>> {
+    let preferred = [
+        Encoding::Json,
+        Encoding::Form,
+        Encoding::Text,
+        Encoding::Bytes,
+    ]
+    .iter()
+    .find_map(|encoding| {
+        content
+            .iter()
+            .find(|(media_type, _)| Encoding::of(media_type) == *encoding)
+    });
+    let (media_type, media) = match preferred {
+        Some(preferred) => preferred,
+        None => return Ok(None),
+    };
+    let encoding = Encoding::of(media_type);
+    let ty = match encoding {
+        Encoding::Json | Encoding::Form => match &media.schema {
+            Some(ObjectOrReference::Object(schema)) => models.rust_type(schema, None, context)?,
+            Some(ObjectOrReference::Ref { ref_path }) => models.rust_type(
+                &Schema {
+                    ref_path: Some(ref_path.clone()),
+                    ..Schema::default()
+                },
+                None,
+                context,
+            )?,
+            None => "serde_json::Value".into(),
+        },
+        Encoding::Text => "String".into(),
+        Encoding::Bytes => "Vec<u8>".into(),
+    };
+    Ok(Some(Body {
+        media_type: media_type.clone(),
+        encoding,
+        ty,
+    }))
+}
+
+/// The variant of a response enum for a status.
+fn status_variant(status: Status) -> String {
+    let code = match status {
+        Status::Code(code) => code,
+        Status::Range(class) => return format!("Status{}XX", class),
+        Status::Default => return "Default".into(),
+    };
+    match code {
+        200 => "Ok",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "NoContent",
+        301 => "MovedPermanently",
+        302 => "Found",
+        304 => "NotModified",
+        400 => "BadRequest",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "NotFound",
+        405 => "MethodNotAllowed",
+        409 => "Conflict",
+        410 => "Gone",
+        412 => "PreconditionFailed",
+        415 => "UnsupportedMediaType",
+        422 => "UnprocessableEntity",
+        429 => "TooManyRequests",
+        500 => "InternalServerError",
+        501 => "NotImplemented",
+        502 => "BadGateway",
+        503 => "ServiceUnavailable",
+        504 => "GatewayTimeout",
+        code => return format!("Status{}", code),
+    }
+    .into()
+}
@@ -0,0 +1,678 @@
+//! A server trait for the operations of a spec, with routing and (de)serialization.
+
+use crate::{
+    codegen::{
+        doc_comment,
+        models::Models,
+        operations::{signatures, Encoding, Signature, FORM_HELPERS},
+    },
+    v3_0::{Parameter, Spec},
+    Result,
+};
+
+const HELPERS: &str = r#"/// The still encoded values of the variables of `template` in `path`, if it matches.
+#[allow(dead_code)]
+fn match_template(template: &str, path: &str) -> Option<Vec<String>> {
+    let templates = template.split('/').collect::<Vec<_>>();
+    let segments = path.split('/').collect::<Vec<_>>();
+    if templates.len() != segments.len() {
+        return None;
+    }
+    let mut values = Vec::new();
+    for (template, segment) in templates.into_iter().zip(segments) {
+        let mut pieces = template.split('{');
+        let mut rest = segment.strip_prefix(pieces.next().unwrap_or_default())?;
+        let pieces = pieces.collect::<Vec<_>>();
+        for (index, piece) in pieces.iter().enumerate() {
+            let (_, literal) = piece.split_once('}')?;
+            let end = match (literal.is_empty(), index + 1 == pieces.len()) {
+                (true, true) => rest.len(),
+                (true, false) => return None,
+                (false, _) => rest.find(literal)?,
+            };
+            if end == 0 {
+                return None;
+            }
+            values.push(rest[..end].to_string());
+            rest = &rest[end + literal.len()..];
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(values)
+}
+
+/// The values of a header, joined by `separator`, or `None` if it is missing.
+#[allow(dead_code)]
+fn header(parts: &http::request::Parts, name: &str, separator: &str) -> Option<String> {
+    let values = parts
+        .headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(separator))
+    }
+}
+
+/// The strings the value of a parameter is made of.
+#[allow(dead_code)]
+enum Strings {
+    One(String),
+    Many(Vec<String>),
+    Map(Vec<(String, String)>),
+}
+
+/// Splits the value of a parameter serialized in its `style` into its strings. `raw` is the
+/// text matched in the path, the query string, the header's values or the `Cookie` header,
+/// and `kind` is `array`, `object` or `primitive`. `None` if the parameter is missing.
+#[allow(dead_code)]
+fn parameter(
+    raw: Option<&str>,
+    name: &str,
+    location: &str,
+    style: &str,
+    explode: bool,
+    kind: &str,
+) -> Result<Option<Strings>, String> {
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
+    let invalid = |reason: String| format!("invalid parameter `{}`: {}", name, reason);
+    let text = |text: &str| match location {
+        "path" | "query" => decode(text.as_bytes(), location == "query"),
+        _ => text.to_string(),
+    };
+    let entry = |entry: &str| {
+        let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+        (text(key), text(value))
+    };
+    let split = |raw: &str, delimiter: &str| -> Vec<String> {
+        if raw.is_empty() {
+            return Vec::new();
+        }
+        // delimiters may also arrive percent-encoded
+        let delimiters = match delimiter {
+            " " => vec![" ", "+", "%20"],
+            "|" => vec!["|", "%7C", "%7c"],
+            delimiter => vec![delimiter],
+        };
+        delimiters
+            .into_iter()
+            .fold(vec![raw.to_string()], |values, delimiter| {
+                values
+                    .iter()
+                    .flat_map(|value| value.split(delimiter))
+                    .map(String::from)
+                    .collect()
+            })
+    };
+    let delimited = |raw: &str, delimiter: &str, explode: bool| -> Result<Strings, String> {
+        Ok(match kind {
+            "array" => Strings::Many(split(raw, delimiter).iter().map(|item| text(item)).collect()),
+            "object" if explode => {
+                Strings::Map(split(raw, delimiter).iter().map(|item| entry(item)).collect())
+            }
+            "object" => {
+                let items = split(raw, delimiter)
+                    .iter()
+                    .map(|item| text(item))
+                    .collect::<Vec<_>>();
+                if items.len() % 2 != 0 {
+                    return Err(invalid(format!(
+                        "expected `{}` to hold pairs of keys and values",
+                        raw
+                    )));
+                }
+                Strings::Map(
+                    items
+                        .chunks(2)
+                        .map(|pair| (pair[0].clone(), pair[1].clone()))
+                        .collect(),
+                )
+            }
+            _ => Strings::One(text(raw)),
+        })
+    };
+    let pairs = || {
+        raw.split(if location == "cookie" { ';' } else { '&' })
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .collect::<Vec<_>>()
+    };
+    // exploded delimited values are written as repeated pairs, as with `form`
+    let style = match style {
+        "spaceDelimited" | "pipeDelimited" if explode => "form",
+        style => style,
+    };
+    match style {
+        "simple" => delimited(raw, ",", explode).map(Some),
+        "label" => match raw.strip_prefix('.') {
+            Some(raw) => delimited(raw, if explode { "." } else { "," }, explode).map(Some),
+            None => Err(invalid(format!("expected `{}` to start with `.`", raw))),
+        },
+        "matrix" => {
+            let raw = raw
+                .strip_prefix(';')
+                .ok_or_else(|| invalid(format!("expected `{}` to start with `;`", raw)))?;
+            if explode && kind != "primitive" {
+                let entries = raw.split(';').map(entry).collect::<Vec<_>>();
+                return Ok(Some(if kind == "array" {
+                    Strings::Many(
+                        entries
+                            .into_iter()
+                            .filter(|(key, _)| key == name)
+                            .map(|(_, value)| value)
+                            .collect(),
+                    )
+                } else {
+                    Strings::Map(entries)
+                }));
+            }
+            let (key, value) = raw.split_once('=').unwrap_or((raw, ""));
+            if text(key) != name {
+                return Ok(None);
+            }
+            delimited(value, ",", false).map(Some)
+        }
+        "form" if explode => {
+            let pairs = pairs()
+                .into_iter()
+                .map(|(key, value)| (text(key), text(value)));
+            if kind == "object" {
+                let entries = pairs.collect::<Vec<_>>();
+                return Ok(if entries.is_empty() {
+                    None
+                } else {
+                    Some(Strings::Map(entries))
+                });
+            }
+            let mut values = pairs
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            Ok(match (values.is_empty(), kind) {
+                (true, _) => None,
+                (false, "array") => Some(Strings::Many(values)),
+                (false, _) => Some(Strings::One(values.remove(0))),
+            })
+        }
+        "form" | "spaceDelimited" | "pipeDelimited" => {
+            let delimiter = match style {
+                "spaceDelimited" => " ",
+                "pipeDelimited" => "|",
+                _ => ",",
+            };
+            match pairs().into_iter().find(|(key, _)| text(key) == name) {
+                Some((_, value)) => delimited(value, delimiter, false).map(Some),
+                None => Ok(None),
+            }
+        }
+        _ => {
+            let prefix = format!("{}[", name);
+            let entries = pairs()
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    let key = text(key);
+                    let key = key.strip_prefix(&prefix)?.strip_suffix(']')?.to_string();
+                    Some((key, text(value)))
+                })
+                .collect::<Vec<_>>();
+            Ok(if entries.is_empty() {
+                None
+            } else {
+                Some(Strings::Map(entries))
+            })
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn required<T: serde::de::DeserializeOwned>(
+    name: &str,
+    strings: Result<Option<Strings>, String>,
+) -> Result<T, String> {
+    optional(name, strings)?.ok_or_else(|| format!("missing parameter `{}`", name))
+}
+
+#[allow(dead_code)]
+fn optional<T: serde::de::DeserializeOwned>(
+    name: &str,
+    strings: Result<Option<Strings>, String>,
+) -> Result<Option<T>, String> {
+    let strings = match strings? {
+        Some(strings) => strings,
+        None => return Ok(None),
+    };
+    from_strings(|item| match &strings {
+        Strings::One(value) => item(value),
+        Strings::Many(values) => {
+            serde_json::Value::Array(values.iter().map(|value| item(value)).collect())
+        }
+        Strings::Map(entries) => serde_json::Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), item(value)))
+                .collect(),
+        ),
+    })
+    .map(Some)
+    .map_err(|error| format!("invalid parameter `{}`: {}", name, error))
+}
+
+#[allow(dead_code)]
+fn json_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(body).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn form_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, String> {
+    from_form(body).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn text_body(body: &[u8]) -> Result<String, String> {
+    String::from_utf8(body.to_vec()).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn respond(
+    status: u16,
+    content_type: Option<&'static str>,
+    body: Vec<u8>,
+) -> http::Response<Vec<u8>> {
+    let mut response = http::Response::new(body);
+    *response.status_mut() =
+        http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = content_type {
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(content_type),
+        );
+    }
+    response
+}
+
+#[allow(dead_code)]
+fn form_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match to_form(body) {
+        Ok(body) => respond(status, Some(content_type), body.into_bytes()),
+        Err(error) => respond(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+
+/// Serializes the properties of an object as the pairs of a form, writing values which
+/// are not strings as JSON and leaving out `null`s.
+#[allow(dead_code)]
+fn to_form<T: serde::Serialize>(body: &T) -> Result<String, serde_json::Error> {
+    let entries = match serde_json::to_value(body)? {
+        serde_json::Value::Object(entries) => entries,
+        _ => {
+            return Err(<serde_json::Error as serde::ser::Error>::custom(
+                "only objects can be serialized as forms",
+            ))
+        }
+    };
+    let encode = |text: &str| {
+        let mut encoded = String::new();
+        for byte in text.bytes() {
+            match byte {
+                b' ' => encoded.push('+'),
+                byte if byte.is_ascii_alphanumeric() || b"*-._".contains(&byte) => {
+                    encoded.push(byte as char)
+                }
+                byte => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    };
+    Ok(entries
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            format!("{}={}", encode(key), encode(&value))
+        })
+        .collect::<Vec<_>>()
+        .join("&"))
+}
+
+#[allow(dead_code)]
+fn json_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match serde_json::to_vec(body) {
+        Ok(body) => respond(status, Some(content_type), body),
+        Err(error) => respond(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+"#;
+
+/// Rust source for implementing the operations of a spec as a server.
+///
+/// The source defines the types of [`rust_models`](crate::codegen::rust_models), a request
+/// struct and a response enum per operation, an `Api` trait with a method per operation
+/// taking its request and returning its response, and a `dispatch` function. `dispatch`
+/// routes an `http::Request` to the operation its path template and method match,
+/// extracting the parameters, decoded according to their `style` and `explode`, and the
+/// body, and turns the response into an `http::Response`. Generated code needs the
+/// `http`, `serde` and `serde_json` crates.
+pub fn rust_server(spec: &Spec) -> Result<String> {
+    let mut models = Models::new(spec.components.as_ref(), &["Api"]);
+    models.define_all()?;
+    let signatures = signatures(spec, &mut models)?;
+    let requests = signatures
+        .iter()
+        .map(|signature| {
+            models.fresh_name(&format!(
+                "{} request",
+                signature.ident.trim_start_matches("r#")
+            ))
+        })
+        .collect::<Vec<_>>();
+    let mut items = vec![models.source()];
+    for (signature, request) in signatures.iter().zip(&requests) {
+        items.push(request_struct(&models, signature, request)?);
+        items.push(signature.response_enum("Clone, Debug, PartialEq", ""));
+        items.push(response_conversion(signature));
+    }
+
+    let mut api = String::new();
+    doc_comment(
+        &mut api,
+        "",
+        Some(&format!("The operations of the {} API.", spec.info.title)),
+    );
+    api.push_str("pub trait Api {\n");
+    for (index, (signature, request)) in signatures.iter().zip(&requests).enumerate() {
+        if index > 0 {
+            api.push('\n');
+        }
+        signature.doc_comment(&mut api, "    ");
+        api.push_str(&format!(
+            "    fn {}(\n        &self,\n        request: {},\n    ) -> impl std::future::Future<Output = {}> + Send;\n",
+            signature.ident, request, signature.response
+        ));
+    }
+    api.push_str("}\n");
+    items.push(api);
+    items.push(dispatch(&signatures, &requests));
+    items.push(HELPERS.into());
+    items.push(FORM_HELPERS.into());
+    Ok(items
+        .into_iter()
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// The request struct of an operation, with a `parse` function extracting it from a request.
+fn request_struct(models: &Models, signature: &Signature, request: &str) -> Result<String> {
+    let mut item = String::new();
+    doc_comment(
+        &mut item,
+        "",
+        Some(&format!(
+            "The parameters and body of `{}`.",
+            signature.ident
+        )),
+    );
+    item.push_str("#[derive(Clone, Debug, PartialEq)]\n");
+    item.push_str(&format!("pub struct {} {{\n", request));
+    let mut parse = String::new();
+    let mut path_index = 0;
+    for argument in &signature.arguments {
+        doc_comment(&mut item, "    ", argument.parameter.description.as_deref());
+        let ty = if argument.required {
+            argument.ty.clone()
+        } else {
+            format!("Option<{}>", argument.ty)
+        };
+        item.push_str(&format!("    pub {}: {},\n", argument.ident, ty));
+        let parameter = argument.parameter;
+        let raw = match parameter.location.as_str() {
+            "path" => {
+                path_index += 1;
+                format!("Some(&path_values[{}])", path_index - 1)
+            }
+            "query" => "Some(parts.uri.query().unwrap_or_default())".into(),
+            "header" => format!("header(parts, {:?}, \",\").as_deref()", parameter.name),
+            _ => "header(parts, \"cookie\", \"; \").as_deref()".into(),
+        };
+        parse.push_str(&format!(
+            "            {}: {}(\n                {:?},\n                \
+             parameter({}, {:?}, {:?}, {:?}, {}, {:?}),\n            )?,\n",
+            argument.ident,
+            if argument.required {
+                "required"
+            } else {
+                "optional"
+            },
+            parameter.name,
+            raw,
+            parameter.name,
+            parameter.location,
+            parameter
+                .effective_style()
+                .map(|style| style.to_string())
+                .unwrap_or_default(),
+            parameter.effective_explode(),
+            kind(models, parameter)?
+        ));
+    }
+    if let Some((body, required)) = &signature.body {
+        let ident = signature.body_ident();
+        item.push_str(&format!(
+            "    pub {}: {},\n",
+            ident,
+            if *required {
+                body.ty.clone()
+            } else {
+                format!("Option<{}>", body.ty)
+            }
+        ));
+        let decode = match body.encoding {
+            Encoding::Json => "json_body(body)?",
+            Encoding::Form => "form_body(body)?",
+            Encoding::Text => "text_body(body)?",
+            Encoding::Bytes => "body.to_vec()",
+        };
+        let decode = if *required {
+            decode.to_string()
+        } else {
+            format!("if body.is_empty() {{ None }} else {{ Some({}) }}", decode)
+        };
+        parse.push_str(&format!("            {}: {},\n", ident, decode));
+    }
+    item.push_str("}\n\n");
+    item.push_str(&format!(
+        "impl {} {{\n    #[allow(unused_variables)]\n    fn parse(\n        \
+         parts: &http::request::Parts,\n        path_values: &[String],\n        \
+         body: &[u8],\n    ) -> Result<Self, String> {{\n        Ok({} {{\n{}        }})\n    }}\n}}\n",
+        request, request, parse
+    ));
+    Ok(item)
+}
+
+/// Whether the value of a parameter is an `array`, an `object` or a `primitive`.
+fn kind(models: &Models, parameter: &Parameter) -> Result<&'static str> {
+    let schema = match &parameter.schema {
+        Some(schema) => models.resolve(schema)?,
+        None => return Ok("primitive"),
+    };
+    Ok(match schema.schema_type.as_deref() {
+        Some("array") => "array",
+        Some("object") => "object",
+        _ if schema.properties.is_some() || schema.additional_properties.is_some() => "object",
+        _ => "primitive",
+    })
+}
+
+/// The conversion of the response enum of an operation to an `http::Response`.
+fn response_conversion(signature: &Signature) -> String {
+    let mut item = format!(
+        "impl From<{response}> for http::Response<Vec<u8>> {{\n    \
+         fn from(response: {response}) -> Self {{\n        match response {{\n",
+        response = signature.response
+    );
+    for outcome in &signature.outcomes {
+        let (pattern, status) = match (&outcome.body, outcome.has_status()) {
+            (None, false) => (outcome.variant.clone(), outcome.status.pattern()),
+            (Some(_), false) => (
+                format!("{}(body)", outcome.variant),
+                outcome.status.pattern(),
+            ),
+            (None, true) => (format!("{} {{ status }}", outcome.variant), "status".into()),
+            (Some(_), true) => (
+                format!("{} {{ status, body }}", outcome.variant),
+                "status".into(),
+            ),
+        };
+        let response = match &outcome.body {
+            None => format!("respond({}, None, Vec::new())", status),
+            Some(body) => match body.encoding {
+                Encoding::Json => {
+                    format!("json_response({}, {:?}, &body)", status, body.media_type)
+                }
+                Encoding::Text => format!(
+                    "respond({}, Some({:?}), body.into_bytes())",
+                    status, body.media_type
+                ),
+                Encoding::Form => {
+                    format!("form_response({}, {:?}, &body)", status, body.media_type)
+                }
+                Encoding::Bytes => {
+                    format!("respond({}, Some({:?}), body)", status, body.media_type)
+                }
+            },
+        };
+        item.push_str(&format!(
+            "            {}::{} => {},\n",
+            signature.response, pattern, response
+        ));
+    }
+    item.push_str("        }\n    }\n}\n");
+    item
+}
+
+/// The `dispatch` function, which tries paths with literal segments before templated ones.
+fn dispatch(signatures: &[Signature], requests: &[String]) -> String {
+    let mut routes: Vec<(&str, Vec<(&Signature, &String)>)> = Vec::new();
+    for (signature, request) in signatures.iter().zip(requests) {
+        match routes.iter_mut().find(|(path, _)| *path == signature.path) {
+            Some((_, operations)) => operations.push((signature, request)),
+            None => routes.push((signature.path, vec![(signature, request)])),
+        }
+    }
+    routes.sort_by_key(|(path, _)| {
+        path.split('/')
+            .map(|segment| segment.contains('{'))
+            .collect::<Vec<_>>()
+    });
+    let mut item = String::from(
+        "/// Routes a request to the operation of `api` whose path and method it matches.\n\
+         ///\n\
+         /// Responds `404 Not Found` if no path matches, `405 Method Not Allowed` if no method\n\
+         /// does, and `400 Bad Request` if the parameters or body are invalid.\n\
+         pub async fn dispatch<A: Api>(\n    api: &A,\n    request: http::Request<Vec<u8>>,\n\
+         ) -> http::Response<Vec<u8>> {\n    \
+         let (parts, body) = request.into_parts();\n    \
+         #[allow(unused_mut)]\n    let mut path_matched = false;\n",
+    );
+    for (path, operations) in routes {
+        item.push_str(&format!(
+            "    if let Some(path_values) = match_template({:?}, parts.uri.path()) {{\n        \
+             path_matched = true;\n",
+            path
+        ));
+        for (signature, request) in operations {
+            item.push_str(&format!(
+                "        if parts.method == http::Method::{} {{\n            \
+                 return match {}::parse(&parts, &path_values, &body) {{\n                \
+                 Ok(request) => api.{}(request).await.into(),\n                \
+                 Err(message) => respond(400, Some(\"text/plain\"), message.into_bytes()),\n            \
+                 }};\n        }}\n",
+                signature.method.as_str().to_ascii_uppercase(),
+                request,
+                signature.ident
+            ));
+        }
+        item.push_str("    }\n");
+    }
+    item.push_str(
+        "    if path_matched {\n        respond(405, None, Vec::new())\n    } else {\n        \
+         respond(404, None, Vec::new())\n    }\n}\n",
+    );
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn petstore() -> String {
+        match from_path("data/v3.0/petstore.yaml").unwrap() {
+            OpenApi::V3_0(spec) => rust_server(&spec).unwrap(),
+            other => panic!("unexpected spec {:?}", other),
+        }
+    }
+
+    #[test]
+    fn generates_a_trait_method_per_operation() {
+        let source = petstore();
+        assert!(source.contains(
+            "pub trait Api {\n    /// List all pets\n    fn list_pets(\n        &self,\n        \
+             request: ListPetsRequest,\n    ) -> impl std::future::Future<Output = \
+             ListPetsResponse> + Send;\n"
+        ));
+        assert!(source.contains(
+            "pub struct ShowPetByIdRequest {\n    /// The id of the pet to retrieve\n    \
+             pub pet_id: String,\n}\n"
+        ));
+        assert!(source.contains(
+            "            pet_id: required(\n                \"petId\",\n                \
+             parameter(Some(&path_values[0]), \"petId\", \"path\", \"simple\", false, \
+             \"primitive\"),\n            )?,\n"
+        ));
+        assert!(source.contains(
+            "            limit: optional(\n                \"limit\",\n                \
+             parameter(Some(parts.uri.query().unwrap_or_default()), \"limit\", \"query\", \
+             \"form\", true, \"primitive\"),\n            )?,\n"
+        ));
+    }
+
+    #[test]
+    fn generates_routing_and_responses() {
+        let source = petstore();
+        assert!(source.contains(
+            "    if let Some(path_values) = match_template(\"/pets\", parts.uri.path()) {\n"
+        ));
+        assert!(
+            source.contains("            Ok(request) => api.create_pets(request).await.into(),\n")
+        );
+        assert!(source.contains(
+            "            ListPetsResponse::Ok(body) => json_response(200, \"application/json\", \
+             &body),\n            ListPetsResponse::Default { status, body } => \
+             json_response(status, \"application/json\", &body),\n"
+        ));
+        assert!(source.contains(
+            "            CreatePetsResponse::Created => respond(201, None, Vec::new()),\n"
+        ));
+    }
+}
@@ -1,10 +1,15 @@
 //! Error types
 
+use crate::{
+    v3_0::{Conflict, ParameterStyle},
+    Method,
+};
 use semver::{SemVerError, Version};
 use serde_json::Error as JsonError;
 use serde_yaml::Error as YamlError;
-use std::io::Error as IoError;
+use std::{fmt, io::Error as IoError};
 use thiserror::Error;
+use toml::de::Error as TomlError;
 
 /// errors that openapi functions may return
 #[derive(Error, Debug)]
@@ -15,8 +20,85 @@ pub enum Error {
     Yaml(#[from] YamlError),
     #[error("JSON serialization error")]
     Serialize(#[from] JsonError),
+    #[error("TOML deserialization error")]
+    Toml(#[from] TomlError),
     #[error("Semantic Versioning parsing error")]
     SemVerError(#[from] SemVerError),
     #[error("Unsupported spec file version ({0})")]
     UnsupportedSpecFileVersion(Version),
+    #[error("Parameter style `{style}` is not supported for `{location}` parameters")]
+    UnsupportedParameterStyle {
+        style: ParameterStyle,
+        location: String,
+    },
+    #[error("Invalid value for parameter `{name}`: {reason}")]
+    InvalidParameterValue { name: String, reason: String },
+    #[error("Malformed template ({0})")]
+    MalformedTemplate(String),
+    #[error("Undefined server variable `{0}`")]
+    UndefinedServerVariable(String),
+    #[error("Invalid value `{value}` for server variable `{name}`")]
+    InvalidServerVariableValue { name: String, value: String },
+    #[error("Unsupported HTTP method ({0})")]
+    UnsupportedMethod(String),
+    #[error("Unresolved reference ({0})")]
+    UnresolvedReference(String),
+    #[error("Duplicate parameter `{name}` in `{location}`")]
+    DuplicateParameter { name: String, location: String },
+    #[error("Duplicate operationId `{0}`")]
+    DuplicateOperationId(String),
+    #[error("Missing required field `{field}` of {object}")]
+    MissingField {
+        object: &'static str,
+        field: &'static str,
+    },
+    #[error("Operation `{method} {path}` is defined twice")]
+    DuplicateOperation { method: Method, path: String },
+    #[error("Path parameter `{name}` of `{method} {path}` is not declared")]
+    UndeclaredPathParameter {
+        method: Method,
+        path: String,
+        name: String,
+    },
+    #[error("Unsupported response status code ({0})")]
+    UnsupportedStatusCode(String),
+    #[error("Invalid pattern `{pattern}`: {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[error("Invalid spec ({})", join(.0))]
+    InvalidSpec(Vec<Problem>),
+    #[error("Unknown lint rule `{0}`")]
+    UnknownLintRule(String),
+    #[error("Conflicting specs ({})", join(.0))]
+    MergeConflicts(Vec<Conflict>),
+}
+
+/// A rule of the specification which a spec breaks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    /// The JSON pointer of the offending node.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        Problem {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`: {}", self.pointer, self.message)
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
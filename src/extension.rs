@@ -5,9 +5,9 @@ use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// Contains openapi specification extensions
+/// Contains openapi specification extensions, the `x-` prefixed fields of v2 and v3 objects
 /// see https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
 pub struct Extensions(HashMap<String, serde_json::Value>);
 
 impl Extensions {
@@ -26,12 +26,6 @@ impl Extensions {
     }
 }
 
-impl Default for Extensions {
-    fn default() -> Self {
-        Self(HashMap::new())
-    }
-}
-
 impl<'de> Deserialize<'de> for Extensions {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -80,7 +74,7 @@ mod tests {
     use serde_json::Value;
     use serde_test::{assert_tokens, Token};
 
-    use crate::v3_0::extension::Extensions;
+    use crate::extension::Extensions;
 
     #[test]
     fn test_serde_extensions() {
@@ -0,0 +1,98 @@
+//! Indexing of operations by their `operationId`.
+
+use crate::{Error, Method, Result};
+use std::collections::BTreeMap;
+
+/// An index of the operations of a spec by their `operationId`, supporting lookups in both
+/// directions.
+///
+/// Build one with `v2::Spec::operation_index` or `v3_0::Spec::operation_index`.
+#[derive(Clone, Debug)]
+pub struct OperationIndex<'a, O> {
+    by_id: BTreeMap<&'a str, (&'a str, Method, &'a O)>,
+    by_location: BTreeMap<Method, BTreeMap<&'a str, &'a str>>,
+}
+
+impl<'a, O> OperationIndex<'a, O> {
+    /// Indexes operations given along with their path, method and `operationId`, followed by
+    /// callback operations given with their callback expression in place of a path.
+    /// Operations without an `operationId` are skipped. Fails if an `operationId` is used
+    /// twice.
+    ///
+    /// Callback operations are only indexed by `operationId`, as the callbacks of several
+    /// operations may well share an expression and method.
+    pub(crate) fn new<I, C>(operations: I, callbacks: C) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a str, Method, &'a O, Option<&'a str>)>,
+        C: IntoIterator<Item = (&'a str, Method, &'a O, Option<&'a str>)>,
+    {
+        let mut index = OperationIndex {
+            by_id: BTreeMap::new(),
+            by_location: BTreeMap::new(),
+        };
+        for (path, method, operation, operation_id) in operations {
+            if let Some(operation_id) = operation_id {
+                index.insert(operation_id, path, method, operation)?;
+                index
+                    .by_location
+                    .entry(method)
+                    .or_default()
+                    .insert(path, operation_id);
+            }
+        }
+        for (expression, method, operation, operation_id) in callbacks {
+            if let Some(operation_id) = operation_id {
+                index.insert(operation_id, expression, method, operation)?;
+            }
+        }
+        Ok(index)
+    }
+
+    fn insert(
+        &mut self,
+        operation_id: &'a str,
+        path: &'a str,
+        method: Method,
+        operation: &'a O,
+    ) -> Result<()> {
+        if self
+            .by_id
+            .insert(operation_id, (path, method, operation))
+            .is_some()
+        {
+            return Err(Error::DuplicateOperationId(operation_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// The path, method and operation with the given `operationId`.
+    pub fn get(&self, operation_id: &str) -> Option<(&'a str, Method, &'a O)> {
+        self.by_id.get(operation_id).copied()
+    }
+
+    /// The `operationId` of the operation defined for a method on a path template. Callback
+    /// operations are not found by their expression.
+    pub fn operation_id(&self, method: Method, path: &str) -> Option<&'a str> {
+        self.by_location.get(&method)?.get(path).copied()
+    }
+
+    /// Returns true if an operation has the given `operationId`.
+    pub fn contains(&self, operation_id: &str) -> bool {
+        self.by_id.contains_key(operation_id)
+    }
+
+    /// The indexed `operationId`s, in order.
+    pub fn operation_ids(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.by_id.keys().copied()
+    }
+
+    /// The number of indexed operations.
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// Returns true if no operation has an `operationId`.
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
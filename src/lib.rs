@@ -27,11 +27,22 @@
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Read, path::Path, result::Result as StdResult};
 
+pub mod codegen;
 pub mod error;
+pub mod extension;
+pub mod index;
+pub mod lint;
+pub mod method;
+pub mod pointer;
+mod router;
+mod template;
 pub mod v2;
 pub mod v3_0;
 
-pub use error::Error;
+pub use error::{Error, Problem};
+pub use extension::Extensions;
+pub use index::OperationIndex;
+pub use method::Method;
 
 const MINIMUM_OPENAPI30_VERSION: &str = ">= 3.0";
 
@@ -40,6 +51,7 @@ pub type Result<T> = StdResult<T, Error>;
 /// Supported versions of the OpenApi.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum OpenApi {
     /// Version 2.0 of the OpenApi specification.
     ///
@@ -115,6 +127,7 @@ mod tests {
     /// paths, comparing the result.
     /// 1. File -> `String` -> `serde_yaml::Value` -> `serde_json::Value` -> `String`
     /// 2. File -> `Spec` -> `serde_json::Value` -> `String`
+    ///
     /// Both conversion of `serde_json::Value` -> `String` are done
     /// using `serde_json::to_string_pretty`.
     /// Since the first conversion is independant of the current crate (and only
@@ -130,7 +143,7 @@ mod tests {
         //     File -> `String` -> `serde_yaml::Value` -> `serde_json::Value` -> `String`
 
         // Read the original file to string
-        let spec_yaml_str = read_to_string(&input_file)
+        let spec_yaml_str = read_to_string(input_file)
             .unwrap_or_else(|e| panic!("failed to read contents of {:?}: {}", input_file, e));
         // Convert YAML string to JSON string
         let spec_json_str = convert_yaml_str_to_json(&spec_yaml_str);
@@ -139,7 +152,7 @@ mod tests {
         //     File -> `Spec` -> `serde_json::Value` -> `String`
 
         // Parse the input file
-        let parsed_spec = from_path(&input_file).unwrap();
+        let parsed_spec = from_path(input_file).unwrap();
         // Convert to serde_json::Value
         let parsed_spec_json = serde_json::to_value(parsed_spec).unwrap();
         // Convert to a JSON string
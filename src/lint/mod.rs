@@ -0,0 +1,358 @@
+//! Style checks of specs beyond the rules of the specification.
+//!
+//! A [`Linter`] runs a set of [`Rule`]s over a v2 or v3 spec, reporting each way a spec
+//! breaks one as a [`Lint`] of the rule's [`Severity`]. The severity of each rule can be
+//! changed, or the rule turned off, with a [`Config`] read from YAML or TOML:
+//!
+//! ```yaml
+//! rules:
+//!   path-kebab-case: error
+//!   operation-tags: off
+//! ```
+//!
+//! A node of a spec opts out of rules for itself and everything below it with an
+//! `x-lint-ignore` extension, holding either a list of rule names or a comma-separated
+//! string of them. Extensions are read from the spec, path items, operations, responses
+//! and schemas.
+//!
+//! ```
+//! use openapi::{lint::Linter, OpenApi};
+//!
+//! # fn main() -> openapi::Result<()> {
+//! let spec = openapi::from_path("data/v3.0/petstore.yaml")?;
+//! for lint in Linter::new().lint(&spec) {
+//!     println!("{}", lint);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod rules;
+
+pub use crate::lint::rules::*;
+
+use crate::{
+    pointer, v2,
+    v3_0::{
+        self,
+        visit::{self, Visit},
+    },
+    Error, OpenApi, Problem, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+/// The extension naming the rules a node and its descendants are exempt from.
+const IGNORE: &str = "x-lint-ignore";
+
+/// How much a broken rule matters.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule is not checked.
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A style rule which specs are checked against.
+pub trait Rule {
+    /// The name the rule is configured and ignored by, such as `path-kebab-case`.
+    fn name(&self) -> &str;
+
+    /// The severity of the rule's lints unless configured otherwise.
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Every way a v3 spec breaks the rule.
+    fn check_v3(&self, _spec: &v3_0::Spec) -> Vec<Problem> {
+        Vec::new()
+    }
+
+    /// Every way a v2 spec breaks the rule.
+    fn check_v2(&self, _spec: &v2::Spec) -> Vec<Problem> {
+        Vec::new()
+    }
+}
+
+/// A way in which a spec breaks a rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lint {
+    pub rule: String,
+    pub severity: Severity,
+    /// The JSON pointer of the offending node.
+    pub pointer: String,
+    pub message: String,
+}
+
+/// The severities of rules, by name.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub rules: BTreeMap<String, Severity>,
+}
+
+impl Config {
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Reads a config file, as TOML if its extension is `.toml` and as YAML otherwise.
+    pub fn from_path<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        match path.extension() {
+            Some(extension) if extension == "toml" => Config::from_toml(&contents),
+            _ => Config::from_yaml(&contents),
+        }
+    }
+}
+
+/// Checks specs against a set of rules.
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new()
+    }
+}
+
+impl Linter {
+    /// A linter with the built-in rules, at their default severities.
+    pub fn new() -> Self {
+        Linter { rules: Vec::new() }
+            .rule(PathKebabCase)
+            .rule(PropertyCamelCase)
+            .rule(OperationSummary)
+            .rule(OperationTags)
+            .rule(ErrorResponseSchema)
+    }
+
+    /// Adds a rule, at its default severity.
+    pub fn rule(mut self, rule: impl Rule + 'static) -> Self {
+        let severity = rule.severity();
+        self.rules.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Applies the severities of a config. Fails if it names a rule the linter does not
+    /// have, so custom rules should be added first.
+    pub fn configure(mut self, config: &Config) -> Result<Self> {
+        for (name, severity) in &config.rules {
+            match self.rules.iter_mut().find(|(rule, _)| rule.name() == name) {
+                Some((_, configured)) => *configured = *severity,
+                None => return Err(Error::UnknownLintRule(name.clone())),
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn lint(&self, spec: &OpenApi) -> Vec<Lint> {
+        match spec {
+            OpenApi::V2(spec) => self.lint_v2(spec),
+            OpenApi::V3_0(spec) => self.lint_v3(spec),
+        }
+    }
+
+    pub fn lint_v3(&self, spec: &v3_0::Spec) -> Vec<Lint> {
+        let mut ignores = Ignores::default();
+        ignores.visit_spec(spec);
+        self.run(&ignores, |rule| rule.check_v3(spec))
+    }
+
+    pub fn lint_v2(&self, spec: &v2::Spec) -> Vec<Lint> {
+        let mut ignores = Ignores::default();
+        v2::walk(spec, &mut |pointer, node| {
+            let extension = match node {
+                v2::Node::Spec(spec) => spec.extensions.get(IGNORE),
+                v2::Node::PathItem(path_item) => path_item.extensions.get(IGNORE),
+                v2::Node::Operation(operation) => operation.extensions.get(IGNORE),
+                v2::Node::Response(response) => response.extensions.get(IGNORE),
+                v2::Node::Schema(schema) => schema.other.get(IGNORE),
+                _ => None,
+            };
+            ignores.add(pointer, extension);
+            true
+        });
+        self.run(&ignores, |rule| rule.check_v2(spec))
+    }
+
+    fn run(&self, ignores: &Ignores, check: impl Fn(&dyn Rule) -> Vec<Problem>) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        for (rule, severity) in &self.rules {
+            if *severity == Severity::Off {
+                continue;
+            }
+            for problem in check(rule.as_ref()) {
+                if !ignores.ignores(&problem.pointer, rule.name()) {
+                    lints.push(Lint {
+                        rule: rule.name().to_string(),
+                        severity: *severity,
+                        pointer: problem.pointer,
+                        message: problem.message,
+                    });
+                }
+            }
+        }
+        lints
+    }
+}
+
+/// The rules ignored by the nodes of a spec, by pointer.
+#[derive(Default)]
+struct Ignores(BTreeMap<String, Vec<String>>);
+
+impl Ignores {
+    fn add(&mut self, pointer: &str, extension: Option<&serde_json::Value>) {
+        let rules = match extension {
+            Some(serde_json::Value::String(rules)) => rules
+                .split(',')
+                .map(|rule| rule.trim().to_string())
+                .collect(),
+            Some(serde_json::Value::Array(rules)) => rules
+                .iter()
+                .filter_map(|rule| rule.as_str().map(ToString::to_string))
+                .collect(),
+            _ => return,
+        };
+        self.0.insert(pointer.to_string(), rules);
+    }
+
+    /// Returns true if the node at `pointer` or one of its ancestors ignores the rule.
+    fn ignores(&self, pointer: &str, rule: &str) -> bool {
+        self.0.iter().any(|(node, rules)| {
+            (node == pointer || pointer::is_ancestor(node, pointer))
+                && rules.iter().any(|ignored| ignored == rule)
+        })
+    }
+}
+
+impl<'a> Visit<'a> for Ignores {
+    fn visit_spec(&mut self, spec: &'a v3_0::Spec) {
+        self.add("", spec.extensions.get(IGNORE));
+        visit::walk_spec(self, spec)
+    }
+
+    fn visit_path_item(&mut self, pointer: &str, path_item: &'a v3_0::PathItem) {
+        self.add(pointer, path_item.extensions.get(IGNORE));
+        visit::walk_path_item(self, pointer, path_item)
+    }
+
+    fn visit_operation(&mut self, pointer: &str, operation: &'a v3_0::Operation) {
+        self.add(pointer, operation.extensions.get(IGNORE));
+        visit::walk_operation(self, pointer, operation)
+    }
+
+    fn visit_response(&mut self, pointer: &str, response: &'a v3_0::Response) {
+        self.add(pointer, response.extensions.get(IGNORE));
+        visit::walk_response(self, pointer, response)
+    }
+
+    fn visit_schema(&mut self, pointer: &str, schema: &'a v3_0::Schema) {
+        self.add(pointer, schema.extensions.get(IGNORE));
+        visit::walk_schema(self, pointer, schema)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] `{}`: {}",
+            self.severity, self.rule, self.pointer, self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_configs() {
+        let yaml =
+            Config::from_yaml("rules:\n  path-kebab-case: error\n  operation-tags: off\n").unwrap();
+        let toml =
+            Config::from_toml("[rules]\npath-kebab-case = \"error\"\noperation-tags = \"off\"\n")
+                .unwrap();
+        assert_eq!(yaml, toml);
+        assert_eq!(yaml.rules["operation-tags"], Severity::Off);
+        assert!(matches!(
+            Linter::new().configure(&Config::from_yaml("rules: { kebab: error }").unwrap()),
+            Err(Error::UnknownLintRule(name)) if name == "kebab"
+        ));
+    }
+
+    #[test]
+    fn configures_and_ignores_rules() {
+        let spec: v3_0::Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Lints
+  version: 1.0.0
+paths:
+  /petOwners:
+    get:
+      summary: List owners
+      responses:
+        '200':
+          description: Owners
+  /Pets:
+    x-lint-ignore: path-kebab-case
+    get:
+      x-lint-ignore: [operation-summary, operation-tags]
+      responses:
+        '200':
+          description: Pets
+components:
+  schemas:
+    Pet:
+      x-lint-ignore: [property-camel-case]
+      properties:
+        pet_id:
+          type: string
+"#,
+        )
+        .unwrap();
+        let config =
+            Config::from_yaml("rules:\n  path-kebab-case: error\n  operation-tags: off\n").unwrap();
+        let lints = Linter::new()
+            .configure(&config)
+            .unwrap()
+            .lint_v3(&spec)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lints,
+            vec![
+                "error [path-kebab-case] `/paths/~1petOwners`: \
+                  path segment `petOwners` is not kebab-case"
+            ]
+        );
+    }
+}
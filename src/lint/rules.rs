@@ -0,0 +1,326 @@
+//! The built-in rules.
+
+use crate::{
+    lint::Rule,
+    pointer, v2,
+    v3_0::{
+        self,
+        visit::{self, Visit},
+    },
+    Problem,
+};
+
+/// The literal segments of paths are kebab-case, such as `/pet-owners/{ownerId}`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PathKebabCase;
+
+/// The property names of schemas are camelCase, such as `ownerId`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PropertyCamelCase;
+
+/// Operations have a `summary`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OperationSummary;
+
+/// Operations have at least one tag.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OperationTags;
+
+/// `4xx` responses describe their body with a schema.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ErrorResponseSchema;
+
+impl Rule for PathKebabCase {
+    fn name(&self) -> &str {
+        "path-kebab-case"
+    }
+
+    fn check_v3(&self, spec: &v3_0::Spec) -> Vec<Problem> {
+        spec.paths
+            .keys()
+            .flat_map(|path| kebab_case(path))
+            .collect()
+    }
+
+    fn check_v2(&self, spec: &v2::Spec) -> Vec<Problem> {
+        spec.paths
+            .keys()
+            .flat_map(|path| kebab_case(path))
+            .collect()
+    }
+}
+
+impl Rule for PropertyCamelCase {
+    fn name(&self) -> &str {
+        "property-camel-case"
+    }
+
+    fn check_v3(&self, spec: &v3_0::Spec) -> Vec<Problem> {
+        let nodes = Nodes::of(spec);
+        nodes
+            .schemas
+            .iter()
+            .flat_map(|(pointer, schema)| camel_case(pointer, schema.properties.iter().flatten()))
+            .collect()
+    }
+
+    fn check_v2(&self, spec: &v2::Spec) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        v2::walk(spec, &mut |pointer, node| {
+            if let v2::Node::Schema(schema) = node {
+                problems.extend(camel_case(pointer, schema.properties.iter().flatten()));
+            }
+            true
+        });
+        problems
+    }
+}
+
+impl Rule for OperationSummary {
+    fn name(&self) -> &str {
+        "operation-summary"
+    }
+
+    fn check_v3(&self, spec: &v3_0::Spec) -> Vec<Problem> {
+        let nodes = Nodes::of(spec);
+        let summaries = nodes
+            .operations
+            .iter()
+            .map(|(pointer, operation)| (pointer.as_str(), operation.summary.as_deref()));
+        missing_summary(summaries)
+    }
+
+    fn check_v2(&self, spec: &v2::Spec) -> Vec<Problem> {
+        let operations = v2_operations(spec);
+        let summaries = operations
+            .iter()
+            .map(|(pointer, operation)| (pointer.as_str(), operation.summary.as_deref()));
+        missing_summary(summaries)
+    }
+}
+
+impl Rule for OperationTags {
+    fn name(&self) -> &str {
+        "operation-tags"
+    }
+
+    fn check_v3(&self, spec: &v3_0::Spec) -> Vec<Problem> {
+        let nodes = Nodes::of(spec);
+        let tags = nodes
+            .operations
+            .iter()
+            .map(|(pointer, operation)| (pointer.as_str(), operation.tags.as_ref()));
+        missing_tags(tags)
+    }
+
+    fn check_v2(&self, spec: &v2::Spec) -> Vec<Problem> {
+        let operations = v2_operations(spec);
+        let tags = operations
+            .iter()
+            .map(|(pointer, operation)| (pointer.as_str(), operation.tags.as_ref()));
+        missing_tags(tags)
+    }
+}
+
+impl Rule for ErrorResponseSchema {
+    fn name(&self) -> &str {
+        "error-response-schema"
+    }
+
+    fn check_v3(&self, spec: &v3_0::Spec) -> Vec<Problem> {
+        let nodes = Nodes::of(spec);
+        let mut problems = Vec::new();
+        for (pointer, operation) in &nodes.operations {
+            for (status, response) in &operation.responses {
+                let has_schema = response
+                    .content
+                    .iter()
+                    .flatten()
+                    .any(|(_, media_type)| media_type.schema.is_some());
+                problems.extend(error_response(pointer, status, has_schema));
+            }
+        }
+        problems
+    }
+
+    fn check_v2(&self, spec: &v2::Spec) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        for (pointer, operation) in v2_operations(spec) {
+            for (status, response) in &operation.responses {
+                problems.extend(error_response(&pointer, status, response.schema.is_some()));
+            }
+        }
+        problems
+    }
+}
+
+/// The v3 operations, including those of callbacks, and schemas, with their pointers.
+#[derive(Default)]
+struct Nodes<'a> {
+    operations: Vec<(String, &'a v3_0::Operation)>,
+    schemas: Vec<(String, &'a v3_0::Schema)>,
+}
+
+impl<'a> Nodes<'a> {
+    fn of(spec: &'a v3_0::Spec) -> Self {
+        let mut nodes = Nodes::default();
+        nodes.visit_spec(spec);
+        nodes
+    }
+}
+
+impl<'a> Visit<'a> for Nodes<'a> {
+    fn visit_operation(&mut self, pointer: &str, operation: &'a v3_0::Operation) {
+        self.operations.push((pointer.to_string(), operation));
+        visit::walk_operation(self, pointer, operation)
+    }
+
+    fn visit_schema(&mut self, pointer: &str, schema: &'a v3_0::Schema) {
+        self.schemas.push((pointer.to_string(), schema));
+        visit::walk_schema(self, pointer, schema)
+    }
+}
+
+fn v2_operations(spec: &v2::Spec) -> Vec<(String, &v2::Operation)> {
+    let mut operations = Vec::new();
+    for (path, path_item) in &spec.paths {
+        for (method, operation) in path_item.operations() {
+            let pointer = pointer::join(&pointer::join("/paths", path), method.as_str());
+            operations.push((pointer, operation));
+        }
+    }
+    operations
+}
+
+fn kebab_case(path: &str) -> Vec<Problem> {
+    let pointer = pointer::join("/paths", path);
+    path.split('/')
+        .filter(|segment| {
+            // template expressions name parameters, so are exempt
+            let mut literal = String::new();
+            let mut depth = 0;
+            for c in segment.chars() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    c if depth == 0 => literal.push(c),
+                    _ => {}
+                }
+            }
+            !literal
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+        })
+        .map(|segment| {
+            Problem::new(
+                &pointer,
+                format!("path segment `{}` is not kebab-case", segment),
+            )
+        })
+        .collect()
+}
+
+fn camel_case<'a, T: 'a>(
+    pointer: &str,
+    properties: impl Iterator<Item = (&'a String, T)>,
+) -> Vec<Problem> {
+    properties
+        .filter(|(name, _)| {
+            let mut chars = name.chars();
+            !(chars.next().is_some_and(|c| c.is_ascii_lowercase())
+                && chars.all(|c| c.is_ascii_alphanumeric()))
+        })
+        .map(|(name, _)| {
+            Problem::new(
+                pointer::join(&pointer::join(pointer, "properties"), name),
+                format!("property `{}` is not camelCase", name),
+            )
+        })
+        .collect()
+}
+
+fn missing_summary<'a>(
+    operations: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+) -> Vec<Problem> {
+    operations
+        .filter(|(_, summary)| summary.is_none_or(|summary| summary.trim().is_empty()))
+        .map(|(pointer, _)| Problem::new(pointer, "the operation has no summary"))
+        .collect()
+}
+
+fn missing_tags<'a>(
+    operations: impl Iterator<Item = (&'a str, Option<&'a Vec<String>>)>,
+) -> Vec<Problem> {
+    operations
+        .filter(|(_, tags)| tags.is_none_or(|tags| tags.is_empty()))
+        .map(|(pointer, _)| Problem::new(pointer, "the operation has no tags"))
+        .collect()
+}
+
+fn error_response(pointer: &str, status: &str, has_schema: bool) -> Option<Problem> {
+    if status.starts_with('4') && !has_schema {
+        let pointer = pointer::join(&pointer::join(pointer, "responses"), status);
+        Some(Problem::new(
+            pointer,
+            format!("the `{}` response has no schema", status),
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::Linter;
+
+    #[test]
+    fn checks_v2_specs() {
+        let spec: v2::Spec = serde_yaml::from_str(
+            r##"
+swagger: "2.0"
+info:
+  title: Lints
+paths:
+  /pet_owners/{ownerId}:
+    get:
+      summary: An owner
+      tags: [owners]
+      responses:
+        '200':
+          description: An owner
+          schema:
+            $ref: "#/definitions/Owner"
+        '404':
+          description: No such owner
+definitions:
+  Owner:
+    x-lint-ignore: property-camel-case
+    properties:
+      owner_id:
+        type: string
+  Error:
+    properties:
+      error_code:
+        type: string
+"##,
+        )
+        .unwrap();
+        let lints = Linter::new()
+            .lint_v2(&spec)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lints,
+            vec![
+                "warning [path-kebab-case] `/paths/~1pet_owners~1{ownerId}`: \
+                 path segment `pet_owners` is not kebab-case",
+                "warning [property-camel-case] `/definitions/Error/properties/error_code`: \
+                 property `error_code` is not camelCase",
+                "warning [error-response-schema] `/paths/~1pet_owners~1{ownerId}/get/responses/404`: \
+                 the `404` response has no schema",
+            ]
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! HTTP methods
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// The HTTP methods an operation may be defined for on a path item.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    Get,
+    Put,
+    Post,
+    Delete,
+    Options,
+    Head,
+    Patch,
+    Trace,
+}
+
+impl Method {
+    /// Every method, in the order path item fields are declared in the specification.
+    pub const ALL: [Method; 8] = [
+        Method::Get,
+        Method::Put,
+        Method::Post,
+        Method::Delete,
+        Method::Options,
+        Method::Head,
+        Method::Patch,
+        Method::Trace,
+    ];
+
+    /// The lowercase name of the method, as used for path item fields.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Put => "put",
+            Method::Post => "post",
+            Method::Delete => "delete",
+            Method::Options => "options",
+            Method::Head => "head",
+            Method::Patch => "patch",
+            Method::Trace => "trace",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.as_str().to_uppercase())
+    }
+}
+
+impl FromStr for Method {
+    type Err = Error;
+
+    /// Parses a method name, ignoring case.
+    fn from_str(method: &str) -> Result<Self> {
+        Method::ALL
+            .iter()
+            .copied()
+            .find(|candidate| candidate.as_str().eq_ignore_ascii_case(method))
+            .ok_or_else(|| Error::UnsupportedMethod(method.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_displays_methods() {
+        assert_eq!("get".parse::<Method>().unwrap(), Method::Get);
+        assert_eq!("PATCH".parse::<Method>().unwrap(), Method::Patch);
+        assert!("CONNECT".parse::<Method>().is_err());
+        assert_eq!(Method::Delete.to_string(), "DELETE");
+    }
+}
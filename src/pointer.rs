@@ -0,0 +1,62 @@
+//! [JSON Pointer](https://tools.ietf.org/html/rfc6901) helpers, used to address nodes of a
+//! document such as `/components/schemas/Pet`.
+
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+
+/// Escapes a reference token, replacing `~` with `~0` and `/` with `~1`.
+pub fn escape(token: &str) -> Cow<'_, str> {
+    if token.contains(['~', '/']) {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Unescapes a reference token, replacing `~1` with `/` and `~0` with `~`.
+pub fn unescape(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Appends an escaped reference token to a pointer.
+pub fn join(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, escape(token))
+}
+
+/// Accepts a pointer either as is or as a URI fragment such as `#/components/schemas/Pet`,
+/// whose characters may be percent-encoded.
+pub(crate) fn from_fragment(pointer: &str) -> Cow<'_, str> {
+    match pointer.strip_prefix('#') {
+        Some(fragment) => percent_decode_str(fragment).decode_utf8_lossy(),
+        None => Cow::Borrowed(pointer),
+    }
+}
+
+/// Returns true if `descendant` addresses a node below the one `pointer` addresses.
+pub(crate) fn is_ancestor(pointer: &str, descendant: &str) -> bool {
+    descendant
+        .strip_prefix(pointer)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_and_unescapes_tokens() {
+        assert_eq!(join("/paths", "/pets/{petId}"), "/paths/~1pets~1{petId}");
+        assert_eq!(escape("a~b"), "a~0b");
+        assert_eq!(unescape("~1pets~0~01"), "/pets~~1");
+        assert!(is_ancestor("", "/paths"));
+        assert!(!is_ancestor("/paths/~1a", "/paths/~1ab"));
+        assert_eq!(
+            from_fragment("#/paths/~1pets~1%7BpetId%7D"),
+            "/paths/~1pets~1{petId}"
+        );
+    }
+}
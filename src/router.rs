@@ -0,0 +1,179 @@
+//! Matching of concrete paths against path templates such as `/pets/{petId}`.
+
+use crate::template::{self, Part};
+use percent_encoding::percent_decode_str;
+use std::collections::BTreeMap;
+
+/// A path template split into its `/` separated segments.
+pub(crate) struct PathTemplate<'a> {
+    segments: Vec<Vec<Part<'a>>>,
+}
+
+impl<'a> PathTemplate<'a> {
+    /// Parses a template, returning `None` if it is malformed.
+    pub(crate) fn parse(template: &'a str) -> Option<Self> {
+        template
+            .split('/')
+            .map(template::parse)
+            .collect::<Option<Vec<_>>>()
+            .map(|segments| PathTemplate { segments })
+    }
+
+    /// Orders templates so that, segment by segment, literal segments come before
+    /// partially templated ones, which come before segments made of a single variable.
+    fn rank(&self) -> Vec<u8> {
+        self.segments
+            .iter()
+            .map(|segment| match segment.as_slice() {
+                [Part::Variable(_)] => 2,
+                parts if parts.iter().all(|part| matches!(part, Part::Literal(_))) => 0,
+                _ => 1,
+            })
+            .collect()
+    }
+
+    /// Matches a concrete path, returning the percent-decoded values of the variables.
+    pub(crate) fn matches(&self, path: &str) -> Option<BTreeMap<String, String>> {
+        let segments = path.split('/').collect::<Vec<_>>();
+        if segments.len() != self.segments.len() {
+            return None;
+        }
+        let mut values = BTreeMap::new();
+        for (parts, segment) in self.segments.iter().zip(segments) {
+            if !match_segment(parts, segment, &mut values) {
+                return None;
+            }
+        }
+        values
+            .into_iter()
+            .map(|(name, value)| {
+                percent_decode_str(value)
+                    .decode_utf8()
+                    .ok()
+                    .map(|value| (name.to_string(), value.into_owned()))
+            })
+            .collect()
+    }
+}
+
+/// Matches all of a segment, binding each variable to at least one character.
+fn match_segment<'a, 's>(
+    parts: &[Part<'a>],
+    segment: &'s str,
+    values: &mut BTreeMap<&'a str, &'s str>,
+) -> bool {
+    match parts.split_first() {
+        None => segment.is_empty(),
+        Some((Part::Literal(literal), parts)) => match segment.strip_prefix(literal) {
+            Some(rest) => match_segment(parts, rest, values),
+            None => false,
+        },
+        Some((Part::Variable(name), parts)) => {
+            for length in 1..=segment.len() {
+                if !segment.is_char_boundary(length) {
+                    continue;
+                }
+                let value = &segment[..length];
+                let bound = match values.get(name) {
+                    Some(existing) if *existing != value => continue,
+                    Some(_) => false,
+                    None => {
+                        values.insert(name, value);
+                        true
+                    }
+                };
+                if match_segment(parts, &segment[length..], values) {
+                    return true;
+                }
+                if bound {
+                    values.remove(name);
+                }
+            }
+            false
+        }
+    }
+}
+
+/// Path templates, ordered by precedence, along with the values they map to.
+pub(crate) struct Routes<'a, T> {
+    routes: Vec<(&'a str, PathTemplate<'a>, &'a T)>,
+}
+
+impl<'a, T> Routes<'a, T> {
+    /// Builds routes from templates, skipping any which are malformed.
+    pub(crate) fn new<I>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a T)>,
+    {
+        let mut routes = paths
+            .into_iter()
+            .filter_map(|(path, value)| {
+                PathTemplate::parse(path).map(|template| (path.as_str(), template, value))
+            })
+            .collect::<Vec<_>>();
+        routes.sort_by_cached_key(|(_, template, _)| template.rank());
+        Routes { routes }
+    }
+
+    /// Finds the first template, by precedence, matching a concrete path.
+    pub(crate) fn find(&self, path: &str) -> Option<(&'a str, &'a T, BTreeMap<String, String>)> {
+        self.find_all(path).next()
+    }
+
+    /// Finds the templates matching a concrete path, by precedence.
+    pub(crate) fn find_all<'r>(
+        &'r self,
+        path: &'r str,
+    ) -> impl Iterator<Item = (&'a str, &'a T, BTreeMap<String, String>)> + 'r {
+        self.routes
+            .iter()
+            .filter_map(move |(template, parsed, value)| {
+                parsed
+                    .matches(path)
+                    .map(|parameters| (*template, *value, parameters))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_templates() {
+        let template = PathTemplate::parse("/pets/{petId}/photos/{name}.{ext}").unwrap();
+        let values = template.matches("/pets/42/photos/cat%20nap.png").unwrap();
+        assert_eq!(values["petId"], "42");
+        assert_eq!(values["name"], "cat nap");
+        assert_eq!(values["ext"], "png");
+        assert_eq!(template.matches("/pets/42/photos"), None);
+        assert_eq!(template.matches("/pets//photos/a.png"), None);
+    }
+
+    #[test]
+    fn prefers_literal_segments() {
+        let paths = [
+            ("/pets/{petId}".to_string(), 1),
+            ("/pets/mine".to_string(), 2),
+            ("/{kind}/mine".to_string(), 3),
+        ];
+        let routes = Routes::new(paths.iter().map(|(path, value)| (path, value)));
+        assert_eq!(
+            routes.find("/pets/mine").map(|(_, value, _)| *value),
+            Some(2)
+        );
+        assert_eq!(routes.find("/pets/42").map(|(_, value, _)| *value), Some(1));
+        assert_eq!(
+            routes.find("/cats/mine").map(|(_, value, _)| *value),
+            Some(3)
+        );
+        assert!(routes.find("/cats").is_none());
+        assert_eq!(
+            routes
+                .find_all("/pets/mine")
+                .map(|(_, value, _)| *value)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 3]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Parsing of `{name}` templates, as used by server URLs and paths.
+
+/// A piece of a template.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Part<'a> {
+    /// Text which must appear as is.
+    Literal(&'a str),
+    /// A named `{variable}`.
+    Variable(&'a str),
+}
+
+/// Splits a template into its literal and variable parts. Returns `None` if a brace is not
+/// closed or a variable has no name.
+pub(crate) fn parse(template: &str) -> Option<Vec<Part<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Literal(&rest[..start]));
+        }
+        let end = start + rest[start..].find('}')?;
+        let name = &rest[start + 1..end];
+        if name.is_empty() || name.contains('{') {
+            return None;
+        }
+        parts.push(Part::Variable(name));
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+    Some(parts)
+}
+
+/// The names of the variables in a parsed template, in order of appearance.
+pub(crate) fn variables<'a>(parts: &[Part<'a>]) -> Vec<&'a str> {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Variable(name) => Some(*name),
+            Part::Literal(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_literals_and_variables() {
+        assert_eq!(
+            parse("https://{region}.example.com/{basePath}"),
+            Some(vec![
+                Part::Literal("https://"),
+                Part::Variable("region"),
+                Part::Literal(".example.com/"),
+                Part::Variable("basePath"),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_braces() {
+        assert_eq!(parse("/pets/{petId"), None);
+        assert_eq!(parse("/pets/{}"), None);
+    }
+}
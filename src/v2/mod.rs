@@ -4,6 +4,11 @@
 //! [specification](https://github.com/OAI/OpenAPI-Specification/blob/0dd79f6/versions/2.0.md)
 //! for more information.
 
+mod node;
+mod operations;
+mod parameters;
+mod router;
 mod schema;
+mod validation;
 
-pub use crate::v2::schema::*;
+pub use crate::v2::{node::*, router::*, schema::*};
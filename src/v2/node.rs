@@ -0,0 +1,201 @@
+//! Addressing of the nodes of a spec by [JSON pointer](crate::pointer).
+
+use crate::{
+    pointer,
+    v2::{Operation, Parameter, ParameterOrRef, PathItem, Response, Schema, Security, Spec},
+};
+use std::ptr;
+
+/// A reference to a node of a spec.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Node<'a> {
+    Spec(&'a Spec),
+    PathItem(&'a PathItem),
+    Operation(&'a Operation),
+    /// A parameter of a path item or operation.
+    ParameterOrRef(&'a ParameterOrRef),
+    /// A parameter of the spec's `parameters`.
+    Parameter(&'a Parameter),
+    Response(&'a Response),
+    Schema(&'a Schema),
+    Security(&'a Security),
+}
+
+impl Node<'_> {
+    /// Returns true if both refer to the same node, rather than to equal ones.
+    pub fn is(&self, other: &Node<'_>) -> bool {
+        match (self, other) {
+            (Node::Spec(a), Node::Spec(b)) => ptr::eq(*a, *b),
+            (Node::PathItem(a), Node::PathItem(b)) => ptr::eq(*a, *b),
+            (Node::Operation(a), Node::Operation(b)) => ptr::eq(*a, *b),
+            (Node::ParameterOrRef(a), Node::ParameterOrRef(b)) => ptr::eq(*a, *b),
+            (Node::Parameter(a), Node::Parameter(b)) => ptr::eq(*a, *b),
+            (Node::Response(a), Node::Response(b)) => ptr::eq(*a, *b),
+            (Node::Schema(a), Node::Schema(b)) => ptr::eq(*a, *b),
+            (Node::Security(a), Node::Security(b)) => ptr::eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Spec {
+    /// The node a JSON pointer, such as `/definitions/Pet/properties/name`, refers to. The
+    /// pointer may also be given as a URI fragment, as in `$ref`s.
+    ///
+    /// Path items, operations, parameters, responses, schemas and security definitions can
+    /// be resolved. References are not followed.
+    pub fn resolve_pointer(&self, pointer: &str) -> Option<Node<'_>> {
+        let target = pointer::from_fragment(pointer);
+        let mut found = None;
+        walk(self, &mut |pointer, node| {
+            if found.is_some() {
+                return false;
+            }
+            if pointer == target {
+                found = Some(node);
+            }
+            pointer::is_ancestor(pointer, &target)
+        });
+        found
+    }
+
+    /// The JSON pointer of a node of this spec, or `None` if the node is not part of it.
+    pub fn pointer_of(&self, node: Node<'_>) -> Option<String> {
+        let mut found = None;
+        walk(self, &mut |pointer, candidate| {
+            if found.is_none() && node.is(&candidate) {
+                found = Some(pointer.to_string());
+            }
+            found.is_none()
+        });
+        found
+    }
+}
+
+/// Calls `enter` with every node of the spec and its pointer, depth first. Children are only
+/// visited if `enter` returns true.
+pub(crate) fn walk<'a>(spec: &'a Spec, enter: &mut dyn FnMut(&str, Node<'a>) -> bool) {
+    if !enter("", Node::Spec(spec)) {
+        return;
+    }
+    for (path, path_item) in &spec.paths {
+        let pointer = pointer::join("/paths", path);
+        if !enter(&pointer, Node::PathItem(path_item)) {
+            continue;
+        }
+        walk_parameters(&pointer, path_item.parameters.as_ref(), enter);
+        for (method, operation) in path_item.operations() {
+            let pointer = pointer::join(&pointer, method.as_str());
+            if enter(&pointer, Node::Operation(operation)) {
+                walk_parameters(&pointer, operation.parameters.as_ref(), enter);
+                for (status, response) in &operation.responses {
+                    let pointer = pointer::join(&pointer::join(&pointer, "responses"), status);
+                    walk_response(&pointer, response, enter);
+                }
+            }
+        }
+    }
+    for (name, schema) in spec.definitions.iter().flatten() {
+        walk_schema(&pointer::join("/definitions", name), schema, enter);
+    }
+    for (name, parameter) in spec.parameters.iter().flatten() {
+        let pointer = pointer::join("/parameters", name);
+        if enter(&pointer, Node::Parameter(parameter)) {
+            if let Some(schema) = &parameter.schema {
+                walk_schema(&pointer::join(&pointer, "schema"), schema, enter);
+            }
+            if let Some(items) = &parameter.items {
+                walk_schema(&pointer::join(&pointer, "items"), items, enter);
+            }
+        }
+    }
+    for (name, response) in spec.responses.iter().flatten() {
+        walk_response(&pointer::join("/responses", name), response, enter);
+    }
+    for (name, security) in spec.security_definitions.iter().flatten() {
+        enter(
+            &pointer::join("/securityDefinitions", name),
+            Node::Security(security),
+        );
+    }
+}
+
+fn walk_parameters<'a>(
+    pointer: &str,
+    parameters: Option<&'a Vec<ParameterOrRef>>,
+    enter: &mut dyn FnMut(&str, Node<'a>) -> bool,
+) {
+    for (index, parameter) in parameters.into_iter().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "parameters"), &index.to_string());
+        if !enter(&pointer, Node::ParameterOrRef(parameter)) {
+            continue;
+        }
+        if let ParameterOrRef::Parameter { schema, items, .. } = parameter {
+            if let Some(schema) = schema {
+                walk_schema(&pointer::join(&pointer, "schema"), schema, enter);
+            }
+            if let Some(items) = items {
+                walk_schema(&pointer::join(&pointer, "items"), items, enter);
+            }
+        }
+    }
+}
+
+fn walk_response<'a>(
+    pointer: &str,
+    response: &'a Response,
+    enter: &mut dyn FnMut(&str, Node<'a>) -> bool,
+) {
+    if enter(pointer, Node::Response(response)) {
+        if let Some(schema) = &response.schema {
+            walk_schema(&pointer::join(pointer, "schema"), schema, enter);
+        }
+    }
+}
+
+fn walk_schema<'a>(
+    pointer: &str,
+    schema: &'a Schema,
+    enter: &mut dyn FnMut(&str, Node<'a>) -> bool,
+) {
+    if !enter(pointer, Node::Schema(schema)) {
+        return;
+    }
+    if let Some(items) = &schema.items {
+        walk_schema(&pointer::join(pointer, "items"), items, enter);
+    }
+    for (name, property) in schema.properties.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "properties"), name);
+        walk_schema(&pointer, property, enter);
+    }
+    for (index, subschema) in schema.all_of.iter().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "allOf"), &index.to_string());
+        walk_schema(&pointer, subschema, enter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn resolves_pointers_and_nodes() {
+        let spec: Spec =
+            serde_yaml::from_reader(File::open("data/v2/petstore-simple.yaml").unwrap()).unwrap();
+        let schema = match spec.resolve_pointer("#/definitions/NewPet/properties/name") {
+            Some(Node::Schema(schema)) => schema,
+            other => panic!("unexpected node {:?}", other),
+        };
+        assert_eq!(schema.schema_type.as_deref(), Some("string"));
+        assert_eq!(
+            spec.pointer_of(Node::Schema(schema)).as_deref(),
+            Some("/definitions/NewPet/properties/name")
+        );
+        assert!(matches!(
+            spec.resolve_pointer("/paths/~1pets~1{id}/delete/parameters/0"),
+            Some(Node::ParameterOrRef(_))
+        ));
+        assert_eq!(spec.resolve_pointer("/definitions/Cat"), None);
+    }
+}
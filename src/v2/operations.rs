@@ -0,0 +1,134 @@
+//! Access to the operations of path items by HTTP method.
+
+use crate::{
+    v2::{Operation, PathItem, Spec},
+    Method, OperationIndex, Result,
+};
+
+impl PathItem {
+    /// The operation defined for a method, if any. Swagger 2.0 does not support `trace`
+    /// operations.
+    pub fn operation(&self, method: Method) -> Option<&Operation> {
+        match method {
+            Method::Get => self.get.as_ref(),
+            Method::Put => self.put.as_ref(),
+            Method::Post => self.post.as_ref(),
+            Method::Delete => self.delete.as_ref(),
+            Method::Options => self.options.as_ref(),
+            Method::Head => self.head.as_ref(),
+            Method::Patch => self.patch.as_ref(),
+            Method::Trace => None,
+        }
+    }
+
+    /// The mutable operation defined for a method, if any.
+    pub fn operation_mut(&mut self, method: Method) -> Option<&mut Operation> {
+        match method {
+            Method::Get => self.get.as_mut(),
+            Method::Put => self.put.as_mut(),
+            Method::Post => self.post.as_mut(),
+            Method::Delete => self.delete.as_mut(),
+            Method::Options => self.options.as_mut(),
+            Method::Head => self.head.as_mut(),
+            Method::Patch => self.patch.as_mut(),
+            Method::Trace => None,
+        }
+    }
+
+    /// The operations defined on this path item, in the order of `Method::ALL`.
+    pub fn operations(&self) -> impl Iterator<Item = (Method, &Operation)> {
+        Method::ALL.iter().filter_map(move |method| {
+            self.operation(*method)
+                .map(|operation| (*method, operation))
+        })
+    }
+
+    /// The mutable operations defined on this path item, in the order of `Method::ALL`.
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (Method, &mut Operation)> {
+        vec![
+            (Method::Get, self.get.as_mut()),
+            (Method::Put, self.put.as_mut()),
+            (Method::Post, self.post.as_mut()),
+            (Method::Delete, self.delete.as_mut()),
+            (Method::Options, self.options.as_mut()),
+            (Method::Head, self.head.as_mut()),
+            (Method::Patch, self.patch.as_mut()),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.map(|operation| (method, operation)))
+    }
+}
+
+impl Spec {
+    /// The operations of every path, along with their path and method.
+    pub fn operations(&self) -> impl Iterator<Item = (&str, Method, &Operation)> {
+        self.paths.iter().flat_map(|(path, path_item)| {
+            path_item
+                .operations()
+                .map(move |(method, operation)| (path.as_str(), method, operation))
+        })
+    }
+
+    /// The mutable operations of every path, along with their path and method.
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (&str, Method, &mut Operation)> {
+        self.paths.iter_mut().flat_map(|(path, path_item)| {
+            path_item
+                .operations_mut()
+                .map(move |(method, operation)| (path.as_str(), method, operation))
+        })
+    }
+
+    /// Indexes the operations of this spec by their `operationId`. Fails if an
+    /// `operationId` is not unique.
+    pub fn operation_index(&self) -> Result<OperationIndex<'_, Operation>> {
+        OperationIndex::new(
+            self.operations().map(|(path, method, operation)| {
+                (path, method, operation, operation.operation_id.as_deref())
+            }),
+            std::iter::empty(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    #[test]
+    fn iterates_operations() {
+        let mut spec = match from_path("data/v2/petstore-simple.yaml").unwrap() {
+            OpenApi::V2(spec) => spec,
+            _ => panic!("expected a v2 spec"),
+        };
+        let operations = spec
+            .operations()
+            .map(|(path, method, _)| (path, method))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                ("/pets", Method::Get),
+                ("/pets", Method::Post),
+                ("/pets/{id}", Method::Get),
+                ("/pets/{id}", Method::Delete),
+            ]
+        );
+        for (_, _, operation) in spec.operations_mut() {
+            operation.summary = Some("summarized".into());
+        }
+        assert!(spec
+            .operations()
+            .all(|(_, _, operation)| operation.summary.as_deref() == Some("summarized")));
+
+        let index = spec.operation_index().unwrap();
+        assert_eq!(
+            index.operation_id(Method::Delete, "/pets/{id}"),
+            Some("deletePet")
+        );
+        assert_eq!(
+            index.get("findPets").map(|(path, _, _)| path),
+            Some("/pets")
+        );
+    }
+}
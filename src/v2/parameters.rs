@@ -0,0 +1,164 @@
+//! Resolution and merging of the parameters of path items and operations.
+
+use crate::{
+    pointer,
+    v2::{Operation, Parameter, ParameterOrRef, PathItem, Spec},
+    Error, Result,
+};
+
+impl Spec {
+    /// Resolves a parameter, following a reference to `#/parameters`. The result is always
+    /// a `ParameterOrRef::Parameter`.
+    pub fn resolve_parameter(&self, parameter: &ParameterOrRef) -> Result<ParameterOrRef> {
+        match parameter {
+            ParameterOrRef::Ref { ref_path } => ref_path
+                .strip_prefix("#/parameters/")
+                .and_then(|name| {
+                    self.parameters
+                        .as_ref()?
+                        .get(pointer::unescape(name).as_ref())
+                })
+                .cloned()
+                .map(ParameterOrRef::from)
+                .ok_or_else(|| Error::UnresolvedReference(ref_path.clone())),
+            parameter => Ok(parameter.clone()),
+        }
+    }
+
+    /// The parameters which apply to an operation of a path item.
+    ///
+    /// These are the path item's parameters, overridden by the operation's parameters
+    /// with the same name and location, followed by the operation's remaining parameters.
+    /// References are resolved. Fails if either list holds the same parameter twice.
+    pub fn effective_parameters(
+        &self,
+        path_item: &PathItem,
+        operation: &Operation,
+    ) -> Result<Vec<ParameterOrRef>> {
+        let mut parameters = self.unique_parameters(path_item.parameters.as_ref())?;
+        for parameter in self.unique_parameters(operation.parameters.as_ref())? {
+            match parameters
+                .iter_mut()
+                .find(|existing| identity(existing) == identity(&parameter))
+            {
+                Some(existing) => *existing = parameter,
+                None => parameters.push(parameter),
+            }
+        }
+        Ok(parameters)
+    }
+
+    fn unique_parameters(
+        &self,
+        parameters: Option<&Vec<ParameterOrRef>>,
+    ) -> Result<Vec<ParameterOrRef>> {
+        let mut unique: Vec<ParameterOrRef> = Vec::new();
+        for parameter in parameters.into_iter().flatten() {
+            let parameter = self.resolve_parameter(parameter)?;
+            if let Some((name, location)) = identity(&parameter) {
+                if unique
+                    .iter()
+                    .any(|existing| identity(existing) == Some((name, location)))
+                {
+                    return Err(Error::DuplicateParameter {
+                        name: name.to_string(),
+                        location: location.to_string(),
+                    });
+                }
+            }
+            unique.push(parameter);
+        }
+        Ok(unique)
+    }
+}
+
+/// Parameters are identified by their name and location.
+fn identity(parameter: &ParameterOrRef) -> Option<(&str, &str)> {
+    match parameter {
+        ParameterOrRef::Parameter { name, location, .. } => Some((name, location)),
+        ParameterOrRef::Ref { .. } => None,
+    }
+}
+
+impl From<Parameter> for ParameterOrRef {
+    fn from(parameter: Parameter) -> Self {
+        ParameterOrRef::Parameter {
+            name: parameter.name,
+            location: parameter.location,
+            required: parameter.required,
+            schema: parameter.schema,
+            unique_items: parameter.unique_items,
+            param_type: parameter.param_type,
+            format: parameter.format,
+            description: parameter.description,
+            collection_format: None,
+            default: parameter.default,
+            items: parameter.items,
+            additional_properties: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_and_resolves_parameters() {
+        let spec: Spec = serde_yaml::from_str(
+            r#"
+swagger: "2.0"
+info:
+  title: Parameters
+paths:
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/parameters/PetId'
+      - name: verbose
+        in: query
+        type: boolean
+    get:
+      parameters:
+        - name: verbose
+          in: query
+          type: string
+      responses:
+        '200':
+          description: a pet
+    put:
+      parameters:
+        - name: verbose
+          in: query
+          type: string
+        - name: verbose
+          in: query
+          type: string
+      responses:
+        '200':
+          description: a pet
+parameters:
+  PetId:
+    name: petId
+    in: path
+    required: true
+    type: integer
+"#,
+        )
+        .unwrap();
+        let path_item = &spec.paths["/pets/{petId}"];
+        let parameters = spec
+            .effective_parameters(path_item, path_item.get.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(parameters.len(), 2);
+        match &parameters[1] {
+            ParameterOrRef::Parameter { param_type, .. } => {
+                assert_eq!(param_type.as_deref(), Some("string"))
+            }
+            other => panic!("unexpected parameter {:?}", other),
+        }
+        assert!(matches!(
+            spec.effective_parameters(path_item, path_item.put.as_ref().unwrap()),
+            Err(Error::DuplicateParameter { .. })
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+//! Routing of requests to the operations of a spec.
+
+use crate::{
+    router::Routes,
+    v2::{Operation, ParameterOrRef, PathItem, Spec},
+    Method, Result,
+};
+use std::collections::BTreeMap;
+
+/// Maps concrete paths to the path items and operations of a spec.
+///
+/// Paths are expected to include the spec's `basePath`. Where several path templates match
+/// a path, the one with literal segments is preferred over templated ones, segment by
+/// segment, so that `/pets/mine` takes precedence over `/pets/{petId}`. Operations are
+/// looked up on the first of these templates whose path item defines the method.
+pub struct Router<'a> {
+    spec: &'a Spec,
+    routes: Routes<'a, PathItem>,
+}
+
+/// A path item whose template matched a path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathMatch<'a> {
+    /// The path template which matched, relative to the `basePath`.
+    pub path: &'a str,
+    /// The path item of the template.
+    pub path_item: &'a PathItem,
+    /// The percent-decoded values of the template's variables.
+    pub path_parameters: BTreeMap<String, String>,
+}
+
+/// An operation matched by a method and path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route<'a> {
+    spec: &'a Spec,
+    /// The path template which matched, relative to the `basePath`.
+    pub path: &'a str,
+    /// The path item of the template.
+    pub path_item: &'a PathItem,
+    /// The method of the operation.
+    pub method: Method,
+    /// The matched operation.
+    pub operation: &'a Operation,
+    /// The percent-decoded values of the template's variables.
+    pub path_parameters: BTreeMap<String, String>,
+}
+
+impl Spec {
+    /// A router over the paths of this spec.
+    pub fn router(&self) -> Router<'_> {
+        Router::new(self)
+    }
+}
+
+impl<'a> Router<'a> {
+    pub fn new(spec: &'a Spec) -> Self {
+        Router {
+            spec,
+            routes: Routes::new(&spec.paths),
+        }
+    }
+
+    /// Finds the path item matching a path, which must start with the spec's `basePath`.
+    /// Any query string is ignored.
+    pub fn find_path(&self, path: &str) -> Option<PathMatch<'a>> {
+        let path = self.relative(path)?;
+        self.routes
+            .find(path)
+            .map(|(path, path_item, path_parameters)| PathMatch {
+                path,
+                path_item,
+                path_parameters,
+            })
+    }
+
+    /// The part of a path after the spec's `basePath`, without any query string.
+    fn relative<'p>(&self, path: &'p str) -> Option<&'p str> {
+        let path = path.split('?').next().unwrap_or_default();
+        let base_path = self
+            .spec
+            .base_path
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        path.strip_prefix(base_path)
+            .filter(|path| path.starts_with('/'))
+    }
+
+    /// Finds the operation matching a method and path, which must start with the spec's
+    /// `basePath`. Any query string is ignored.
+    pub fn find(&self, method: Method, path: &str) -> Option<Route<'a>> {
+        let path = self.relative(path)?;
+        self.routes
+            .find_all(path)
+            .find_map(|(path, path_item, path_parameters)| {
+                Some(Route {
+                    spec: self.spec,
+                    path,
+                    path_item,
+                    method,
+                    operation: path_item.operation(method)?,
+                    path_parameters,
+                })
+            })
+    }
+}
+
+impl<'a> Route<'a> {
+    /// The parameters which apply to the operation, as given by
+    /// [`Spec::effective_parameters`](struct.Spec.html#method.effective_parameters).
+    pub fn parameters(&self) -> Result<Vec<ParameterOrRef>> {
+        self.spec
+            .effective_parameters(self.path_item, self.operation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    #[test]
+    fn finds_operations_under_base_path() {
+        let spec = match from_path("data/v2/uber.yaml").unwrap() {
+            OpenApi::V2(spec) => spec,
+            _ => panic!("expected a v2 spec"),
+        };
+        let router = spec.router();
+        let route = router.find(Method::Get, "/v1/products?latitude=1").unwrap();
+        assert_eq!(route.path, "/products");
+        let parameters = route.parameters().unwrap();
+        assert_eq!(parameters.len(), 2);
+        assert!(router.find(Method::Get, "/products").is_none());
+        assert!(router.find(Method::Post, "/v1/products").is_none());
+    }
+
+    #[test]
+    fn falls_back_to_templates_defining_the_method() {
+        let mut spec = match from_path("data/v2/uber.yaml").unwrap() {
+            OpenApi::V2(spec) => spec,
+            _ => panic!("expected a v2 spec"),
+        };
+        let mut products = spec.paths["/products"].clone();
+        products.post = products.get.take();
+        let any = std::mem::replace(spec.paths.get_mut("/products").unwrap(), products);
+        spec.paths.insert("/{kind}".into(), any);
+        let router = spec.router();
+        assert_eq!(
+            router.find(Method::Post, "/v1/products").unwrap().path,
+            "/products"
+        );
+        assert_eq!(
+            router.find(Method::Get, "/v1/products").unwrap().path,
+            "/{kind}"
+        );
+    }
+}
@@ -1,23 +1,19 @@
+use crate::Extensions;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 // http://json.schemastore.org/swagger-2.0
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Scheme {
+    #[default]
     Http,
     Https,
     Ws,
     Wss,
 }
 
-impl Default for Scheme {
-    fn default() -> Self {
-        Scheme::Http
-    }
-}
-
 /// top level document
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -59,6 +55,8 @@ pub struct Spec {
     pub security: Option<Vec<BTreeMap<String, Vec<String>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<Vec<ExternalDoc>>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -125,7 +123,6 @@ pub struct License {
     pub url: Option<String>,
 }
 
-/// todo support x-* properties
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct PathItem {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -144,6 +141,8 @@ pub struct PathItem {
     pub head: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parameters: Option<Vec<ParameterOrRef>>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#operation-object
@@ -169,6 +168,8 @@ pub struct Operation {
     pub parameters: Option<Vec<ParameterOrRef>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub security: Option<Vec<SecurityRequirement>>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#securityRequirementObject
@@ -196,7 +197,7 @@ pub struct Parameter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub items: Option<Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    default: Option<serde_json::Value>,
+    pub default: Option<serde_json::Value>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -204,12 +205,15 @@ pub struct Response {
     pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<Schema>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 // todo: support x-* fields
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#parameter-object
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum ParameterOrRef {
     /// both bodyParameter and nonBodyParameter in one for now
     Parameter {
@@ -352,7 +356,7 @@ mod tests {
     fn security_api_deserializes() {
         let json = r#"{"type":"apiKey", "name":"foo", "in": "query"}"#;
         assert_eq!(
-            serde_yaml::from_str::<Security>(&json).unwrap(),
+            serde_yaml::from_str::<Security>(json).unwrap(),
             Security::ApiKey {
                 name: "foo".into(),
                 location: "query".into(),
@@ -379,7 +383,7 @@ mod tests {
     fn security_basic_deserializes() {
         let json = r#"{"type":"basic"}"#;
         assert_eq!(
-            serde_yaml::from_str::<Security>(&json).unwrap(),
+            serde_yaml::from_str::<Security>(json).unwrap(),
             Security::Basic { description: None }
         );
     }
@@ -399,12 +403,12 @@ mod tests {
         let mut scopes = BTreeMap::new();
         scopes.insert("foo".into(), "bar".into());
         assert_eq!(
-            serde_yaml::from_str::<Security>(&json).unwrap(),
+            serde_yaml::from_str::<Security>(json).unwrap(),
             Security::Oauth2 {
                 flow: Flow::Implicit,
                 authorization_url: "foo/bar".into(),
                 token_url: None,
-                scopes: scopes,
+                scopes,
                 description: None,
             }
         );
@@ -421,7 +425,7 @@ mod tests {
                 flow: Flow::Implicit,
                 authorization_url: "foo/bar".into(),
                 token_url: None,
-                scopes: scopes,
+                scopes,
                 description: None,
             })
             .unwrap()
@@ -432,7 +436,7 @@ mod tests {
     fn parameter_or_ref_deserializes_ref() {
         let json = r#"{"$ref":"foo/bar"}"#;
         assert_eq!(
-            serde_yaml::from_str::<ParameterOrRef>(&json).unwrap(),
+            serde_yaml::from_str::<ParameterOrRef>(json).unwrap(),
             ParameterOrRef::Ref {
                 ref_path: "foo/bar".into()
             }
@@ -0,0 +1,372 @@
+//! Checks of the rules of the specification which parsing does not enforce.
+
+use crate::{
+    pointer,
+    v2::{Operation, ParameterOrRef, PathItem, Schema, Spec},
+    Error, Problem, Result,
+};
+use regex::Regex;
+
+/// The pattern the JSON schema of Swagger 2.0 documents for `host`.
+const HOST: &str = r"^[^{}/ :\\]+(?::\d+)?$";
+
+/// The media types under which `file` parameters may be sent.
+const FORM_MEDIA_TYPES: &[&str] = &["multipart/form-data", "application/x-www-form-urlencoded"];
+
+impl Spec {
+    /// Every rule of the specification this spec breaks which parsing does not enforce:
+    ///
+    /// * `swagger` is exactly `2.0`
+    /// * `host` matches `^[^{}/ :\\]+(?::\d+)?$`, so has neither a scheme nor a path
+    /// * operations have at most one `body` parameter, and not both `body` and `formData`
+    ///   parameters
+    /// * `file` parameters are `formData` parameters of operations consuming
+    ///   `multipart/form-data` or `application/x-www-form-urlencoded`
+    /// * `collectionFormat: multi` is only used by `query` and `formData` parameters
+    /// * local references to `#/definitions` and `#/parameters` resolve
+    pub fn problems(&self) -> Vec<Problem> {
+        let mut rules = Rules {
+            spec: self,
+            problems: Vec::new(),
+        };
+        if self.swagger != "2.0" {
+            rules.problem("/swagger", format!("`{}` is not `2.0`", self.swagger));
+        }
+        if let Some(host) = &self.host {
+            if !Regex::new(HOST).unwrap().is_match(host) {
+                rules.problem("/host", format!("`{}` does not match `{}`", host, HOST));
+            }
+        }
+        rules.paths();
+        rules.definitions();
+        rules.problems
+    }
+
+    /// Checks the rules listed by [`problems`](#method.problems), failing with
+    /// [`Error::InvalidSpec`] if any are broken.
+    pub fn validate(&self) -> Result<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSpec(problems))
+        }
+    }
+}
+
+struct Rules<'a> {
+    spec: &'a Spec,
+    problems: Vec<Problem>,
+}
+
+impl Rules<'_> {
+    fn problem(&mut self, pointer: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem::new(pointer, message));
+    }
+
+    fn paths(&mut self) {
+        for (path, path_item) in &self.spec.paths {
+            let pointer = pointer::join("/paths", path);
+            self.parameters(&pointer, path_item.parameters.as_ref());
+            for (method, operation) in path_item.operations() {
+                let pointer = pointer::join(&pointer, method.as_str());
+                self.parameters(&pointer, operation.parameters.as_ref());
+                self.operation(&pointer, path_item, operation);
+                for (status, response) in &operation.responses {
+                    if let Some(schema) = &response.schema {
+                        let pointer = pointer::join(&pointer::join(&pointer, "responses"), status);
+                        self.schema(&pointer::join(&pointer, "schema"), schema);
+                    }
+                }
+            }
+        }
+    }
+
+    fn definitions(&mut self) {
+        let spec = self.spec;
+        for (name, schema) in spec.definitions.iter().flatten() {
+            self.schema(&pointer::join("/definitions", name), schema);
+        }
+        for (name, parameter) in spec.parameters.iter().flatten() {
+            let pointer = pointer::join("/parameters", name);
+            for (keyword, schema) in [("schema", &parameter.schema), ("items", &parameter.items)] {
+                if let Some(schema) = schema {
+                    self.schema(&pointer::join(&pointer, keyword), schema);
+                }
+            }
+        }
+        for (name, response) in spec.responses.iter().flatten() {
+            if let Some(schema) = &response.schema {
+                let pointer = pointer::join("/responses", name);
+                self.schema(&pointer::join(&pointer, "schema"), schema);
+            }
+        }
+    }
+
+    /// Checks a list of parameters on their own, and the references and schemas they hold.
+    fn parameters(&mut self, pointer: &str, parameters: Option<&Vec<ParameterOrRef>>) {
+        let pointer = pointer::join(pointer, "parameters");
+        for (index, parameter) in parameters.into_iter().flatten().enumerate() {
+            let pointer = pointer::join(&pointer, &index.to_string());
+            let resolved = match self.spec.resolve_parameter(parameter) {
+                Ok(resolved) => resolved,
+                Err(error) => {
+                    self.problem(pointer::join(&pointer, "$ref"), error.to_string());
+                    continue;
+                }
+            };
+            if let ParameterOrRef::Parameter {
+                location,
+                param_type,
+                collection_format,
+                ..
+            } = &resolved
+            {
+                if param_type.as_deref() == Some("file") && location != "formData" {
+                    self.problem(
+                        pointer::join(&pointer, "type"),
+                        format!(
+                            "`file` parameters must be in `formData`, not `{}`",
+                            location
+                        ),
+                    );
+                }
+                if collection_format.as_deref() == Some("multi")
+                    && location != "query"
+                    && location != "formData"
+                {
+                    self.problem(
+                        pointer::join(&pointer, "collectionFormat"),
+                        format!(
+                            "`multi` is only valid for `query` and `formData` parameters, not `{}`",
+                            location
+                        ),
+                    );
+                }
+            }
+            if let ParameterOrRef::Parameter {
+                schema,
+                items,
+                additional_properties,
+                ..
+            } = parameter
+            {
+                for (keyword, schema) in [
+                    ("schema", schema),
+                    ("items", items),
+                    ("additionalProperties", additional_properties),
+                ] {
+                    if let Some(schema) = schema {
+                        self.schema(&pointer::join(&pointer, keyword), schema);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the parameters which apply to an operation together.
+    fn operation(&mut self, pointer: &str, path_item: &PathItem, operation: &Operation) {
+        let parameters = match self.spec.effective_parameters(path_item, operation) {
+            Ok(parameters) => parameters,
+            // unresolved references are reported where they are found
+            Err(Error::UnresolvedReference(_)) => return,
+            Err(error) => return self.problem(pointer, error.to_string()),
+        };
+        let (mut body, mut form, mut file) = (0, false, false);
+        for parameter in &parameters {
+            if let ParameterOrRef::Parameter {
+                location,
+                param_type,
+                ..
+            } = parameter
+            {
+                body += (location == "body") as usize;
+                form |= location == "formData";
+                file |= param_type.as_deref() == Some("file");
+            }
+        }
+        if body > 1 {
+            self.problem(
+                pointer,
+                format!("the operation has {} body parameters", body),
+            );
+        }
+        if body > 0 && form {
+            self.problem(
+                pointer,
+                "the operation has both body and formData parameters",
+            );
+        }
+        let consumes = operation.consumes.as_ref().or(self.spec.consumes.as_ref());
+        let consumes_form = consumes.is_some_and(|consumes| {
+            consumes.iter().any(|media_type| {
+                FORM_MEDIA_TYPES.contains(&media_type.split(';').next().unwrap_or("").trim())
+            })
+        });
+        if file && !consumes_form {
+            self.problem(
+                pointer,
+                "file parameters require the operation to consume \
+                 `multipart/form-data` or `application/x-www-form-urlencoded`",
+            );
+        }
+    }
+
+    /// Checks that the local references of a schema and its subschemas resolve.
+    fn schema(&mut self, pointer: &str, schema: &Schema) {
+        if let Some(ref_path) = &schema.ref_path {
+            let resolves = match ref_path.strip_prefix("#/definitions/") {
+                Some(name) => self.spec.definitions.as_ref().is_some_and(|definitions| {
+                    definitions.contains_key(pointer::unescape(name).as_ref())
+                }),
+                None => !ref_path.starts_with('#'),
+            };
+            if !resolves {
+                self.problem(
+                    pointer::join(pointer, "$ref"),
+                    Error::UnresolvedReference(ref_path.clone()).to_string(),
+                );
+            }
+        }
+        if let Some(items) = &schema.items {
+            self.schema(&pointer::join(pointer, "items"), items);
+        }
+        for (name, property) in schema.properties.iter().flatten() {
+            self.schema(
+                &pointer::join(&pointer::join(pointer, "properties"), name),
+                property,
+            );
+        }
+        for (index, schema) in schema.all_of.iter().flatten().enumerate() {
+            self.schema(
+                &pointer::join(&pointer::join(pointer, "allOf"), &index.to_string()),
+                schema,
+            );
+        }
+        let additional = schema
+            .other
+            .get("additionalProperties")
+            .filter(|value| value.is_object())
+            .and_then(|value| serde_json::from_value::<Schema>(value.clone()).ok());
+        if let Some(additional) = additional {
+            self.schema(&pointer::join(pointer, "additionalProperties"), &additional);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    #[test]
+    fn accepts_sample_specs() {
+        for sample in &[
+            "k8s.json",
+            "petstore-simple.yaml",
+            "petstore_minimal.yaml",
+            "rocks.yaml",
+            "uber.yaml",
+        ] {
+            match from_path(format!("data/v2/{}", sample)).unwrap() {
+                OpenApi::V2(spec) => assert_eq!(spec.problems(), vec![], "{}", sample),
+                _ => panic!("expected a v2 spec"),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_broken_rules() {
+        let spec: Spec = serde_yaml::from_str(
+            r##"
+swagger: "2"
+info:
+  title: Problems
+host: https://example.com/api
+consumes:
+  - application/json
+paths:
+  /pets:
+    post:
+      parameters:
+        - name: pet
+          in: body
+          schema:
+            $ref: "#/definitions/Pet"
+        - name: owner
+          in: body
+          schema:
+            type: object
+        - name: photo
+          in: formData
+          type: file
+      responses:
+        '200':
+          description: Pets
+          schema:
+            type: array
+            items:
+              $ref: "#/definitions/Pets"
+  /photos:
+    get:
+      parameters:
+        - name: ids
+          in: header
+          type: array
+          collectionFormat: multi
+          items:
+            type: string
+        - name: photo
+          in: query
+          type: file
+      responses:
+        '200':
+          description: Photos
+  /owners:
+    parameters:
+      - $ref: "#/parameters/Missing"
+    get:
+      responses:
+        '200':
+          description: Owners
+definitions:
+  Pet:
+    type: object
+    additionalProperties:
+      $ref: "#/definitions/Tag"
+"##,
+        )
+        .unwrap();
+        let problems = spec
+            .problems()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                "`/swagger`: `2` is not `2.0`",
+                "`/host`: `https://example.com/api` does not match `^[^{}/ :\\\\]+(?::\\d+)?$`",
+                "`/paths/~1owners/parameters/0/$ref`: \
+                 Unresolved reference (#/parameters/Missing)",
+                "`/paths/~1pets/post`: the operation has 2 body parameters",
+                "`/paths/~1pets/post`: the operation has both body and formData parameters",
+                "`/paths/~1pets/post`: file parameters require the operation to consume \
+                 `multipart/form-data` or `application/x-www-form-urlencoded`",
+                "`/paths/~1pets/post/responses/200/schema/items/$ref`: \
+                 Unresolved reference (#/definitions/Pets)",
+                "`/paths/~1photos/get/parameters/0/collectionFormat`: \
+                 `multi` is only valid for `query` and `formData` parameters, not `header`",
+                "`/paths/~1photos/get/parameters/1/type`: \
+                 `file` parameters must be in `formData`, not `query`",
+                "`/paths/~1photos/get`: file parameters require the operation to consume \
+                 `multipart/form-data` or `application/x-www-form-urlencoded`",
+                "`/definitions/Pet/additionalProperties/$ref`: \
+                 Unresolved reference (#/definitions/Tag)",
+            ]
+        );
+        assert!(
+            matches!(spec.validate(), Err(Error::InvalidSpec(problems)) if problems.len() == 11)
+        );
+    }
+}
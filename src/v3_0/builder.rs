@@ -0,0 +1,673 @@
+//! Fluent builders for constructing specs programmatically.
+//!
+//! Each builder collects values through chained calls and checks the document it produces
+//! when `build` is called.
+//!
+//! ```
+//! use openapi::{
+//!     v3_0::{OperationBuilder, ParameterBuilder, ResponseBuilder, SchemaBuilder, SpecBuilder},
+//!     Method,
+//! };
+//!
+//! let spec = SpecBuilder::new()
+//!     .title("Pets")
+//!     .version("1.0.0")
+//!     .schema(
+//!         "Pet",
+//!         SchemaBuilder::object()
+//!             .required_property("id", SchemaBuilder::integer())
+//!             .property("name", SchemaBuilder::string()),
+//!     )
+//!     .operation(
+//!         "/pets/{petId}",
+//!         Method::Get,
+//!         OperationBuilder::new()
+//!             .operation_id("showPetById")
+//!             .parameter(ParameterBuilder::path("petId").schema(SchemaBuilder::integer()))
+//!             .response(
+//!                 "200",
+//!                 ResponseBuilder::new()
+//!                     .description("A pet")
+//!                     .json(SchemaBuilder::component("Pet")),
+//!             ),
+//!     )
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(spec.operation_index().unwrap().len(), 1);
+//! ```
+
+use crate::{
+    pointer, template,
+    v3_0::{
+        visit::Visit, Components, Header, Info, MediaType, ObjectOrReference, Operation, Parameter,
+        ParameterStyle, RequestBody, Response, Schema, Server, Spec, Tag,
+    },
+    Error, Method, Result,
+};
+use std::collections::BTreeMap;
+
+/// The `openapi` version of built specs.
+const OPENAPI_VERSION: &str = "3.0.1";
+
+const JSON: &str = "application/json";
+
+/// Builds a [`Spec`].
+///
+/// `build` fails if the title or version is missing, if an operation is defined twice or
+/// fails to build, if `operationId`s are not unique, if a path template references a
+/// parameter an operation does not declare, or if a local `$ref`, such as one made with
+/// [`SchemaBuilder::component`], does not resolve.
+#[derive(Clone, Debug, Default)]
+pub struct SpecBuilder {
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    servers: Vec<Server>,
+    tags: Vec<Tag>,
+    operations: Vec<(String, Method, OperationBuilder)>,
+    schemas: BTreeMap<String, Schema>,
+}
+
+impl SpecBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(Server {
+            url: url.into(),
+            ..Server::default()
+        });
+        self
+    }
+
+    pub fn tag(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.tags.push(Tag {
+            name: name.into(),
+            description: Some(description.into()),
+            ..Tag::default()
+        });
+        self
+    }
+
+    /// Adds an operation for a method on a path.
+    pub fn operation(
+        mut self,
+        path: impl Into<String>,
+        method: Method,
+        operation: OperationBuilder,
+    ) -> Self {
+        self.operations.push((path.into(), method, operation));
+        self
+    }
+
+    /// Adds a schema to `components.schemas`, where [`SchemaBuilder::component`] refers to it.
+    pub fn schema(mut self, name: impl Into<String>, schema: SchemaBuilder) -> Self {
+        self.schemas.insert(name.into(), schema.build());
+        self
+    }
+
+    pub fn build(self) -> Result<Spec> {
+        let mut spec = Spec {
+            openapi: OPENAPI_VERSION.into(),
+            info: Info {
+                title: self.title.ok_or(Error::MissingField {
+                    object: "info",
+                    field: "title",
+                })?,
+                version: self.version.ok_or(Error::MissingField {
+                    object: "info",
+                    field: "version",
+                })?,
+                description: self.description,
+                ..Info::default()
+            },
+            servers: non_empty(self.servers),
+            tags: non_empty(self.tags),
+            ..Spec::default()
+        };
+        if !self.schemas.is_empty() {
+            spec.components = Some(Components {
+                schemas: Some(
+                    self.schemas
+                        .into_iter()
+                        .map(|(name, schema)| (name, ObjectOrReference::Object(schema)))
+                        .collect(),
+                ),
+                ..Components::default()
+            });
+        }
+        for (path, method, operation) in self.operations {
+            let slot = spec
+                .paths
+                .entry(path.clone())
+                .or_default()
+                .operation_slot(method);
+            if slot.is_some() {
+                return Err(Error::DuplicateOperation { method, path });
+            }
+            *slot = Some(operation.build()?);
+        }
+        spec.operation_index()?;
+        for (path, path_item) in &spec.paths {
+            let parts =
+                template::parse(path).ok_or_else(|| Error::MalformedTemplate(path.clone()))?;
+            for (method, operation) in path_item.operations() {
+                let parameters = spec.effective_parameters(path_item, operation)?;
+                if let Some(name) = template::variables(&parts).into_iter().find(|name| {
+                    !parameters
+                        .iter()
+                        .any(|parameter| parameter.location == "path" && parameter.name == *name)
+                }) {
+                    return Err(Error::UndeclaredPathParameter {
+                        method,
+                        path: path.clone(),
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+        let mut references = References(Vec::new());
+        references.visit_spec(&spec);
+        if let Some(reference) = references
+            .0
+            .into_iter()
+            .find(|reference| spec.resolve_pointer(reference).is_none())
+        {
+            return Err(Error::UnresolvedReference(reference.to_string()));
+        }
+        Ok(spec)
+    }
+}
+
+/// Collects the local `$ref`s of a spec.
+struct References<'a>(Vec<&'a str>);
+
+impl<'a> Visit<'a> for References<'a> {
+    fn visit_reference(&mut self, _pointer: &str, reference: &'a str) {
+        if reference.starts_with('#') {
+            self.0.push(reference);
+        }
+    }
+}
+
+/// Builds an [`Operation`].
+///
+/// `build` fails if no response is defined, or if a parameter fails to build.
+#[derive(Clone, Debug, Default)]
+pub struct OperationBuilder {
+    operation: Operation,
+    parameters: Vec<ParameterBuilder>,
+    responses: Vec<(String, ResponseBuilder)>,
+}
+
+impl OperationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation.operation_id = Some(operation_id.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.operation.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.operation.description = Some(description.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.operation
+            .tags
+            .get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.operation.deprecated = Some(true);
+        self
+    }
+
+    pub fn parameter(mut self, parameter: ParameterBuilder) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sets a required request body with `application/json` content.
+    pub fn json_body(mut self, schema: SchemaBuilder) -> Self {
+        self.operation.request_body = Some(ObjectOrReference::Object(RequestBody {
+            content: json_content(schema),
+            required: Some(true),
+            ..RequestBody::default()
+        }));
+        self
+    }
+
+    /// Adds the response for a status code, such as `200` or `default`.
+    pub fn response(mut self, status: impl Into<String>, response: ResponseBuilder) -> Self {
+        self.responses.push((status.into(), response));
+        self
+    }
+
+    pub fn build(self) -> Result<Operation> {
+        let mut operation = self.operation;
+        if self.responses.is_empty() {
+            return Err(Error::MissingField {
+                object: "operation",
+                field: "responses",
+            });
+        }
+        for (status, response) in self.responses {
+            operation.responses.insert(status, response.build()?);
+        }
+        if !self.parameters.is_empty() {
+            operation.parameters = Some(
+                self.parameters
+                    .into_iter()
+                    .map(|parameter| parameter.build().map(ObjectOrReference::Object))
+                    .collect::<Result<_>>()?,
+            );
+        }
+        Ok(operation)
+    }
+}
+
+/// Builds a [`Parameter`].
+///
+/// Path parameters are always required. `build` fails if the style is not supported for
+/// the parameter's location.
+#[derive(Clone, Debug)]
+pub struct ParameterBuilder {
+    parameter: Parameter,
+}
+
+impl ParameterBuilder {
+    pub fn new(name: impl Into<String>, location: impl Into<String>) -> Self {
+        let location = location.into();
+        ParameterBuilder {
+            parameter: Parameter {
+                name: name.into(),
+                required: if location == "path" { Some(true) } else { None },
+                location,
+                ..Parameter::default()
+            },
+        }
+    }
+
+    pub fn path(name: impl Into<String>) -> Self {
+        Self::new(name, "path")
+    }
+
+    pub fn query(name: impl Into<String>) -> Self {
+        Self::new(name, "query")
+    }
+
+    pub fn header(name: impl Into<String>) -> Self {
+        Self::new(name, "header")
+    }
+
+    pub fn cookie(name: impl Into<String>) -> Self {
+        Self::new(name, "cookie")
+    }
+
+    pub fn required(mut self) -> Self {
+        self.parameter.required = Some(true);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.parameter.description = Some(description.into());
+        self
+    }
+
+    pub fn schema(mut self, schema: SchemaBuilder) -> Self {
+        self.parameter.schema = Some(schema.build());
+        self
+    }
+
+    pub fn style(mut self, style: ParameterStyle) -> Self {
+        self.parameter.style = Some(style);
+        self
+    }
+
+    pub fn explode(mut self, explode: bool) -> Self {
+        self.parameter.explode = Some(explode);
+        self
+    }
+
+    pub fn build(self) -> Result<Parameter> {
+        let parameter = self.parameter;
+        if let Some(style) = parameter.style {
+            if !style.supports(&parameter.location) {
+                return Err(Error::UnsupportedParameterStyle {
+                    style,
+                    location: parameter.location,
+                });
+            }
+        }
+        Ok(parameter)
+    }
+}
+
+/// Builds a [`Response`].
+///
+/// `build` fails if the description is missing.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseBuilder {
+    response: Response,
+}
+
+impl ResponseBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.response.description = Some(description.into());
+        self
+    }
+
+    /// Adds content of a media type, such as `text/plain`, described by a schema.
+    pub fn content(mut self, media_type: impl Into<String>, schema: SchemaBuilder) -> Self {
+        self.response
+            .content
+            .get_or_insert_with(BTreeMap::new)
+            .insert(media_type.into(), media_type_of(schema));
+        self
+    }
+
+    /// Adds `application/json` content described by a schema.
+    pub fn json(self, schema: SchemaBuilder) -> Self {
+        self.content(JSON, schema)
+    }
+
+    pub fn header(mut self, name: impl Into<String>, schema: SchemaBuilder) -> Self {
+        self.response
+            .headers
+            .get_or_insert_with(BTreeMap::new)
+            .insert(
+                name.into(),
+                ObjectOrReference::Object(Header {
+                    schema: Some(schema.build()),
+                    ..Header::default()
+                }),
+            );
+        self
+    }
+
+    pub fn build(self) -> Result<Response> {
+        if self.response.description.is_none() {
+            return Err(Error::MissingField {
+                object: "response",
+                field: "description",
+            });
+        }
+        Ok(self.response)
+    }
+}
+
+/// Builds a [`Schema`].
+#[derive(Clone, Debug, Default)]
+pub struct SchemaBuilder {
+    schema: Schema,
+}
+
+impl SchemaBuilder {
+    /// A schema of the given `type`.
+    pub fn new(schema_type: impl Into<String>) -> Self {
+        SchemaBuilder {
+            schema: Schema {
+                schema_type: Some(schema_type.into()),
+                ..Schema::default()
+            },
+        }
+    }
+
+    pub fn string() -> Self {
+        Self::new("string")
+    }
+
+    pub fn integer() -> Self {
+        Self::new("integer")
+    }
+
+    pub fn number() -> Self {
+        Self::new("number")
+    }
+
+    pub fn boolean() -> Self {
+        Self::new("boolean")
+    }
+
+    pub fn object() -> Self {
+        Self::new("object")
+    }
+
+    pub fn array(items: SchemaBuilder) -> Self {
+        let mut builder = Self::new("array");
+        builder.schema.items = Some(Box::new(items.build()));
+        builder
+    }
+
+    /// A `$ref` to a schema.
+    pub fn reference(reference: impl Into<String>) -> Self {
+        SchemaBuilder {
+            schema: Schema {
+                ref_path: Some(reference.into()),
+                ..Schema::default()
+            },
+        }
+    }
+
+    /// A `$ref` to a schema of `components.schemas`.
+    pub fn component(name: &str) -> Self {
+        Self::reference(format!("#{}", pointer::join("/components/schemas", name)))
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.schema.format = Some(format.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.schema.description = Some(description.into());
+        self
+    }
+
+    pub fn nullable(mut self) -> Self {
+        self.schema.nullable = Some(true);
+        self
+    }
+
+    pub fn enum_values<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.schema.enum_values = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn property(mut self, name: impl Into<String>, schema: SchemaBuilder) -> Self {
+        self.schema
+            .properties
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.into(), schema.build());
+        self
+    }
+
+    /// Adds a property and lists it in `required`.
+    pub fn required_property(mut self, name: impl Into<String>, schema: SchemaBuilder) -> Self {
+        let name = name.into();
+        self.schema
+            .required
+            .get_or_insert_with(Vec::new)
+            .push(name.clone());
+        self.property(name, schema)
+    }
+
+    pub fn build(self) -> Schema {
+        self.schema
+    }
+}
+
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+fn media_type_of(schema: SchemaBuilder) -> MediaType {
+    MediaType {
+        schema: Some(ObjectOrReference::Object(schema.build())),
+        ..MediaType::default()
+    }
+}
+
+fn json_content(schema: SchemaBuilder) -> BTreeMap<String, MediaType> {
+    let mut content = BTreeMap::new();
+    content.insert(JSON.to_string(), media_type_of(schema));
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok() -> ResponseBuilder {
+        ResponseBuilder::new().description("OK")
+    }
+
+    #[test]
+    fn builds_specs() {
+        let spec = SpecBuilder::new()
+            .title("Pets")
+            .version("1.0.0")
+            .server("/v1")
+            .schema(
+                "Pet",
+                SchemaBuilder::object().required_property("name", SchemaBuilder::string()),
+            )
+            .operation(
+                "/pets",
+                Method::Post,
+                OperationBuilder::new()
+                    .operation_id("createPet")
+                    .json_body(SchemaBuilder::component("Pet"))
+                    .response("201", ok().json(SchemaBuilder::component("Pet"))),
+            )
+            .build()
+            .unwrap();
+        let operation = spec.paths["/pets"].post.as_ref().unwrap();
+        let expected: Operation = serde_yaml::from_str(
+            r##"
+operationId: createPet
+requestBody:
+  required: true
+  content:
+    application/json:
+      schema:
+        $ref: "#/components/schemas/Pet"
+responses:
+  "201":
+    description: OK
+    content:
+      application/json:
+        schema:
+          $ref: "#/components/schemas/Pet"
+"##,
+        )
+        .unwrap();
+        assert_eq!(operation, &expected);
+        assert!(spec.validate_version().is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_fields() {
+        assert!(matches!(
+            SpecBuilder::new().version("1").build(),
+            Err(Error::MissingField {
+                object: "info",
+                field: "title"
+            })
+        ));
+        assert!(matches!(
+            OperationBuilder::new().build(),
+            Err(Error::MissingField {
+                object: "operation",
+                field: "responses"
+            })
+        ));
+        assert!(matches!(
+            OperationBuilder::new()
+                .response("200", ResponseBuilder::new())
+                .build(),
+            Err(Error::MissingField {
+                object: "response",
+                field: "description"
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        let spec = || SpecBuilder::new().title("Pets").version("1");
+        let get = || OperationBuilder::new().response("200", ok());
+        assert!(matches!(
+            spec()
+                .operation("/pets/{petId}", Method::Get, get())
+                .build(),
+            Err(Error::UndeclaredPathParameter { .. })
+        ));
+        assert!(matches!(
+            spec()
+                .operation("/pets", Method::Get, get())
+                .operation("/pets", Method::Get, get())
+                .build(),
+            Err(Error::DuplicateOperation {
+                method: Method::Get,
+                ..
+            })
+        ));
+        assert!(matches!(
+            spec()
+                .operation(
+                    "/pets",
+                    Method::Get,
+                    OperationBuilder::new()
+                        .response("200", ok().json(SchemaBuilder::component("Pet"))),
+                )
+                .build(),
+            Err(Error::UnresolvedReference(reference)) if reference == "#/components/schemas/Pet"
+        ));
+        assert!(matches!(
+            ParameterBuilder::header("X-Id")
+                .style(ParameterStyle::Form)
+                .build(),
+            Err(Error::UnsupportedParameterStyle { .. })
+        ));
+    }
+}
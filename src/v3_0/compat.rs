@@ -0,0 +1,1385 @@
+//! Classification of the changes between two versions of a spec by whether they can break
+//! existing clients.
+
+use crate::{
+    pointer, template,
+    v3_0::{
+        parameters::{resolve, MAX_REFERENCE_DEPTH},
+        validation::Direction,
+        BooleanObjectOrReference, Flows, MediaType, ObjectOrReference, Operation, Parameter,
+        PathItem, RequestBody, Schema, SecurityScheme, Spec,
+    },
+    Error, Method, Result,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, ptr,
+};
+
+const SCHEMAS: &str = "#/components/schemas/";
+const SECURITY_SCHEMES: &str = "#/components/securitySchemes/";
+
+/// Whether a change can break clients written against the older spec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    NonBreaking,
+    Breaking,
+}
+
+/// A change between two versions of a spec.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub compatibility: Compatibility,
+    /// The JSON pointer of the changed node, in the older spec if it was removed and in the
+    /// newer one otherwise.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+}
+
+impl Spec {
+    /// The changes from this spec to a newer version of it which matter to clients, each
+    /// classified as breaking or not.
+    ///
+    /// Operations are matched by method and path, ignoring the names of path parameters.
+    /// Breaking changes include removed operations, new required parameters and request
+    /// properties, request bodies no longer accepting a media type, responses no longer
+    /// returning one, and removed security schemes, OAuth flows and scopes. Schemas,
+    /// including their `allOf`, `oneOf`, `anyOf`, `not` and `additionalProperties` schemas,
+    /// are compared by the way values travel: narrowing the values a request schema
+    /// accepts, by changing its type or format, restricting its enum, tightening its bounds
+    /// or making it non-nullable, is breaking, as is widening the values a response schema
+    /// allows or removing one of its properties.
+    ///
+    /// Fails if either spec holds an unresolvable reference.
+    pub fn changes_to(&self, newer: &Spec) -> Result<Vec<Change>> {
+        let mut compare = Compare {
+            old: self,
+            new: newer,
+            compared: HashSet::new(),
+            changes: Vec::new(),
+        };
+        compare.paths()?;
+        compare.security_schemes();
+        Ok(compare.changes)
+    }
+}
+
+struct Compare<'a> {
+    old: &'a Spec,
+    new: &'a Spec,
+    /// The pairs of schemas already compared, so shared and recursive schemas are compared
+    /// once.
+    compared: HashSet<(String, String, Direction)>,
+    changes: Vec<Change>,
+}
+
+/// An operation along with where it is found.
+struct Located<'a> {
+    path: &'a str,
+    method: Method,
+    path_item: &'a PathItem,
+    operation: &'a Operation,
+}
+
+impl Located<'_> {
+    fn pointer(&self) -> String {
+        pointer::join(&pointer::join("/paths", self.path), self.method.as_str())
+    }
+}
+
+impl<'a> Compare<'a> {
+    fn change(
+        &mut self,
+        compatibility: Compatibility,
+        pointer: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.changes.push(Change {
+            compatibility,
+            pointer: pointer.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Records a change which narrows the values a schema allows, which breaks requests.
+    fn narrowed(&mut self, direction: Direction, pointer: &str, message: String) {
+        let compatibility = match direction {
+            Direction::Request => Compatibility::Breaking,
+            Direction::Response => Compatibility::NonBreaking,
+        };
+        self.change(compatibility, pointer, message)
+    }
+
+    /// Records a change which widens the values a schema allows, which breaks responses.
+    fn widened(&mut self, direction: Direction, pointer: &str, message: String) {
+        let compatibility = match direction {
+            Direction::Request => Compatibility::NonBreaking,
+            Direction::Response => Compatibility::Breaking,
+        };
+        self.change(compatibility, pointer, message)
+    }
+
+    fn paths(&mut self) -> Result<()> {
+        let old = operations(self.old);
+        let new = operations(self.new);
+        for (key, old) in &old {
+            match new.get(key) {
+                Some(new) => self.operation(old, new)?,
+                None => self.change(
+                    Compatibility::Breaking,
+                    old.pointer(),
+                    format!("operation `{} {}` was removed", old.method, old.path),
+                ),
+            }
+        }
+        for (key, new) in &new {
+            if !old.contains_key(key) {
+                self.change(
+                    Compatibility::NonBreaking,
+                    new.pointer(),
+                    format!("operation `{} {}` was added", new.method, new.path),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn operation(&mut self, old: &Located<'a>, new: &Located<'a>) -> Result<()> {
+        self.parameters(old, new)?;
+        self.request_body(old, new)?;
+        let pointer = pointer::join(&new.pointer(), "responses");
+        for (status, old_response) in &old.operation.responses {
+            let new_response = match new.operation.responses.get(status) {
+                Some(response) => response,
+                None => {
+                    self.change(
+                        Compatibility::NonBreaking,
+                        pointer::join(&pointer::join(&old.pointer(), "responses"), status),
+                        format!("the `{}` response was removed", status),
+                    );
+                    continue;
+                }
+            };
+            let old_content = match &old_response.content {
+                Some(content) => content,
+                None => continue,
+            };
+            let old_pointer = pointer::join(&pointer::join(&old.pointer(), "responses"), status);
+            let new_pointer = pointer::join(&pointer::join(&pointer, status), "content");
+            for media_type in old_content.keys() {
+                if !new_response
+                    .content
+                    .as_ref()
+                    .is_some_and(|content| content.contains_key(media_type))
+                {
+                    self.change(
+                        Compatibility::Breaking,
+                        &new_pointer,
+                        format!(
+                            "the `{}` response no longer returns `{}`",
+                            status, media_type
+                        ),
+                    );
+                }
+            }
+            if let Some(new_content) = &new_response.content {
+                self.content(
+                    (&pointer::join(&old_pointer, "content"), old_content),
+                    (&new_pointer, new_content),
+                    Direction::Response,
+                )?;
+            }
+        }
+        for status in new.operation.responses.keys() {
+            if !old.operation.responses.contains_key(status) {
+                self.change(
+                    Compatibility::NonBreaking,
+                    pointer::join(&pointer, status),
+                    format!("the `{}` response was added", status),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn parameters(&mut self, old: &Located<'a>, new: &Located<'a>) -> Result<()> {
+        let old_parameters = self
+            .old
+            .effective_parameters(old.path_item, old.operation)?;
+        let new_parameters = self
+            .new
+            .effective_parameters(new.path_item, new.operation)?;
+        for &old_parameter in &old_parameters {
+            let new_parameter = new_parameters
+                .iter()
+                .copied()
+                .find(|&parameter| same_parameter((old, old_parameter), (new, parameter)));
+            let new_parameter = match new_parameter {
+                Some(parameter) => parameter,
+                None => {
+                    let pointer = parameter_pointer(self.old, old, old_parameter);
+                    self.change(
+                        Compatibility::NonBreaking,
+                        pointer,
+                        format!("{} was removed", describe(old_parameter)),
+                    );
+                    continue;
+                }
+            };
+            let pointer = parameter_pointer(self.new, new, new_parameter);
+            match (is_required(old_parameter), is_required(new_parameter)) {
+                (false, true) => self.change(
+                    Compatibility::Breaking,
+                    &pointer,
+                    format!("{} is now required", describe(new_parameter)),
+                ),
+                (true, false) => self.change(
+                    Compatibility::NonBreaking,
+                    &pointer,
+                    format!("{} is no longer required", describe(new_parameter)),
+                ),
+                _ => {}
+            }
+            if let (Some(old_schema), Some(new_schema)) =
+                (&old_parameter.schema, &new_parameter.schema)
+            {
+                let old_pointer =
+                    pointer::join(&parameter_pointer(self.old, old, old_parameter), "schema");
+                let new_pointer = pointer::join(&pointer, "schema");
+                self.schema(
+                    (&old_pointer, old_schema),
+                    (&new_pointer, new_schema),
+                    Direction::Request,
+                )?;
+            }
+        }
+        for &new_parameter in &new_parameters {
+            let existed = old_parameters
+                .iter()
+                .any(|&parameter| same_parameter((old, parameter), (new, new_parameter)));
+            if !existed {
+                let (compatibility, kind) = if is_required(new_parameter) {
+                    (Compatibility::Breaking, "required")
+                } else {
+                    (Compatibility::NonBreaking, "optional")
+                };
+                self.change(
+                    compatibility,
+                    parameter_pointer(self.new, new, new_parameter),
+                    format!("new {} {}", kind, describe(new_parameter)),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn request_body(&mut self, old: &Located<'a>, new: &Located<'a>) -> Result<()> {
+        let old_body = request_body(self.old, old)?;
+        let new_body = request_body(self.new, new)?;
+        match (old_body, new_body) {
+            (None, None) => {}
+            (Some((pointer, _)), None) => self.change(
+                Compatibility::NonBreaking,
+                pointer,
+                "the request body was removed",
+            ),
+            (None, Some((pointer, body))) => {
+                if body.required == Some(true) {
+                    self.change(
+                        Compatibility::Breaking,
+                        pointer,
+                        "a required request body was added",
+                    )
+                } else {
+                    self.change(
+                        Compatibility::NonBreaking,
+                        pointer,
+                        "an optional request body was added",
+                    )
+                }
+            }
+            (Some((old_pointer, old_body)), Some((pointer, new_body))) => {
+                if old_body.required != Some(true) && new_body.required == Some(true) {
+                    self.change(
+                        Compatibility::Breaking,
+                        &pointer,
+                        "the request body is now required",
+                    );
+                }
+                let pointer = pointer::join(&pointer, "content");
+                for media_type in old_body.content.keys() {
+                    if !new_body.content.contains_key(media_type) {
+                        self.change(
+                            Compatibility::Breaking,
+                            &pointer,
+                            format!("the request body no longer accepts `{}`", media_type),
+                        );
+                    }
+                }
+                self.content(
+                    (&pointer::join(&old_pointer, "content"), &old_body.content),
+                    (&pointer, &new_body.content),
+                    Direction::Request,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the schemas of the media types found in both versions of some content.
+    fn content(
+        &mut self,
+        old: (&str, &'a BTreeMap<String, MediaType>),
+        new: (&str, &'a BTreeMap<String, MediaType>),
+        direction: Direction,
+    ) -> Result<()> {
+        for (media_type, old_media_type) in old.1 {
+            let new_media_type = match new.1.get(media_type) {
+                Some(new_media_type) => new_media_type,
+                None => continue,
+            };
+            if let (Some(old_schema), Some(new_schema)) =
+                (&old_media_type.schema, &new_media_type.schema)
+            {
+                let old_pointer = pointer::join(&pointer::join(old.0, media_type), "schema");
+                let new_pointer = pointer::join(&pointer::join(new.0, media_type), "schema");
+                let (old_pointer, old_schema) =
+                    schema_reference(self.old, &old_pointer, old_schema)?;
+                let (new_pointer, new_schema) =
+                    schema_reference(self.new, &new_pointer, new_schema)?;
+                self.schema(
+                    (&old_pointer, old_schema),
+                    (&new_pointer, new_schema),
+                    direction,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn schema(
+        &mut self,
+        old: (&str, &'a Schema),
+        new: (&str, &'a Schema),
+        direction: Direction,
+    ) -> Result<()> {
+        let (old_pointer, old) = target(self.old, old.0, old.1)?;
+        let (pointer, new) = target(self.new, new.0, new.1)?;
+        if !self
+            .compared
+            .insert((old_pointer.clone(), pointer.clone(), direction))
+        {
+            return Ok(());
+        }
+        let pointer = pointer.as_str();
+        match (&old.schema_type, &new.schema_type) {
+            (Some(old_type), Some(new_type)) if old_type != new_type => self.change(
+                Compatibility::Breaking,
+                pointer,
+                format!("the type changed from `{}` to `{}`", old_type, new_type),
+            ),
+            (None, Some(new_type)) => self.narrowed(
+                direction,
+                pointer,
+                format!("the type is now `{}`", new_type),
+            ),
+            (Some(old_type), None) => self.widened(
+                direction,
+                pointer,
+                format!("the type is no longer `{}`", old_type),
+            ),
+            _ => {}
+        }
+        self.format(pointer, old, new, direction);
+        self.enumeration(pointer, old, new, direction);
+        if old.nullable == Some(true) && new.nullable != Some(true) {
+            self.narrowed(direction, pointer, "the value is no longer nullable".into());
+        } else if old.nullable != Some(true) && new.nullable == Some(true) {
+            self.widened(direction, pointer, "the value is now nullable".into());
+        }
+        self.bounds(pointer, old, new, direction);
+        self.properties(pointer, (&old_pointer, old), new, direction)?;
+        if let (Some(old_items), Some(new_items)) = (&old.items, &new.items) {
+            self.schema(
+                (&pointer::join(&old_pointer, "items"), old_items),
+                (&pointer::join(pointer, "items"), new_items),
+                direction,
+            )?;
+        }
+        self.additional_properties(pointer, (&old_pointer, old), new, direction)?;
+        self.composition(pointer, (&old_pointer, old), new, direction)?;
+        Ok(())
+    }
+
+    fn format(&mut self, pointer: &str, old: &Schema, new: &Schema, direction: Direction) {
+        match (&old.format, &new.format) {
+            (Some(old_format), Some(new_format)) if old_format != new_format => self.change(
+                Compatibility::Breaking,
+                pointer,
+                format!(
+                    "the format changed from `{}` to `{}`",
+                    old_format, new_format
+                ),
+            ),
+            (None, Some(new_format)) => self.narrowed(
+                direction,
+                pointer,
+                format!("the format is now `{}`", new_format),
+            ),
+            (Some(old_format), None) => self.widened(
+                direction,
+                pointer,
+                format!("the format is no longer `{}`", old_format),
+            ),
+            _ => {}
+        }
+    }
+
+    fn additional_properties(
+        &mut self,
+        pointer: &str,
+        old: (&str, &'a Schema),
+        new: &'a Schema,
+        direction: Direction,
+    ) -> Result<()> {
+        let (old_pointer, old) = old;
+        let old = additional_properties(self.old, old_pointer, old)?;
+        let new = additional_properties(self.new, pointer, new)?;
+        let pointer = pointer::join(pointer, "additionalProperties");
+        match (old, new) {
+            (Additional::Allowed(true), Additional::Allowed(false))
+            | (Additional::Schema(..), Additional::Allowed(false)) => self.narrowed(
+                direction,
+                &pointer,
+                "additional properties are no longer allowed".into(),
+            ),
+            (Additional::Allowed(false), Additional::Allowed(true))
+            | (Additional::Allowed(false), Additional::Schema(..)) => self.widened(
+                direction,
+                &pointer,
+                "additional properties are now allowed".into(),
+            ),
+            (Additional::Allowed(true), Additional::Schema(..)) => self.narrowed(
+                direction,
+                &pointer,
+                "additional properties now have a schema".into(),
+            ),
+            (Additional::Schema(..), Additional::Allowed(true)) => self.widened(
+                direction,
+                &pointer,
+                "additional properties no longer have a schema".into(),
+            ),
+            (Additional::Schema(old_pointer, old), Additional::Schema(new_pointer, new)) => {
+                self.schema((&old_pointer, old), (&new_pointer, new), direction)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Compares the `allOf`, `oneOf`, `anyOf` and `not` schemas of two schemas by position.
+    ///
+    /// An added `allOf` or `not` schema narrows the values allowed, and an added `oneOf` or
+    /// `anyOf` alternative widens them. As a `not` schema allows what its schemas do not,
+    /// they are compared in the opposite direction.
+    fn composition(
+        &mut self,
+        pointer: &str,
+        old: (&str, &'a Schema),
+        new: &'a Schema,
+        direction: Direction,
+    ) -> Result<()> {
+        let (old_pointer, old) = old;
+        let opposite = match direction {
+            Direction::Request => Direction::Response,
+            Direction::Response => Direction::Request,
+        };
+        let keywords = [
+            ("allOf", &old.all_of, &new.all_of, direction, true),
+            ("oneOf", &old.one_of, &new.one_of, direction, false),
+            ("anyOf", &old.any_of, &new.any_of, direction, false),
+            ("not", &old.not, &new.not, opposite, false),
+        ];
+        for (keyword, old_schemas, new_schemas, direction, narrows) in keywords {
+            let old_schemas = old_schemas.as_deref().unwrap_or_default();
+            let new_schemas = new_schemas.as_deref().unwrap_or_default();
+            let old_pointer = pointer::join(old_pointer, keyword);
+            let new_pointer = pointer::join(pointer, keyword);
+            for index in 0..old_schemas.len().max(new_schemas.len()) {
+                let old_pointer = pointer::join(&old_pointer, &index.to_string());
+                let new_pointer = pointer::join(&new_pointer, &index.to_string());
+                match (old_schemas.get(index), new_schemas.get(index)) {
+                    (Some(old_schema), Some(new_schema)) => {
+                        let (old_pointer, old_schema) =
+                            schema_reference(self.old, &old_pointer, old_schema)?;
+                        let (new_pointer, new_schema) =
+                            schema_reference(self.new, &new_pointer, new_schema)?;
+                        self.schema(
+                            (&old_pointer, old_schema),
+                            (&new_pointer, new_schema),
+                            direction,
+                        )?;
+                    }
+                    (Some(_), None) => {
+                        let message = format!("a `{}` schema was removed", keyword);
+                        if narrows {
+                            self.widened(direction, &old_pointer, message)
+                        } else {
+                            self.narrowed(direction, &old_pointer, message)
+                        }
+                    }
+                    (None, Some(_)) => {
+                        let message = format!("a `{}` schema was added", keyword);
+                        if narrows {
+                            self.narrowed(direction, &new_pointer, message)
+                        } else {
+                            self.widened(direction, &new_pointer, message)
+                        }
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn enumeration(&mut self, pointer: &str, old: &Schema, new: &Schema, direction: Direction) {
+        match (&old.enum_values, &new.enum_values) {
+            (None, Some(_)) => {
+                self.narrowed(direction, pointer, "the values are now enumerated".into())
+            }
+            (Some(_), None) => self.widened(
+                direction,
+                pointer,
+                "the values are no longer enumerated".into(),
+            ),
+            (Some(old_values), Some(new_values)) => {
+                let removed = difference(old_values, new_values);
+                if !removed.is_empty() {
+                    let message = format!("the enum values {} were removed", removed);
+                    self.narrowed(direction, pointer, message);
+                }
+                let added = difference(new_values, old_values);
+                if !added.is_empty() {
+                    let message = format!("the enum values {} were added", added);
+                    self.widened(direction, pointer, message);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn bounds(&mut self, pointer: &str, old: &Schema, new: &Schema, direction: Direction) {
+        let upper = |schema: &Schema| {
+            [
+                ("maximum", schema.maximum.map(i64::from)),
+                ("maxLength", schema.max_length.map(i64::from)),
+                ("maxItems", schema.max_items.map(i64::from)),
+                ("maxProperties", schema.max_properties.map(i64::from)),
+            ]
+        };
+        let lower = |schema: &Schema| {
+            [
+                ("minimum", schema.minimum.map(i64::from)),
+                ("minLength", schema.min_length.map(i64::from)),
+                ("minItems", schema.min_items.map(i64::from)),
+                ("minProperties", schema.min_properties.map(i64::from)),
+            ]
+        };
+        let bounds = upper(old)
+            .iter()
+            .zip(upper(new).iter())
+            .map(|(old, new)| (old.0, old.1, new.1, true))
+            .chain(
+                lower(old)
+                    .iter()
+                    .zip(lower(new).iter())
+                    .map(|(old, new)| (old.0, old.1, new.1, false)),
+            )
+            .collect::<Vec<_>>();
+        for (keyword, old_bound, new_bound, is_upper) in bounds {
+            let pointer = pointer::join(pointer, keyword);
+            match (old_bound, new_bound) {
+                (None, Some(bound)) => self.narrowed(
+                    direction,
+                    &pointer,
+                    format!("`{}` of {} was added", keyword, bound),
+                ),
+                (Some(bound), None) => self.widened(
+                    direction,
+                    &pointer,
+                    format!("`{}` of {} was removed", keyword, bound),
+                ),
+                (Some(old_bound), Some(new_bound)) if old_bound != new_bound => {
+                    let message =
+                        format!("`{}` changed from {} to {}", keyword, old_bound, new_bound);
+                    if (new_bound < old_bound) == is_upper {
+                        self.narrowed(direction, &pointer, message)
+                    } else {
+                        self.widened(direction, &pointer, message)
+                    }
+                }
+                _ => {}
+            }
+        }
+        let exclusive = [
+            (
+                "exclusiveMaximum",
+                old.exclusive_maximum,
+                new.exclusive_maximum,
+            ),
+            (
+                "exclusiveMinimum",
+                old.exclusive_minimum,
+                new.exclusive_minimum,
+            ),
+        ];
+        for (keyword, old_flag, new_flag) in exclusive {
+            let pointer = pointer::join(pointer, keyword);
+            match (old_flag == Some(true), new_flag == Some(true)) {
+                (false, true) => {
+                    self.narrowed(direction, &pointer, format!("`{}` was set", keyword))
+                }
+                (true, false) => {
+                    self.widened(direction, &pointer, format!("`{}` was unset", keyword))
+                }
+                _ => {}
+            }
+        }
+        if old.pattern != new.pattern {
+            let pointer = pointer::join(pointer, "pattern");
+            match &new.pattern {
+                Some(pattern) => self.narrowed(
+                    direction,
+                    &pointer,
+                    format!("the pattern is now `{}`", pattern),
+                ),
+                None => self.widened(direction, &pointer, "the pattern was removed".into()),
+            }
+        }
+    }
+
+    fn properties(
+        &mut self,
+        pointer: &str,
+        old: (&str, &'a Schema),
+        new: &'a Schema,
+        direction: Direction,
+    ) -> Result<()> {
+        let (old_pointer, old) = old;
+        let old_required = old.required.iter().flatten().collect::<HashSet<_>>();
+        let new_required = new.required.iter().flatten().collect::<HashSet<_>>();
+        let properties = pointer::join(pointer, "properties");
+        for name in new.required.iter().flatten() {
+            if !old_required.contains(name) {
+                let message = format!("property `{}` is now required", name);
+                self.narrowed(direction, &pointer::join(&properties, name), message);
+            }
+        }
+        for name in old.required.iter().flatten() {
+            if !new_required.contains(name) {
+                let message = format!("property `{}` is no longer required", name);
+                self.widened(direction, &pointer::join(&properties, name), message);
+            }
+        }
+        for (name, old_property) in old.properties.iter().flatten() {
+            let old_property_pointer =
+                pointer::join(&pointer::join(old_pointer, "properties"), name);
+            match new
+                .properties
+                .as_ref()
+                .and_then(|properties| properties.get(name))
+            {
+                Some(new_property) => self.schema(
+                    (&old_property_pointer, old_property),
+                    (&pointer::join(&properties, name), new_property),
+                    direction,
+                )?,
+                None => {
+                    let compatibility = match direction {
+                        Direction::Request => Compatibility::NonBreaking,
+                        Direction::Response => Compatibility::Breaking,
+                    };
+                    self.change(
+                        compatibility,
+                        old_property_pointer,
+                        format!("property `{}` was removed", name),
+                    )
+                }
+            }
+        }
+        for name in new.properties.iter().flatten().map(|(name, _)| name) {
+            if !old.properties.iter().flatten().any(|(old, _)| old == name) {
+                self.change(
+                    Compatibility::NonBreaking,
+                    pointer::join(&properties, name),
+                    format!("property `{}` was added", name),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn security_schemes(&mut self) {
+        let old_schemes = security_schemes(self.old);
+        let new_schemes = security_schemes(self.new);
+        for (name, old_scheme) in old_schemes.iter().copied().flatten() {
+            let pointer = pointer::join("/components/securitySchemes", name);
+            let new_scheme = match new_schemes.and_then(|schemes| schemes.get(name)) {
+                Some(new_scheme) => resolve(new_schemes, SECURITY_SCHEMES, new_scheme).ok(),
+                None => {
+                    self.change(
+                        Compatibility::Breaking,
+                        pointer,
+                        format!("security scheme `{}` was removed", name),
+                    );
+                    continue;
+                }
+            };
+            if let (
+                Some(SecurityScheme::OAuth2 { flows: old_flows }),
+                Some(SecurityScheme::OAuth2 { flows: new_flows }),
+            ) = (
+                resolve(old_schemes, SECURITY_SCHEMES, old_scheme).ok(),
+                new_scheme,
+            ) {
+                let pointer = pointer::join(&pointer, "flows");
+                for (flow, old_scopes) in scopes(old_flows) {
+                    let pointer = pointer::join(&pointer, flow);
+                    let new_scopes = match scopes(new_flows)
+                        .into_iter()
+                        .find(|(new_flow, _)| *new_flow == flow)
+                    {
+                        Some((_, new_scopes)) => new_scopes,
+                        None => {
+                            self.change(
+                                Compatibility::Breaking,
+                                pointer,
+                                format!("the `{}` flow was removed", flow),
+                            );
+                            continue;
+                        }
+                    };
+                    for scope in old_scopes.keys() {
+                        if !new_scopes.contains_key(scope) {
+                            self.change(
+                                Compatibility::Breaking,
+                                pointer::join(&pointer, "scopes"),
+                                format!("scope `{}` was removed", scope),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The operations of a spec by method and path, with the names of path parameters
+/// removed.
+fn operations(spec: &Spec) -> BTreeMap<(String, Method), Located<'_>> {
+    let mut operations = BTreeMap::new();
+    for (path, path_item) in &spec.paths {
+        for (method, operation) in path_item.operations() {
+            let located = Located {
+                path,
+                method,
+                path_item,
+                operation,
+            };
+            operations.insert((anonymous(path), method), located);
+        }
+    }
+    operations
+}
+
+/// Whether two parameters are the same one: path parameters by their position in the
+/// template, as operations are matched with the names of path parameters removed, and
+/// others by their name and location.
+fn same_parameter(old: (&Located, &Parameter), new: (&Located, &Parameter)) -> bool {
+    let position = |(located, parameter): (&Located, &Parameter)| {
+        let parts = template::parse(located.path)?;
+        template::variables(&parts)
+            .iter()
+            .position(|name| *name == parameter.name)
+    };
+    if old.1.location != new.1.location {
+        return false;
+    }
+    match (old.1.location.as_str(), position(old), position(new)) {
+        ("path", Some(old_position), Some(new_position)) => old_position == new_position,
+        _ => old.1.name == new.1.name,
+    }
+}
+
+/// A path template with the names of its parameters removed, as in `/pets/{}`.
+pub(crate) fn anonymous(path: &str) -> String {
+    let mut anonymous = String::new();
+    let mut depth = 0;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    anonymous.push('{');
+                }
+                depth += 1;
+            }
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    anonymous.push('}');
+                }
+            }
+            c if depth == 0 => anonymous.push(c),
+            _ => {}
+        }
+    }
+    anonymous
+}
+
+/// Resolves a schema which may be a reference object, along with its pointer.
+fn schema_reference<'s>(
+    spec: &'s Spec,
+    pointer: &str,
+    schema: &'s ObjectOrReference<Schema>,
+) -> Result<(String, &'s Schema)> {
+    match schema {
+        ObjectOrReference::Object(schema) => Ok((pointer.to_string(), schema)),
+        ObjectOrReference::Ref { ref_path } => Ok((
+            pointer::from_fragment(ref_path).into_owned(),
+            resolve(schemas(spec), SCHEMAS, schema)?,
+        )),
+    }
+}
+
+/// The pointer of a parameter of an operation, which is that of the component if it was
+/// referenced.
+fn parameter_pointer(spec: &Spec, located: &Located<'_>, parameter: &Parameter) -> String {
+    let lists = [
+        (located.pointer(), located.operation.parameters.as_ref()),
+        (
+            pointer::join("/paths", located.path),
+            located.path_item.parameters.as_ref(),
+        ),
+    ];
+    for (pointer, parameters) in lists {
+        for (index, candidate) in parameters.into_iter().flatten().enumerate() {
+            if spec
+                .resolve_parameter(candidate)
+                .is_ok_and(|candidate| ptr::eq(candidate, parameter))
+            {
+                return match candidate {
+                    ObjectOrReference::Object(_) => {
+                        pointer::join(&pointer::join(&pointer, "parameters"), &index.to_string())
+                    }
+                    ObjectOrReference::Ref { ref_path } => {
+                        pointer::from_fragment(ref_path).into_owned()
+                    }
+                };
+            }
+        }
+    }
+    located.pointer()
+}
+
+/// The resolved request body of an operation, along with its pointer.
+fn request_body<'s>(
+    spec: &'s Spec,
+    located: &Located<'s>,
+) -> Result<Option<(String, &'s RequestBody)>> {
+    let request_body = match &located.operation.request_body {
+        Some(request_body) => request_body,
+        None => return Ok(None),
+    };
+    let pointer = match request_body {
+        ObjectOrReference::Object(_) => pointer::join(&located.pointer(), "requestBody"),
+        ObjectOrReference::Ref { ref_path } => pointer::from_fragment(ref_path).into_owned(),
+    };
+    Ok(Some((pointer, spec.resolve_request_body(request_body)?)))
+}
+
+/// Follows the `$ref`s of a schema, returning it along with its pointer.
+fn target<'s>(spec: &'s Spec, pointer: &str, schema: &'s Schema) -> Result<(String, &'s Schema)> {
+    let mut schema = schema;
+    let mut pointer = pointer.to_string();
+    for _ in 0..MAX_REFERENCE_DEPTH {
+        let ref_path = match &schema.ref_path {
+            Some(ref_path) => ref_path,
+            None => return Ok((pointer, schema)),
+        };
+        let (component_pointer, component) = component(spec, ref_path)?;
+        pointer = component_pointer;
+        schema = component;
+    }
+    Err(Error::UnresolvedReference(pointer))
+}
+
+/// Resolves a reference to a component schema, along with its pointer.
+fn component<'s>(spec: &'s Spec, ref_path: &str) -> Result<(String, &'s Schema)> {
+    let component = ref_path
+        .strip_prefix(SCHEMAS)
+        .and_then(|name| schemas(spec)?.get(pointer::unescape(name).as_ref()))
+        .ok_or_else(|| Error::UnresolvedReference(ref_path.to_string()))?;
+    Ok((
+        pointer::from_fragment(ref_path).into_owned(),
+        resolve(schemas(spec), SCHEMAS, component)?,
+    ))
+}
+
+/// What a schema allows of the properties it does not list.
+enum Additional<'s> {
+    Allowed(bool),
+    Schema(String, &'s Schema),
+}
+
+fn additional_properties<'s>(
+    spec: &'s Spec,
+    pointer: &str,
+    schema: &'s Schema,
+) -> Result<Additional<'s>> {
+    Ok(match &schema.additional_properties {
+        None => Additional::Allowed(true),
+        Some(BooleanObjectOrReference::Boolean(allowed)) => Additional::Allowed(*allowed),
+        Some(BooleanObjectOrReference::Object(schema)) => {
+            Additional::Schema(pointer::join(pointer, "additionalProperties"), schema)
+        }
+        Some(BooleanObjectOrReference::Ref { ref_path }) => {
+            let (pointer, schema) = component(spec, ref_path)?;
+            Additional::Schema(pointer, schema)
+        }
+    })
+}
+
+fn schemas(spec: &Spec) -> Option<&BTreeMap<String, ObjectOrReference<Schema>>> {
+    spec.components
+        .as_ref()
+        .and_then(|components| components.schemas.as_ref())
+}
+
+fn security_schemes(spec: &Spec) -> Option<&BTreeMap<String, ObjectOrReference<SecurityScheme>>> {
+    spec.components
+        .as_ref()
+        .and_then(|components| components.security_schemes.as_ref())
+}
+
+/// The scopes of each flow of an OAuth scheme, by the flow's name in the spec.
+fn scopes(flows: &Flows) -> Vec<(&'static str, &BTreeMap<String, String>)> {
+    let mut scopes = Vec::new();
+    if let Some(flow) = &flows.implicit {
+        scopes.push(("implicit", &flow.scopes));
+    }
+    if let Some(flow) = &flows.password {
+        scopes.push(("password", &flow.scopes));
+    }
+    if let Some(flow) = &flows.client_credentials {
+        scopes.push(("clientCredentials", &flow.scopes));
+    }
+    if let Some(flow) = &flows.authorization_code {
+        scopes.push(("authorizationCode", &flow.scopes));
+    }
+    scopes
+}
+
+fn is_required(parameter: &Parameter) -> bool {
+    parameter.required == Some(true)
+}
+
+fn describe(parameter: &Parameter) -> String {
+    format!("{} parameter `{}`", parameter.location, parameter.name)
+}
+
+/// The values of `a` missing from `b`, quoted and comma-separated.
+fn difference(a: &[String], b: &[String]) -> String {
+    a.iter()
+        .filter(|value| !b.contains(value))
+        .map(|value| format!("`{}`", value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Compatibility::NonBreaking => "non-breaking",
+            Compatibility::Breaking => "breaking",
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} `{}`: {}",
+            self.compatibility, self.pointer, self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: status
+          in: query
+          schema:
+            type: string
+            enum: [available, pending, sold]
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewPet"
+      responses:
+        '201':
+          description: Created
+  /pets/{id}:
+    delete:
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '204':
+          description: Deleted
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+        tag:
+          type: string
+    NewPet:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+          maxLength: 100
+        tag:
+          type: string
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/oauth
+          scopes:
+            read: Read pets
+            write: Write pets
+"##;
+
+    const NEW: &str = r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 2.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: status
+          in: query
+          schema:
+            type: string
+            enum: [available, sold]
+        - name: owner
+          in: query
+          required: true
+          schema:
+            type: string
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewPet"
+      responses:
+        '201':
+          description: Created
+  /pets/{petId}:
+    get:
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: A pet
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: string
+        name:
+          type: string
+    NewPet:
+      type: object
+      required: [name, tag]
+      properties:
+        name:
+          type: string
+          maxLength: 50
+        tag:
+          type: string
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/oauth
+          scopes:
+            read: Read pets
+"##;
+
+    #[test]
+    fn classifies_changes() {
+        let old: Spec = serde_yaml::from_str(OLD).unwrap();
+        let new: Spec = serde_yaml::from_str(NEW).unwrap();
+        let changes = old
+            .changes_to(&new)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "breaking `/paths/~1pets/get/parameters/0/schema`: \
+                 the enum values `pending` were removed",
+                "breaking `/paths/~1pets/get/parameters/1`: new required query parameter `owner`",
+                "breaking `/components/schemas/Pet/properties/id`: \
+                 the type changed from `integer` to `string`",
+                "breaking `/components/schemas/Pet/properties/tag`: property `tag` was removed",
+                "breaking `/components/schemas/NewPet/properties/tag`: \
+                 property `tag` is now required",
+                "breaking `/components/schemas/NewPet/properties/name/maxLength`: \
+                 `maxLength` changed from 100 to 50",
+                "breaking `/paths/~1pets~1{id}/delete`: operation `DELETE /pets/{id}` was removed",
+                "non-breaking `/paths/~1pets~1{petId}/get`: operation `GET /pets/{petId}` was added",
+                "breaking `/components/securitySchemes/oauth/flows/implicit/scopes`: \
+                 scope `write` was removed",
+            ]
+        );
+        assert!(new.changes_to(&new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn matches_path_parameters_by_position() {
+        let spec = |name: &str| -> Spec {
+            serde_yaml::from_str(&format!(
+                "
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets/{{{name}}}:
+    get:
+      parameters:
+        - name: {name}
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: A pet
+",
+                name = name
+            ))
+            .unwrap()
+        };
+        assert!(spec("petId").changes_to(&spec("id")).unwrap().is_empty());
+    }
+
+    const OLD_NODES: &str = r##"
+openapi: 3.0.0
+info:
+  title: Nodes
+  version: 1.0.0
+paths:
+  /nodes:
+    get:
+      responses:
+        '200':
+          description: Nodes
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Node"
+            application/xml:
+              schema:
+                $ref: "#/components/schemas/Node"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Node"
+      responses:
+        '201':
+          description: Created
+components:
+  schemas:
+    Node:
+      type: object
+      additionalProperties: false
+      properties:
+        children:
+          type: array
+          items:
+            $ref: "#/components/schemas/Node"
+        id:
+          type: string
+          format: uuid
+        name:
+          type: string
+          not:
+            - enum: [admin]
+        size:
+          allOf:
+            - type: integer
+              maximum: 10
+        value:
+          oneOf:
+            - type: string
+            - type: integer
+"##;
+
+    const NEW_NODES: &str = r##"
+openapi: 3.0.0
+info:
+  title: Nodes
+  version: 2.0.0
+paths:
+  /nodes:
+    get:
+      responses:
+        '200':
+          description: Nodes
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Node"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Node"
+      responses:
+        '201':
+          description: Created
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        children:
+          type: array
+          items:
+            $ref: "#/components/schemas/Node"
+        id:
+          type: string
+        name:
+          type: string
+          not:
+            - enum: [admin, root]
+        size:
+          allOf:
+            - type: integer
+              maximum: 5
+        value:
+          oneOf:
+            - type: string
+            - type: integer
+            - type: boolean
+"##;
+
+    #[test]
+    fn classifies_composed_and_recursive_schemas_by_direction() {
+        let old: Spec = serde_yaml::from_str(OLD_NODES).unwrap();
+        let new: Spec = serde_yaml::from_str(NEW_NODES).unwrap();
+        let changes = old
+            .changes_to(&new)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        // the component is compared once as a response and once as a request, and once
+        // only although it contains itself
+        assert_eq!(
+            changes,
+            vec![
+                "breaking `/paths/~1nodes/get/responses/200/content`: \
+                 the `200` response no longer returns `application/xml`",
+                "breaking `/components/schemas/Node/properties/id`: \
+                 the format is no longer `uuid`",
+                "non-breaking `/components/schemas/Node/properties/name/not/0`: \
+                 the enum values `root` were added",
+                "non-breaking `/components/schemas/Node/properties/size/allOf/0/maximum`: \
+                 `maximum` changed from 10 to 5",
+                "breaking `/components/schemas/Node/properties/value/oneOf/2`: \
+                 a `oneOf` schema was added",
+                "breaking `/components/schemas/Node/additionalProperties`: \
+                 additional properties are now allowed",
+                "non-breaking `/components/schemas/Node/properties/id`: \
+                 the format is no longer `uuid`",
+                "breaking `/components/schemas/Node/properties/name/not/0`: \
+                 the enum values `root` were added",
+                "breaking `/components/schemas/Node/properties/size/allOf/0/maximum`: \
+                 `maximum` changed from 10 to 5",
+                "non-breaking `/components/schemas/Node/properties/value/oneOf/2`: \
+                 a `oneOf` schema was added",
+                "non-breaking `/components/schemas/Node/additionalProperties`: \
+                 additional properties are now allowed",
+            ]
+        );
+        assert!(new.changes_to(&new).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,533 @@
+//! Structural differences between two versions of a spec, rendered as Markdown or JSON for
+//! release notes.
+
+use crate::{
+    pointer,
+    v3_0::{parameters::resolve, ObjectOrReference, Operation, Parameter, PathItem, Schema, Spec},
+    Method, Result,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The kinds of nodes which differences are reported for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Element {
+    Path,
+    Operation,
+    Parameter,
+    Schema,
+    Property,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A node which was added, removed or modified.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Difference {
+    pub element: Element,
+    pub kind: DiffKind,
+    /// A name for the node, such as `GET /pets` or `Pet.name`.
+    pub name: String,
+    /// The JSON pointer of the node, in the older spec if it was removed and in the newer
+    /// one otherwise.
+    pub pointer: String,
+    /// What changed about a modified node, such as "`type` changed from `integer` to
+    /// `string`".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+/// The differences between two versions of a spec, in the order of their elements.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Diff {
+    pub differences: Vec<Difference>,
+}
+
+impl Spec {
+    /// The paths, operations, parameters, component schemas and their properties added,
+    /// removed or modified from this spec to a newer version of it.
+    ///
+    /// Nodes are matched by name: paths by template, operations by path and method,
+    /// parameters by name and location, and schemas and properties by name. The parameters
+    /// of an operation are those which apply to it, including those of its path item, and
+    /// references to parameters are resolved. The differences of paths, operations and
+    /// parameters leave out their nested elements, which are reported on their own.
+    ///
+    /// Fails if either spec holds an unresolvable parameter reference.
+    pub fn diff(&self, newer: &Spec) -> Result<Diff> {
+        let mut differences = Vec::new();
+        paths(self, newer, &mut differences)?;
+        schemas(self, newer, &mut differences);
+        differences.sort_by_key(|difference| difference.element);
+        Ok(Diff { differences })
+    }
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// A Markdown section per kind of element, each listing its differences.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let sections = [
+            (Element::Path, "Paths"),
+            (Element::Operation, "Operations"),
+            (Element::Parameter, "Parameters"),
+            (Element::Schema, "Schemas"),
+            (Element::Property, "Properties"),
+        ];
+        for (element, title) in sections {
+            let mut differences = self
+                .differences
+                .iter()
+                .filter(|difference| difference.element == element)
+                .peekable();
+            if differences.peek().is_none() {
+                continue;
+            }
+            if !markdown.is_empty() {
+                markdown.push('\n');
+            }
+            let _ = writeln!(markdown, "## {}\n", title);
+            for difference in differences {
+                let kind = match difference.kind {
+                    DiffKind::Added => "Added",
+                    DiffKind::Removed => "Removed",
+                    DiffKind::Modified => "Modified",
+                };
+                let _ = write!(markdown, "* {} `{}`", kind, difference.name);
+                if !difference.details.is_empty() {
+                    let _ = write!(markdown, ": {}", difference.details.join("; "));
+                }
+                markdown.push('\n');
+            }
+        }
+        if markdown.is_empty() {
+            markdown.push_str("No changes.\n");
+        }
+        markdown
+    }
+}
+
+/// Nodes by the key they are matched by, each along with its pointer.
+type Nodes<K, T> = BTreeMap<K, (String, T)>;
+
+fn paths(old: &Spec, new: &Spec, differences: &mut Vec<Difference>) -> Result<()> {
+    for (path, old_path_item) in &old.paths {
+        let pointer = pointer::join("/paths", path);
+        let new_path_item = match new.paths.get(path) {
+            Some(new_path_item) => new_path_item,
+            None => {
+                differences.push(difference(Element::Path, DiffKind::Removed, path, &pointer));
+                continue;
+            }
+        };
+        let details = fields(
+            old_path_item,
+            new_path_item,
+            &[
+                "parameters",
+                "get",
+                "put",
+                "post",
+                "delete",
+                "options",
+                "head",
+                "patch",
+                "trace",
+            ],
+        );
+        if !details.is_empty() {
+            differences.push(modified(Element::Path, path, &pointer, details));
+        }
+        for method in Method::ALL.iter().copied() {
+            let name = format!("{} {}", method, path);
+            let pointer = pointer::join(&pointer, method.as_str());
+            match (
+                old_path_item.operation(method),
+                new_path_item.operation(method),
+            ) {
+                (Some(_), None) => differences.push(difference(
+                    Element::Operation,
+                    DiffKind::Removed,
+                    &name,
+                    &pointer,
+                )),
+                (Some(old_operation), Some(new_operation)) => {
+                    let details = fields(old_operation, new_operation, &["parameters"]);
+                    if !details.is_empty() {
+                        differences.push(modified(Element::Operation, &name, &pointer, details));
+                    }
+                    let old_parameters =
+                        parameters(old, path, old_path_item, method, old_operation)?;
+                    let new_parameters =
+                        parameters(new, path, new_path_item, method, new_operation)?;
+                    compare(
+                        Element::Parameter,
+                        |key: &(String, String)| format!("{} ({} {})", name, key.1, key.0),
+                        &old_parameters,
+                        &new_parameters,
+                        differences,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    for (path, new_path_item) in &new.paths {
+        let pointer = pointer::join("/paths", path);
+        if !old.paths.contains_key(path) {
+            differences.push(difference(Element::Path, DiffKind::Added, path, &pointer));
+        }
+        for (method, _) in new_path_item.operations() {
+            let existed = old
+                .paths
+                .get(path)
+                .and_then(|old_path_item| old_path_item.operation(method))
+                .is_some();
+            if !existed {
+                differences.push(difference(
+                    Element::Operation,
+                    DiffKind::Added,
+                    &format!("{} {}", method, path),
+                    &pointer::join(&pointer, method.as_str()),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn schemas(old: &Spec, new: &Spec, differences: &mut Vec<Difference>) {
+    let old_schemas = component_schemas(old);
+    let new_schemas = component_schemas(new);
+    compare(
+        Element::Schema,
+        |name: &String| name.clone(),
+        &old_schemas,
+        &new_schemas,
+        differences,
+    );
+    for (name, (_, old_schema)) in &old_schemas {
+        let (pointer, new_schema) = match new_schemas.get(name) {
+            Some((pointer, new_schema)) => (pointer, new_schema),
+            None => continue,
+        };
+        let (old_schema, new_schema) = match (old_schema, new_schema) {
+            (Target::Schema(old_schema), Target::Schema(new_schema)) => (old_schema, new_schema),
+            _ => continue,
+        };
+        let old_pointer = pointer::join("/components/schemas", name);
+        compare(
+            Element::Property,
+            |property: &String| format!("{}.{}", name, property),
+            &properties(old_schema, &old_pointer),
+            &properties(new_schema, pointer),
+            differences,
+        );
+    }
+}
+
+/// The properties of a schema by name, each along with its pointer.
+fn properties<'a>(schema: &'a Schema, pointer: &str) -> Nodes<String, &'a Schema> {
+    schema
+        .properties
+        .iter()
+        .flatten()
+        .map(|(property, schema)| {
+            let pointer = pointer::join(&pointer::join(pointer, "properties"), property);
+            (property.clone(), (pointer, schema))
+        })
+        .collect()
+}
+
+/// A component schema, which may itself be a reference to another.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Target<'a> {
+    Schema(&'a Schema),
+    Reference {
+        #[serde(rename = "$ref")]
+        ref_path: &'a str,
+    },
+}
+
+fn component_schemas(spec: &Spec) -> Nodes<String, Target<'_>> {
+    spec.components
+        .iter()
+        .flat_map(|components| components.schemas.iter().flatten())
+        .map(|(name, schema)| {
+            let target = match schema {
+                ObjectOrReference::Object(schema) => Target::Schema(schema),
+                ObjectOrReference::Ref { ref_path } => Target::Reference { ref_path },
+            };
+            (
+                name.clone(),
+                (pointer::join("/components/schemas", name), target),
+            )
+        })
+        .collect()
+}
+
+/// The parameters which apply to an operation by name and location, each along with the
+/// pointer of its definition.
+fn parameters<'a>(
+    spec: &'a Spec,
+    path: &str,
+    path_item: &'a PathItem,
+    method: Method,
+    operation: &'a Operation,
+) -> Result<Nodes<(String, String), &'a Parameter>> {
+    let path_pointer = pointer::join("/paths", path);
+    let operation_pointer = pointer::join(&path_pointer, method.as_str());
+    let components = spec
+        .components
+        .as_ref()
+        .and_then(|components| components.parameters.as_ref());
+    let mut parameters = BTreeMap::new();
+    let lists = [
+        (path_pointer, path_item.parameters.as_ref()),
+        (operation_pointer, operation.parameters.as_ref()),
+    ];
+    for (pointer, list) in lists {
+        for (index, parameter) in list.into_iter().flatten().enumerate() {
+            let pointer = match parameter {
+                ObjectOrReference::Object(_) => {
+                    pointer::join(&pointer::join(&pointer, "parameters"), &index.to_string())
+                }
+                ObjectOrReference::Ref { ref_path } => {
+                    pointer::from_fragment(ref_path).into_owned()
+                }
+            };
+            let parameter = resolve(components, "#/components/parameters/", parameter)?;
+            parameters.insert(
+                (parameter.name.clone(), parameter.location.clone()),
+                (pointer, parameter),
+            );
+        }
+    }
+    Ok(parameters)
+}
+
+/// Reports the nodes added to, removed from or modified in a map of them by name.
+fn compare<K: Ord, T: Serialize>(
+    element: Element,
+    name: impl Fn(&K) -> String,
+    old: &Nodes<K, T>,
+    new: &Nodes<K, T>,
+    differences: &mut Vec<Difference>,
+) {
+    for (key, (old_pointer, old_node)) in old {
+        match new.get(key) {
+            Some((pointer, new_node)) => {
+                let details = fields(old_node, new_node, &["properties"]);
+                if !details.is_empty() {
+                    differences.push(modified(element, &name(key), pointer, details));
+                }
+            }
+            None => differences.push(difference(
+                element,
+                DiffKind::Removed,
+                &name(key),
+                old_pointer,
+            )),
+        }
+    }
+    for (key, (pointer, _)) in new {
+        if !old.contains_key(key) {
+            differences.push(difference(element, DiffKind::Added, &name(key), pointer));
+        }
+    }
+}
+
+/// Describes the fields of two versions of a node which differ, leaving out those in
+/// `skip`.
+fn fields<T: Serialize>(old: &T, new: &T, skip: &[&str]) -> Vec<String> {
+    let (old, new) = match (serde_json::to_value(old), serde_json::to_value(new)) {
+        (Ok(Value::Object(old)), Ok(Value::Object(new))) => (old, new),
+        _ => return Vec::new(),
+    };
+    let mut details = Vec::new();
+    for (field, old_value) in &old {
+        if skip.contains(&field.as_str()) {
+            continue;
+        }
+        match new.get(field) {
+            None => details.push(format!("`{}` was removed", field)),
+            Some(new_value) if new_value != old_value => {
+                if is_scalar(old_value) && is_scalar(new_value) {
+                    details.push(format!(
+                        "`{}` changed from `{}` to `{}`",
+                        field,
+                        scalar(old_value),
+                        scalar(new_value)
+                    ))
+                } else {
+                    details.push(format!("`{}` changed", field))
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    for field in new.keys() {
+        if !skip.contains(&field.as_str()) && !old.contains_key(field) {
+            details.push(format!("`{}` was added", field));
+        }
+    }
+    details
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !value.is_object() && !value.is_array()
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn difference(element: Element, kind: DiffKind, name: &str, pointer: &str) -> Difference {
+    Difference {
+        element,
+        kind,
+        name: name.to_string(),
+        pointer: pointer.to_string(),
+        details: Vec::new(),
+    }
+}
+
+fn modified(element: Element, name: &str, pointer: &str, details: Vec<String>) -> Difference {
+    Difference {
+        details,
+        ..difference(element, DiffKind::Modified, name, pointer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_differences() {
+        let old: Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      summary: List pets
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: Pets
+  /owners:
+    get:
+      responses:
+        '200':
+          description: Owners
+components:
+  schemas:
+    Pet:
+      properties:
+        id:
+          type: integer
+        tag:
+          type: string
+    Owner:
+      type: object
+"#,
+        )
+        .unwrap();
+        let new: Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 2.0.0
+paths:
+  /pets:
+    get:
+      summary: List all pets
+      parameters:
+        - name: limit
+          in: query
+          required: true
+          schema:
+            type: integer
+        - name: offset
+          in: query
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: Pets
+    post:
+      responses:
+        '201':
+          description: Created
+components:
+  schemas:
+    Pet:
+      properties:
+        id:
+          type: string
+        name:
+          type: string
+"#,
+        )
+        .unwrap();
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(
+            diff.to_markdown(),
+            "## Paths\n\n\
+             * Removed `/owners`\n\n\
+             ## Operations\n\n\
+             * Modified `GET /pets`: `summary` changed from `List pets` to `List all pets`\n\
+             * Added `POST /pets`\n\n\
+             ## Parameters\n\n\
+             * Modified `GET /pets (query limit)`: `required` was added\n\
+             * Added `GET /pets (query offset)`\n\n\
+             ## Schemas\n\n\
+             * Removed `Owner`\n\n\
+             ## Properties\n\n\
+             * Modified `Pet.id`: `type` changed from `integer` to `string`\n\
+             * Removed `Pet.tag`\n\
+             * Added `Pet.name`\n"
+        );
+        let json: Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(
+            json["differences"][1],
+            serde_json::json!({
+                "element": "operation",
+                "kind": "modified",
+                "name": "GET /pets",
+                "pointer": "/paths/~1pets/get",
+                "details": ["`summary` changed from `List pets` to `List all pets`"],
+            })
+        );
+        assert_eq!(new.diff(&new).unwrap().to_markdown(), "No changes.\n");
+    }
+}
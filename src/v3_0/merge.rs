@@ -0,0 +1,737 @@
+//! Merging of specs, such as those of the services behind a gateway.
+
+use crate::{
+    pointer,
+    v3_0::{
+        compat::anonymous,
+        visit::{self, VisitMut},
+        Components, ObjectOrReference, Operation, PathItem, SecurityRequirement, Spec,
+    },
+    Error, Method, Result,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// What to do with components of the same kind and name which both merged specs define.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Collision {
+    /// Fail on any collision.
+    Error,
+    /// Keep a single copy of identical components, and fail on different ones.
+    KeepIdentical,
+    /// Keep a single copy of identical components, and rename the different ones of the
+    /// merged spec by prepending a prefix, rewriting its references to them.
+    Prefix(String),
+}
+
+/// A way in which two merged specs collide.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    /// The JSON pointer of the colliding node of the merged spec.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Conflict {
+    fn new(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        Conflict {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`: {}", self.pointer, self.message)
+    }
+}
+
+impl Spec {
+    /// Merges another spec into this one, taking the union of their paths, components,
+    /// tags and servers. The `openapi` version, `info`, `security` and other top-level
+    /// fields of this spec are kept. If the global `security` of the specs differs, that of
+    /// the other spec is copied to those of its operations which do not set their own.
+    ///
+    /// Operations defined by both specs on the same method and path, or on equivalent
+    /// templated paths such as `/pets/{id}` and `/pets/{petId}`, `operationId`s used
+    /// by both, and path items whose shared fields differ are conflicts, as are components
+    /// which collide and which `collision` does not resolve. Identical operations and path
+    /// items are kept once. Tags defined by both are kept as this spec describes them.
+    ///
+    /// Returns the conflicts which were resolved. Fails with [`Error::MergeConflicts`]
+    /// listing those which were not, leaving this spec unchanged.
+    pub fn merge(&mut self, other: Spec, collision: &Collision) -> Result<Vec<Conflict>> {
+        let mut other = other;
+        let mut merge = Merge {
+            resolved: Vec::new(),
+            unresolved: Vec::new(),
+        };
+        let renames = merge.plan_components(self, &other, collision);
+        if !renames.is_empty() {
+            rename_components(&mut other, &renames);
+        }
+        merge.operation_ids(self, &other);
+        if other.security != self.security {
+            // operations relying on the other spec's global security would otherwise fall
+            // under this spec's
+            let security = other.security.take().unwrap_or_default();
+            for (_, _, operation) in other.operations_mut() {
+                operation.security.get_or_insert_with(|| security.clone());
+            }
+            merge.resolved.push(Conflict::new(
+                "/security",
+                "the specs require different security; applied the merged spec's to its operations",
+            ));
+        }
+
+        let mut merged = self.clone();
+        merge_components(&mut merged, other.components.take());
+        for (path, path_item) in other.paths {
+            match merged.paths.get_mut(&path) {
+                Some(existing) => merge.path_item(&path, existing, path_item),
+                None => {
+                    merge.equivalent_paths(&merged, &path, &path_item);
+                    merged.paths.insert(path, path_item);
+                }
+            }
+        }
+        for tag in other.tags.into_iter().flatten() {
+            let tags = merged.tags.get_or_insert_with(Vec::new);
+            match tags.iter().find(|existing| existing.name == tag.name) {
+                Some(existing) if *existing != tag => merge.resolved.push(Conflict::new(
+                    "/tags",
+                    format!(
+                        "tag `{}` is described differently; kept the first",
+                        tag.name
+                    ),
+                )),
+                Some(_) => {}
+                None => tags.push(tag),
+            }
+        }
+        for server in other.servers.into_iter().flatten() {
+            let servers = merged.servers.get_or_insert_with(Vec::new);
+            if !servers.iter().any(|existing| existing.url == server.url) {
+                servers.push(server);
+            }
+        }
+
+        if merge.unresolved.is_empty() {
+            *self = merged;
+            Ok(merge.resolved)
+        } else {
+            Err(Error::MergeConflicts(merge.unresolved))
+        }
+    }
+}
+
+struct Merge {
+    resolved: Vec<Conflict>,
+    unresolved: Vec<Conflict>,
+}
+
+impl Merge {
+    /// Finds the components of `other` which collide with those of `spec`, returning the
+    /// references to rename, as in `#/components/schemas/Pet` to
+    /// `#/components/schemas/BillingPet`.
+    fn plan_components(
+        &mut self,
+        spec: &Spec,
+        other: &Spec,
+        collision: &Collision,
+    ) -> BTreeMap<String, String> {
+        let mut renames = BTreeMap::new();
+        let (ours, theirs) = match (&spec.components, &other.components) {
+            (Some(ours), Some(theirs)) => (ours, theirs),
+            _ => return renames,
+        };
+        let kinds = [
+            (
+                "schemas",
+                names(&ours.schemas),
+                differing(&ours.schemas, &theirs.schemas),
+            ),
+            (
+                "responses",
+                names(&ours.responses),
+                differing(&ours.responses, &theirs.responses),
+            ),
+            (
+                "parameters",
+                names(&ours.parameters),
+                differing(&ours.parameters, &theirs.parameters),
+            ),
+            (
+                "examples",
+                names(&ours.examples),
+                differing(&ours.examples, &theirs.examples),
+            ),
+            (
+                "requestBodies",
+                names(&ours.request_bodies),
+                differing(&ours.request_bodies, &theirs.request_bodies),
+            ),
+            (
+                "headers",
+                names(&ours.headers),
+                differing(&ours.headers, &theirs.headers),
+            ),
+            (
+                "securitySchemes",
+                names(&ours.security_schemes),
+                differing(&ours.security_schemes, &theirs.security_schemes),
+            ),
+            (
+                "links",
+                names(&ours.links),
+                differing(&ours.links, &theirs.links),
+            ),
+            (
+                "callbacks",
+                names(&ours.callbacks),
+                differing(&ours.callbacks, &theirs.callbacks),
+            ),
+        ];
+        let their_names = [
+            names(&theirs.schemas),
+            names(&theirs.responses),
+            names(&theirs.parameters),
+            names(&theirs.examples),
+            names(&theirs.request_bodies),
+            names(&theirs.headers),
+            names(&theirs.security_schemes),
+            names(&theirs.links),
+            names(&theirs.callbacks),
+        ];
+        for ((kind, our_names, collisions), their_names) in kinds.iter().zip(&their_names) {
+            for (name, identical) in collisions {
+                let pointer = pointer::join(&pointer::join("/components", kind), name);
+                match (collision, identical) {
+                    (Collision::Error, _) => self.unresolved.push(Conflict::new(
+                        pointer,
+                        "the component is defined by both specs",
+                    )),
+                    (_, true) => self.resolved.push(Conflict::new(
+                        pointer,
+                        "the component is identical in both specs; kept one copy",
+                    )),
+                    (Collision::KeepIdentical, false) => self.unresolved.push(Conflict::new(
+                        pointer,
+                        "the component is defined differently by both specs",
+                    )),
+                    (Collision::Prefix(prefix), false) => {
+                        let renamed = format!("{}{}", prefix, name);
+                        if our_names.contains(&renamed) || their_names.contains(&renamed) {
+                            self.unresolved.push(Conflict::new(
+                                pointer,
+                                format!(
+                                    "cannot rename the component to `{}`, which is taken",
+                                    renamed
+                                ),
+                            ));
+                            continue;
+                        }
+                        let prefix = format!("#/components/{}/", kind);
+                        renames.insert(
+                            format!("{}{}", prefix, pointer::escape(name)),
+                            format!("{}{}", prefix, pointer::escape(&renamed)),
+                        );
+                        self.resolved.push(Conflict::new(
+                            pointer,
+                            format!(
+                                "the component is defined differently by both specs; renamed to `{}`",
+                                renamed
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        renames
+    }
+
+    /// Finds the `operationId`s of `other` which `spec` uses for a different operation.
+    fn operation_ids(&mut self, spec: &Spec, other: &Spec) {
+        let mut ours = BTreeMap::new();
+        for (path, method, operation) in spec.operations_with_callbacks() {
+            if let Some(operation_id) = &operation.operation_id {
+                ours.entry(operation_id.as_str())
+                    .or_insert((path, method, operation));
+            }
+        }
+        for (path, method, operation) in other.operations_with_callbacks() {
+            let operation_id = match &operation.operation_id {
+                Some(operation_id) => operation_id,
+                None => continue,
+            };
+            match ours.get(operation_id.as_str()) {
+                Some((our_path, our_method, our_operation))
+                    if !(*our_path == path
+                        && *our_method == method
+                        && *our_operation == operation) =>
+                {
+                    let pointer = pointer::join(&pointer::join("/paths", path), method.as_str());
+                    self.unresolved.push(Conflict::new(
+                        pointer::join(&pointer, "operationId"),
+                        format!(
+                            "operationId `{}` is already used by `{} {}`",
+                            operation_id, our_method, our_path
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Merges a path item of the other spec into the one this spec defines for the path.
+    /// Reports the operations of `path_item` whose method the path item of an equivalent
+    /// template of `spec`, such as `/pets/{id}` for `/pets/{petId}`, also defines.
+    fn equivalent_paths(&mut self, spec: &Spec, path: &str, path_item: &PathItem) {
+        let template = anonymous(path);
+        for (existing_path, existing) in &spec.paths {
+            if anonymous(existing_path) != template {
+                continue;
+            }
+            for (method, _) in path_item.operations() {
+                if existing.operation(method).is_some() {
+                    self.unresolved.push(Conflict::new(
+                        pointer::join(&pointer::join("/paths", path), method.as_str()),
+                        format!(
+                            "operation `{} {}` is defined by both specs, as `{} {}`",
+                            method, path, method, existing_path
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn path_item(&mut self, path: &str, ours: &mut PathItem, theirs: PathItem) {
+        let pointer = pointer::join("/paths", path);
+        let mut theirs = theirs;
+        for method in Method::ALL.iter().copied() {
+            let operation = match theirs.operation_slot(method).take() {
+                Some(operation) => operation,
+                None => continue,
+            };
+            match ours.operation_slot(method) {
+                Some(existing) if *existing == operation => {}
+                Some(_) => self.unresolved.push(Conflict::new(
+                    pointer::join(&pointer, method.as_str()),
+                    format!("operation `{} {}` is defined by both specs", method, path),
+                )),
+                slot @ None => *slot = Some(operation),
+            }
+        }
+        let fields = [
+            ("$ref", field(&mut ours.reference, theirs.reference)),
+            ("summary", field(&mut ours.summary, theirs.summary)),
+            (
+                "description",
+                field(&mut ours.description, theirs.description),
+            ),
+            ("servers", field(&mut ours.servers, theirs.servers)),
+            ("parameters", field(&mut ours.parameters, theirs.parameters)),
+        ];
+        for (name, merged) in fields {
+            if !merged {
+                self.unresolved.push(Conflict::new(
+                    pointer::join(&pointer, name),
+                    format!("`{}` of path `{}` differs between the specs", name, path),
+                ));
+            }
+        }
+    }
+}
+
+/// Fills in a field of this spec from the other spec, returning false if both set it
+/// differently.
+fn field<T: PartialEq>(ours: &mut Option<T>, theirs: Option<T>) -> bool {
+    match (ours.as_ref(), theirs) {
+        (Some(ours), Some(theirs)) => *ours == theirs,
+        (None, theirs) => {
+            *ours = theirs;
+            true
+        }
+        (Some(_), None) => true,
+    }
+}
+
+fn names<T>(objects: &Option<BTreeMap<String, T>>) -> Vec<String> {
+    objects
+        .iter()
+        .flatten()
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// The names both maps define, each with whether both define it identically.
+fn differing<T: PartialEq>(
+    ours: &Option<BTreeMap<String, T>>,
+    theirs: &Option<BTreeMap<String, T>>,
+) -> Vec<(String, bool)> {
+    let ours = match ours {
+        Some(ours) => ours,
+        None => return Vec::new(),
+    };
+    theirs
+        .iter()
+        .flatten()
+        .filter_map(|(name, theirs)| ours.get(name).map(|ours| (name.clone(), ours == theirs)))
+        .collect()
+}
+
+/// Renames components, rewriting references to them and to nodes below them.
+fn rename_components(spec: &mut Spec, renames: &BTreeMap<String, String>) {
+    struct Rename<'a>(&'a BTreeMap<String, String>);
+
+    impl Rename<'_> {
+        /// Renames the schemes of security requirements, which name them rather than
+        /// reference them.
+        fn security(&self, security: &mut Option<Vec<SecurityRequirement>>) {
+            for requirement in security.iter_mut().flatten() {
+                *requirement = std::mem::take(requirement)
+                    .into_iter()
+                    .map(|(name, scopes)| {
+                        let reference =
+                            format!("#{}", pointer::join("/components/securitySchemes", &name));
+                        match self.0.get(&reference).and_then(|renamed| {
+                            renamed.strip_prefix("#/components/securitySchemes/")
+                        }) {
+                            Some(renamed) => (pointer::unescape(renamed).into_owned(), scopes),
+                            None => (name, scopes),
+                        }
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    impl VisitMut for Rename<'_> {
+        fn visit_operation_mut(&mut self, pointer: &str, operation: &mut Operation) {
+            self.security(&mut operation.security);
+            visit::walk_operation_mut(self, pointer, operation)
+        }
+
+        fn visit_reference_mut(&mut self, _pointer: &str, reference: &mut String) {
+            for (from, to) in self.0 {
+                if let Some(rest) = reference.strip_prefix(from.as_str()) {
+                    if rest.is_empty() || rest.starts_with('/') {
+                        *reference = format!("{}{}", to, rest);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut rename = Rename(renames);
+    rename.security(&mut spec.security);
+    rename.visit_spec_mut(spec);
+    if let Some(components) = &mut spec.components {
+        rename_keys(&mut components.schemas, "schemas", renames);
+        rename_keys(&mut components.responses, "responses", renames);
+        rename_keys(&mut components.parameters, "parameters", renames);
+        rename_keys(&mut components.examples, "examples", renames);
+        rename_keys(&mut components.request_bodies, "requestBodies", renames);
+        rename_keys(&mut components.headers, "headers", renames);
+        rename_keys(&mut components.security_schemes, "securitySchemes", renames);
+        rename_keys(&mut components.links, "links", renames);
+        rename_keys(&mut components.callbacks, "callbacks", renames);
+    }
+}
+
+fn rename_keys<T>(
+    objects: &mut Option<BTreeMap<String, T>>,
+    kind: &str,
+    renames: &BTreeMap<String, String>,
+) {
+    let objects = match objects {
+        Some(objects) => objects,
+        None => return,
+    };
+    let prefix = format!("#/components/{}/", kind);
+    for (from, to) in renames {
+        let (from, to) = match (from.strip_prefix(&prefix), to.strip_prefix(&prefix)) {
+            (Some(from), Some(to)) => (pointer::unescape(from), pointer::unescape(to)),
+            _ => continue,
+        };
+        if let Some(object) = objects.remove(from.as_ref()) {
+            objects.insert(to.into_owned(), object);
+        }
+    }
+}
+
+/// Adds the components of the other spec which this one does not define.
+fn merge_components(spec: &mut Spec, theirs: Option<Components>) {
+    let theirs = match theirs {
+        Some(theirs) => theirs,
+        None => return,
+    };
+    let ours = spec.components.get_or_insert_with(Components::default);
+    union(&mut ours.schemas, theirs.schemas);
+    union(&mut ours.responses, theirs.responses);
+    union(&mut ours.parameters, theirs.parameters);
+    union(&mut ours.examples, theirs.examples);
+    union(&mut ours.request_bodies, theirs.request_bodies);
+    union(&mut ours.headers, theirs.headers);
+    union(&mut ours.security_schemes, theirs.security_schemes);
+    union(&mut ours.links, theirs.links);
+    union(&mut ours.callbacks, theirs.callbacks);
+}
+
+fn union<T>(
+    ours: &mut Option<BTreeMap<String, ObjectOrReference<T>>>,
+    theirs: Option<BTreeMap<String, ObjectOrReference<T>>>,
+) {
+    for (name, object) in theirs.into_iter().flatten() {
+        ours.get_or_insert_with(BTreeMap::new)
+            .entry(name)
+            .or_insert(object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(yaml: &str) -> Spec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const PETS: &str = r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+tags:
+  - name: pets
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+components:
+  schemas:
+    Error:
+      type: string
+    Pet:
+      properties:
+        name:
+          type: string
+"##;
+
+    const STORE: &str = r##"
+openapi: 3.0.0
+info:
+  title: Store
+  version: 1.0.0
+tags:
+  - name: pets
+  - name: orders
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        '201':
+          description: Created
+  /orders:
+    get:
+      operationId: listOrders
+      responses:
+        '200':
+          description: Orders
+components:
+  schemas:
+    Error:
+      type: string
+    Pet:
+      properties:
+        price:
+          type: number
+"##;
+
+    #[test]
+    fn merges_with_prefixed_components() {
+        let mut merged = spec(PETS);
+        let resolved = merged
+            .merge(spec(STORE), &Collision::Prefix("Store".into()))
+            .unwrap();
+        assert_eq!(
+            resolved.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "`/components/schemas/Error`: the component is identical in both specs; kept one copy",
+                "`/components/schemas/Pet`: the component is defined differently by both specs; renamed to `StorePet`",
+            ]
+        );
+        let schemas = merged
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            vec!["Error", "Pet", "StorePet"]
+        );
+        let post = merged.paths["/pets"].post.as_ref().unwrap();
+        let body = post.request_body.as_ref().unwrap();
+        assert!(serde_json::to_string(body)
+            .unwrap()
+            .contains("#/components/schemas/StorePet"));
+        assert!(merged.paths["/pets"].get.is_some());
+        assert!(merged.paths.contains_key("/orders"));
+        assert_eq!(merged.tags.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_unresolved_conflicts() {
+        let mut merged = spec(PETS);
+        let mut other = spec(STORE);
+        other
+            .paths
+            .get_mut("/orders")
+            .unwrap()
+            .get
+            .as_mut()
+            .unwrap()
+            .operation_id = Some("listPets".into());
+        match merged.merge(other, &Collision::KeepIdentical) {
+            Err(Error::MergeConflicts(conflicts)) => assert_eq!(
+                conflicts.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                vec![
+                    "`/components/schemas/Pet`: the component is defined differently by both specs",
+                    "`/paths/~1orders/get/operationId`: operationId `listPets` is already used by `GET /pets`",
+                ]
+            ),
+            other => panic!("expected conflicts, got {:?}", other),
+        }
+        assert_eq!(merged, spec(PETS));
+    }
+
+    #[test]
+    fn reports_operations_on_equivalent_paths() {
+        let pets = |name: &str| {
+            spec(&format!(
+                "
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets/{{{}}}:
+    get:
+      responses:
+        '200':
+          description: A pet
+",
+                name
+            ))
+        };
+        let mut merged = pets("id");
+        match merged.merge(pets("petId"), &Collision::KeepIdentical) {
+            Err(Error::MergeConflicts(conflicts)) => assert_eq!(
+                conflicts
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                vec![
+                    "`/paths/~1pets~1{petId}/get`: operation `GET /pets/{petId}` is defined by \
+                     both specs, as `GET /pets/{id}`",
+                ]
+            ),
+            other => panic!("expected conflicts, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn renames_security_schemes_and_keeps_global_security() {
+        let mut merged = spec(
+            r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+security:
+  - key: []
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: Pets
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      name: X-Key
+      in: header
+"#,
+        );
+        let other = spec(
+            r#"
+openapi: 3.0.0
+info:
+  title: Store
+  version: 1.0.0
+security:
+  - key: []
+paths:
+  /orders:
+    get:
+      responses:
+        '200':
+          description: Orders
+  /health:
+    get:
+      security: []
+      responses:
+        '200':
+          description: Healthy
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      name: key
+      in: query
+"#,
+        );
+        merged
+            .merge(other, &Collision::Prefix("store".into()))
+            .unwrap();
+        let security = |path: &str| merged.paths[path].get.as_ref().unwrap().security.clone();
+        let mut store_key = SecurityRequirement::new();
+        store_key.insert("storekey".to_string(), Vec::new());
+        assert_eq!(security("/orders"), Some(vec![store_key]));
+        assert_eq!(security("/health"), Some(Vec::new()));
+        assert_eq!(security("/pets"), None);
+        assert_eq!(
+            merged.security.as_ref().unwrap()[0]
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["key"]
+        );
+        let schemes = merged
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .as_ref()
+            .unwrap();
+        assert_eq!(schemes.keys().collect::<Vec<_>>(), vec!["key", "storekey"]);
+    }
+}
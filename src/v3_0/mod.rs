@@ -4,11 +4,34 @@
 //! [specification](https://github.com/OAI/OpenAPI-Specification/blob/0dd79f6/versions/3.0.1.md)
 //! for more information.
 
+mod builder;
+mod compat;
 mod components;
-mod extension;
+mod diff;
+mod merge;
+mod node;
+mod normalize;
+mod operations;
+mod parameters;
+mod reachable;
+mod router;
 mod schema;
+mod server;
+mod split;
+mod style;
+mod to_schema;
+pub mod validation;
+pub mod visit;
 
-pub use crate::v3_0::{components::*, extension::*, schema::*};
+pub use crate::v3_0::{
+    builder::*, compat::*, components::*, diff::*, merge::*, node::*, router::*, schema::*,
+    server::*, to_schema::*,
+};
+
+pub use crate::extension::Extensions;
+
+#[cfg(feature = "derive")]
+pub use openapi_derive::ToSchema;
 
 // Yet OpenAPI dont have an implemented representation
 // the `serde_json::Value` is used in place of a custom enum
@@ -0,0 +1,239 @@
+//! Addressing of the nodes of a spec by [JSON pointer](crate::pointer).
+
+use crate::{
+    pointer,
+    v3_0::{
+        visit::{self, Visit},
+        Callback, Components, Example, Header, Link, MediaType, Operation, Parameter, PathItem,
+        RequestBody, Response, Schema, SecurityScheme, Spec,
+    },
+};
+use std::ptr;
+
+/// A reference to a node of a spec.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Node<'a> {
+    Spec(&'a Spec),
+    PathItem(&'a PathItem),
+    Operation(&'a Operation),
+    Parameter(&'a Parameter),
+    RequestBody(&'a RequestBody),
+    Response(&'a Response),
+    MediaType(&'a MediaType),
+    Header(&'a Header),
+    Schema(&'a Schema),
+    Callback(&'a Callback),
+    Components(&'a Components),
+    Example(&'a Example),
+    Link(&'a Link),
+    SecurityScheme(&'a SecurityScheme),
+    /// The `$ref` of a reference object.
+    Reference(&'a str),
+}
+
+impl Node<'_> {
+    /// Returns true if both refer to the same node, rather than to equal ones.
+    pub fn is(&self, other: &Node<'_>) -> bool {
+        match (self, other) {
+            (Node::Spec(a), Node::Spec(b)) => ptr::eq(*a, *b),
+            (Node::PathItem(a), Node::PathItem(b)) => ptr::eq(*a, *b),
+            (Node::Operation(a), Node::Operation(b)) => ptr::eq(*a, *b),
+            (Node::Parameter(a), Node::Parameter(b)) => ptr::eq(*a, *b),
+            (Node::RequestBody(a), Node::RequestBody(b)) => ptr::eq(*a, *b),
+            (Node::Response(a), Node::Response(b)) => ptr::eq(*a, *b),
+            (Node::MediaType(a), Node::MediaType(b)) => ptr::eq(*a, *b),
+            (Node::Header(a), Node::Header(b)) => ptr::eq(*a, *b),
+            (Node::Schema(a), Node::Schema(b)) => ptr::eq(*a, *b),
+            (Node::Callback(a), Node::Callback(b)) => ptr::eq(*a, *b),
+            (Node::Components(a), Node::Components(b)) => ptr::eq(*a, *b),
+            (Node::Example(a), Node::Example(b)) => ptr::eq(*a, *b),
+            (Node::Link(a), Node::Link(b)) => ptr::eq(*a, *b),
+            (Node::SecurityScheme(a), Node::SecurityScheme(b)) => ptr::eq(*a, *b),
+            (Node::Reference(a), Node::Reference(b)) => ptr::eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Spec {
+    /// The node a JSON pointer, such as `/components/schemas/Pet/properties/name`, refers
+    /// to. The pointer may also be given as a URI fragment, as in `$ref`s.
+    ///
+    /// Only nodes which are visited by [`Visit`] can be resolved. A pointer to an object
+    /// holding a `$ref` resolves to its schema if it is a schema, and to the reference
+    /// otherwise; references are not followed.
+    pub fn resolve_pointer(&self, pointer: &str) -> Option<Node<'_>> {
+        let pointer = pointer::from_fragment(pointer);
+        let mut locate = Locate {
+            target: Target::Pointer(&pointer),
+            found: None,
+        };
+        locate.visit_spec(self);
+        locate.found.map(|(_, node)| node)
+    }
+
+    /// The JSON pointer of a node of this spec, or `None` if the node is not part of it.
+    pub fn pointer_of(&self, node: Node<'_>) -> Option<String> {
+        let mut locate = Locate {
+            target: Target::Node(node),
+            found: None,
+        };
+        locate.visit_spec(self);
+        locate.found.map(|(pointer, _)| pointer)
+    }
+}
+
+enum Target<'t> {
+    Pointer(&'t str),
+    Node(Node<'t>),
+}
+
+/// Finds a node by pointer or identity, visiting only what may lead to it.
+struct Locate<'a, 't> {
+    target: Target<'t>,
+    found: Option<(String, Node<'a>)>,
+}
+
+impl<'a> Locate<'a, '_> {
+    /// Records the node if it is the target, and returns whether to visit its children.
+    fn enter(&mut self, pointer: &str, node: Node<'a>) -> bool {
+        if self.found.is_some() {
+            return false;
+        }
+        let (found, descend) = match &self.target {
+            Target::Pointer(target) => (*target == pointer, pointer::is_ancestor(pointer, target)),
+            Target::Node(target) => (target.is(&node), true),
+        };
+        if found {
+            self.found = Some((pointer.to_string(), node));
+        }
+        descend && !found
+    }
+}
+
+impl<'a> Visit<'a> for Locate<'a, '_> {
+    fn visit_spec(&mut self, spec: &'a Spec) {
+        if self.enter("", Node::Spec(spec)) {
+            visit::walk_spec(self, spec)
+        }
+    }
+
+    fn visit_path_item(&mut self, pointer: &str, path_item: &'a PathItem) {
+        if self.enter(pointer, Node::PathItem(path_item)) {
+            visit::walk_path_item(self, pointer, path_item)
+        }
+    }
+
+    fn visit_operation(&mut self, pointer: &str, operation: &'a Operation) {
+        if self.enter(pointer, Node::Operation(operation)) {
+            visit::walk_operation(self, pointer, operation)
+        }
+    }
+
+    fn visit_parameter(&mut self, pointer: &str, parameter: &'a Parameter) {
+        if self.enter(pointer, Node::Parameter(parameter)) {
+            visit::walk_parameter(self, pointer, parameter)
+        }
+    }
+
+    fn visit_request_body(&mut self, pointer: &str, request_body: &'a RequestBody) {
+        if self.enter(pointer, Node::RequestBody(request_body)) {
+            visit::walk_request_body(self, pointer, request_body)
+        }
+    }
+
+    fn visit_response(&mut self, pointer: &str, response: &'a Response) {
+        if self.enter(pointer, Node::Response(response)) {
+            visit::walk_response(self, pointer, response)
+        }
+    }
+
+    fn visit_media_type(&mut self, pointer: &str, media_type: &'a MediaType) {
+        if self.enter(pointer, Node::MediaType(media_type)) {
+            visit::walk_media_type(self, pointer, media_type)
+        }
+    }
+
+    fn visit_header(&mut self, pointer: &str, header: &'a Header) {
+        if self.enter(pointer, Node::Header(header)) {
+            visit::walk_header(self, pointer, header)
+        }
+    }
+
+    fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+        if self.enter(pointer, Node::Schema(schema)) {
+            visit::walk_schema(self, pointer, schema)
+        }
+    }
+
+    fn visit_callback(&mut self, pointer: &str, callback: &'a Callback) {
+        if self.enter(pointer, Node::Callback(callback)) {
+            visit::walk_callback(self, pointer, callback)
+        }
+    }
+
+    fn visit_components(&mut self, pointer: &str, components: &'a Components) {
+        if self.enter(pointer, Node::Components(components)) {
+            visit::walk_components(self, pointer, components)
+        }
+    }
+
+    fn visit_example(&mut self, pointer: &str, example: &'a Example) {
+        self.enter(pointer, Node::Example(example));
+    }
+
+    fn visit_link(&mut self, pointer: &str, link: &'a Link) {
+        self.enter(pointer, Node::Link(link));
+    }
+
+    fn visit_security_scheme(&mut self, pointer: &str, security_scheme: &'a SecurityScheme) {
+        self.enter(pointer, Node::SecurityScheme(security_scheme));
+    }
+
+    fn visit_reference(&mut self, pointer: &str, reference: &'a str) {
+        self.enter(pointer, Node::Reference(reference));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn petstore() -> Spec {
+        serde_yaml::from_reader(File::open("data/v3.0/petstore.yaml").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn resolves_pointers() {
+        let spec = petstore();
+        match spec.resolve_pointer("/components/schemas/Pet/properties/name") {
+            Some(Node::Schema(schema)) => {
+                assert_eq!(schema.schema_type.as_deref(), Some("string"))
+            }
+            other => panic!("unexpected node {:?}", other),
+        }
+        match spec.resolve_pointer("#/paths/~1pets~1%7BpetId%7D/get") {
+            Some(Node::Operation(operation)) => {
+                assert_eq!(operation.operation_id.as_deref(), Some("showPetById"))
+            }
+            other => panic!("unexpected node {:?}", other),
+        }
+        assert!(matches!(spec.resolve_pointer(""), Some(Node::Spec(_))));
+        assert_eq!(spec.resolve_pointer("/components/schemas/Dog"), None);
+        assert_eq!(spec.resolve_pointer("/info"), None);
+    }
+
+    #[test]
+    fn computes_pointers_of_nodes() {
+        let spec = petstore();
+        let pets = &spec.paths["/pets"];
+        let response = &pets.get.as_ref().unwrap().responses["200"];
+        assert_eq!(
+            spec.pointer_of(Node::Response(response)).as_deref(),
+            Some("/paths/~1pets/get/responses/200")
+        );
+        let copy = response.clone();
+        assert_eq!(spec.pointer_of(Node::Response(&copy)), None);
+    }
+}
@@ -0,0 +1,538 @@
+//! Normalization of where a spec defines its schemas, moving them between the media types
+//! which use them and `components.schemas`.
+
+use crate::{
+    codegen::type_name,
+    pointer,
+    v3_0::{
+        visit::{self, Visit, VisitMut},
+        BooleanObjectOrReference, Components, MediaType, ObjectOrReference, Operation, Schema,
+        Spec,
+    },
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+impl Spec {
+    /// Moves the inline object schemas of request bodies and responses, including those of
+    /// callbacks, into `components.schemas`, replacing them with references. Returns the
+    /// names of the components added.
+    ///
+    /// Components are named after the `operationId`, or else the method and path, of their
+    /// operation, as in `CreatePetRequest` and `CreatePet201Response`, with a number
+    /// appended if the name is taken. Identical schemas share one component, named after
+    /// the first operation using it with an `operationId`, or else the first operation using
+    /// it. A schema identical to an existing component references that component instead.
+    pub fn extract_inline_schemas(&mut self) -> Vec<String> {
+        let mut schemas = self
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.take())
+            .unwrap_or_default();
+
+        // name each distinct schema before extracting any, so that names do not depend on
+        // which operation happens to come first
+        let mut components = schemas
+            .iter()
+            .filter_map(|(name, schema)| match schema {
+                ObjectOrReference::Object(schema) => Some((key(schema), name.clone())),
+                ObjectOrReference::Ref { .. } => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let mut extracted: Vec<(Schema, String, bool)> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for (path, method, operation) in self.operations_with_callbacks() {
+            let named = operation.operation_id.is_some();
+            for (name, schema) in inline_schemas(path, method.as_str(), operation) {
+                let key = key(&schema);
+                if components.contains_key(&key) {
+                    continue;
+                }
+                match indices.get(&key) {
+                    Some(&index) => {
+                        let (_, other, other_named) = &mut extracted[index];
+                        if named && !*other_named {
+                            *other = name;
+                            *other_named = true;
+                        }
+                    }
+                    None => {
+                        indices.insert(key, extracted.len());
+                        extracted.push((schema, name, named));
+                    }
+                }
+            }
+        }
+        let mut names = Vec::new();
+        for (schema, name, _) in extracted {
+            let name = (1..)
+                .map(|n| match n {
+                    1 => name.clone(),
+                    n => format!("{}{}", name, n),
+                })
+                .find(|name| !schemas.contains_key(name))
+                .expect("an unused name");
+            components.insert(key(&schema), name.clone());
+            schemas.insert(name.clone(), ObjectOrReference::Object(schema));
+            names.push(name);
+        }
+
+        self.for_each_operation_mut(true, |_, _, operation| {
+            if let Some(ObjectOrReference::Object(request_body)) = &mut operation.request_body {
+                for media_type in request_body.content.values_mut() {
+                    extract(media_type, &components);
+                }
+            }
+            for response in operation.responses.values_mut() {
+                for media_type in response
+                    .content
+                    .iter_mut()
+                    .flat_map(|content| content.values_mut())
+                {
+                    extract(media_type, &components);
+                }
+            }
+        });
+        if !schemas.is_empty() {
+            self.components
+                .get_or_insert_with(Components::default)
+                .schemas = Some(schemas);
+        }
+        names
+    }
+
+    /// Replaces the only reference to each component of `components.schemas` referenced
+    /// exactly once with the schema itself, removing the component. Returns the names of
+    /// the components inlined.
+    ///
+    /// Components which reference themselves, are referenced by a pointer below them, are
+    /// named by a discriminator mapping or are only referenced from a cycle of such
+    /// components are kept.
+    pub fn inline_single_use_schemas(&mut self) -> Vec<String> {
+        let mut inline = Inline {
+            schemas: BTreeMap::new(),
+        };
+        let candidates = single_use(self);
+        if let Some(schemas) = self
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.as_mut())
+        {
+            for name in &candidates {
+                if let Some(schema) = schemas.remove(name) {
+                    let reference = format!("{}{}", SCHEMAS, pointer::escape(name));
+                    inline.schemas.insert(reference, schema);
+                }
+            }
+        }
+        inline.visit_spec_mut(self);
+
+        // put back any component whose reference was not found where a schema belongs
+        let mut inlined = candidates;
+        for (reference, schema) in inline.schemas {
+            let name = pointer::unescape(&reference[SCHEMAS.len()..]).into_owned();
+            inlined.retain(|inlined| *inlined != name);
+            self.components
+                .get_or_insert_with(Components::default)
+                .schemas
+                .get_or_insert_with(BTreeMap::new)
+                .insert(name, schema);
+        }
+        if let Some(components) = &mut self.components {
+            if components
+                .schemas
+                .as_ref()
+                .is_some_and(|schemas| schemas.is_empty())
+            {
+                components.schemas = None;
+            }
+        }
+        inlined
+    }
+}
+
+/// The inline object schemas of an operation's request body and responses, each with the
+/// name it would be extracted under.
+fn inline_schemas(path: &str, method: &str, operation: &Operation) -> Vec<(String, Schema)> {
+    let base = match &operation.operation_id {
+        Some(operation_id) => operation_id.clone(),
+        None => format!("{} {}", method, path),
+    };
+    let mut schemas = Vec::new();
+    if let Some(ObjectOrReference::Object(request_body)) = &operation.request_body {
+        let name = type_name(&format!("{} request", base));
+        for media_type in request_body.content.values() {
+            if let Some(schema) = extractable(media_type) {
+                schemas.push((name.clone(), schema.clone()));
+            }
+        }
+    }
+    for (status, response) in &operation.responses {
+        let name = type_name(&format!("{} {} response", base, status));
+        for media_type in response.content.iter().flat_map(|content| content.values()) {
+            if let Some(schema) = extractable(media_type) {
+                schemas.push((name.clone(), schema.clone()));
+            }
+        }
+    }
+    schemas
+}
+
+/// The schema of a media type, if it is an inline object schema.
+fn extractable(media_type: &MediaType) -> Option<&Schema> {
+    match &media_type.schema {
+        Some(ObjectOrReference::Object(schema))
+            if schema.ref_path.is_none()
+                && (schema.schema_type.as_deref() == Some("object")
+                    || schema.properties.is_some()) =>
+        {
+            Some(schema)
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the inline object schema of a media type with a reference to the identical
+/// component, given the names of components by [`key`].
+fn extract(media_type: &mut MediaType, components: &HashMap<String, String>) {
+    let name = match extractable(media_type).and_then(|schema| components.get(&key(schema))) {
+        Some(name) => name,
+        None => return,
+    };
+    media_type.schema = Some(ObjectOrReference::Ref {
+        ref_path: format!("{}{}", SCHEMAS, pointer::escape(name)),
+    });
+}
+
+/// A key identical schemas share, as their serialized form with sorted fields.
+fn key(schema: &Schema) -> String {
+    serde_json::to_value(schema)
+        .map(|value| value.to_string())
+        .unwrap_or_else(|_| format!("{:?}", schema))
+}
+
+/// The names of the component schemas which can be inlined.
+fn single_use(spec: &Spec) -> Vec<String> {
+    let mut uses = Uses::default();
+    uses.visit_spec(spec);
+    let schemas = match spec
+        .components
+        .as_ref()
+        .and_then(|components| components.schemas.as_ref())
+    {
+        Some(schemas) => schemas,
+        None => return Vec::new(),
+    };
+
+    // each candidate, with the candidate whose schema holds its only reference, if any
+    let mut owners = BTreeMap::new();
+    for name in schemas.keys() {
+        let component = pointer::join("/components/schemas", name);
+        let reference = format!("#{}", component);
+        let below = format!("{}/", reference);
+        let referenced_below = uses
+            .references
+            .range(below.clone()..)
+            .next()
+            .is_some_and(|(used, _)| used.starts_with(&below));
+        if uses.pinned.contains(name) || referenced_below {
+            continue;
+        }
+        match uses.references.get(&reference).map(Vec::as_slice) {
+            Some([pointer])
+                if *pointer != component && !pointer::is_ancestor(&component, pointer) =>
+            {
+                let owner = pointer
+                    .strip_prefix("/components/schemas/")
+                    .and_then(|rest| rest.split('/').next())
+                    .map(|owner| pointer::unescape(owner).into_owned());
+                owners.insert(name.clone(), owner);
+            }
+            _ => {}
+        }
+    }
+
+    // candidates only referenced from a cycle of candidates would be lost with it
+    let in_cycle = owners
+        .keys()
+        .filter(|name| {
+            let mut visited = BTreeSet::new();
+            let mut current = owners.get(*name).cloned().flatten();
+            while let Some(owner) = current {
+                if owner == **name {
+                    return true;
+                }
+                if !visited.insert(owner.clone()) {
+                    return false;
+                }
+                current = owners.get(&owner).cloned().flatten();
+            }
+            false
+        })
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    owners
+        .into_keys()
+        .filter(|name| !in_cycle.contains(name))
+        .collect()
+}
+
+/// The pointers of the objects holding each `$ref`, and the names of the schemas
+/// discriminator mappings name.
+#[derive(Default)]
+struct Uses {
+    references: BTreeMap<String, Vec<String>>,
+    pinned: BTreeSet<String>,
+}
+
+impl<'a> Visit<'a> for Uses {
+    fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+        let mapping = schema
+            .discriminator
+            .iter()
+            .flat_map(|discriminator| discriminator.mapping.iter().flatten());
+        for (_, target) in mapping {
+            let name = match target.strip_prefix(SCHEMAS) {
+                Some(name) => pointer::unescape(name).into_owned(),
+                None => target.clone(),
+            };
+            self.pinned.insert(name);
+        }
+        visit::walk_schema(self, pointer, schema)
+    }
+
+    fn visit_reference(&mut self, pointer: &str, reference: &'a str) {
+        self.references
+            .entry(reference.to_string())
+            .or_default()
+            .push(pointer.to_string());
+    }
+}
+
+/// Replaces references to the schemas it holds with the schemas themselves, walking into
+/// each so that the schemas it refers to are inlined in turn.
+struct Inline {
+    schemas: BTreeMap<String, ObjectOrReference<Schema>>,
+}
+
+impl Inline {
+    fn take(&mut self, reference: &str) -> Option<Schema> {
+        self.schemas.remove(reference).map(|schema| match schema {
+            ObjectOrReference::Object(schema) => schema,
+            ObjectOrReference::Ref { ref_path } => Schema {
+                ref_path: Some(ref_path),
+                ..Schema::default()
+            },
+        })
+    }
+
+    fn object_or_reference(&mut self, schema: &mut ObjectOrReference<Schema>) {
+        if let ObjectOrReference::Ref { ref_path } = schema {
+            if let Some(inlined) = self.take(ref_path) {
+                *schema = ObjectOrReference::Object(inlined);
+            }
+        }
+    }
+}
+
+impl VisitMut for Inline {
+    fn visit_components_mut(&mut self, pointer: &str, components: &mut Components) {
+        for schema in components
+            .schemas
+            .iter_mut()
+            .flat_map(|schemas| schemas.values_mut())
+        {
+            self.object_or_reference(schema);
+        }
+        visit::walk_components_mut(self, pointer, components)
+    }
+
+    fn visit_media_type_mut(&mut self, pointer: &str, media_type: &mut MediaType) {
+        if let Some(schema) = &mut media_type.schema {
+            self.object_or_reference(schema);
+        }
+        visit::walk_media_type_mut(self, pointer, media_type)
+    }
+
+    fn visit_schema_mut(&mut self, pointer: &str, schema: &mut Schema) {
+        while let Some(inlined) = schema
+            .ref_path
+            .as_deref()
+            .and_then(|reference| self.take(reference))
+        {
+            *schema = inlined;
+        }
+        for subschemas in [
+            &mut schema.all_of,
+            &mut schema.one_of,
+            &mut schema.any_of,
+            &mut schema.not,
+        ] {
+            for subschema in subschemas.iter_mut().flatten() {
+                self.object_or_reference(subschema);
+            }
+        }
+        if let Some(BooleanObjectOrReference::Ref { ref_path }) = &schema.additional_properties {
+            if let Some(inlined) = self.take(ref_path) {
+                schema.additional_properties =
+                    Some(BooleanObjectOrReference::Object(Box::new(inlined)));
+            }
+        }
+        visit::walk_schema_mut(self, pointer, schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_and_inlines_schemas() {
+        let original: Spec = serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+      responses:
+        '201':
+          description: Created
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+    get:
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+  /owners:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+      responses:
+        '204':
+          description: Created
+components:
+  schemas:
+    Pet:
+      properties:
+        owner:
+          $ref: "#/components/schemas/Owner"
+    Owner:
+      type: object
+"##,
+        )
+        .unwrap();
+        let mut spec = original.clone();
+        assert_eq!(spec.extract_inline_schemas(), vec!["CreatePetRequest"]);
+        for path in ["/pets", "/owners"] {
+            let body = match &spec.paths[path].post.as_ref().unwrap().request_body {
+                Some(ObjectOrReference::Object(body)) => body,
+                other => panic!("unexpected request body {:?}", other),
+            };
+            assert_eq!(
+                body.content["application/json"].schema,
+                Some(ObjectOrReference::Ref {
+                    ref_path: "#/components/schemas/CreatePetRequest".into()
+                })
+            );
+        }
+
+        assert_eq!(spec.inline_single_use_schemas(), vec!["Owner"]);
+        let schemas = spec.components.as_ref().unwrap().schemas.as_ref().unwrap();
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            vec!["CreatePetRequest", "Pet"]
+        );
+        match &schemas["Pet"] {
+            ObjectOrReference::Object(pet) => {
+                assert_eq!(
+                    pet.properties.as_ref().unwrap()["owner"]
+                        .schema_type
+                        .as_deref(),
+                    Some("object")
+                )
+            }
+            other => panic!("unexpected schema {:?}", other),
+        }
+    }
+
+    #[test]
+    fn inlines_chains_but_not_cycles() {
+        let mut spec: Spec = serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Chains
+  version: 1.0.0
+paths:
+  /a:
+    get:
+      responses:
+        '200':
+          description: A
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/A"
+components:
+  schemas:
+    A:
+      properties:
+        b:
+          $ref: "#/components/schemas/B"
+    B:
+      type: string
+    C:
+      properties:
+        d:
+          $ref: "#/components/schemas/D"
+        e:
+          $ref: "#/components/schemas/E"
+    D:
+      properties:
+        c:
+          $ref: "#/components/schemas/C"
+    E:
+      type: integer
+"##,
+        )
+        .unwrap();
+        assert_eq!(spec.inline_single_use_schemas(), vec!["A", "B", "E"]);
+        let schemas = spec.components.as_ref().unwrap().schemas.as_ref().unwrap();
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), vec!["C", "D"]);
+        let response = &spec.paths["/a"].get.as_ref().unwrap().responses["200"];
+        match &response.content.as_ref().unwrap()["application/json"].schema {
+            Some(ObjectOrReference::Object(a)) => assert_eq!(
+                a.properties.as_ref().unwrap()["b"].schema_type.as_deref(),
+                Some("string")
+            ),
+            other => panic!("unexpected schema {:?}", other),
+        }
+    }
+}
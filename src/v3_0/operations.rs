@@ -0,0 +1,324 @@
+//! Access to the operations of path items by HTTP method.
+
+use crate::{
+    v3_0::{Operation, PathItem, Spec},
+    Method, OperationIndex, Result,
+};
+
+impl PathItem {
+    /// The operation defined for a method, if any.
+    pub fn operation(&self, method: Method) -> Option<&Operation> {
+        match method {
+            Method::Get => self.get.as_ref(),
+            Method::Put => self.put.as_ref(),
+            Method::Post => self.post.as_ref(),
+            Method::Delete => self.delete.as_ref(),
+            Method::Options => self.options.as_ref(),
+            Method::Head => self.head.as_ref(),
+            Method::Patch => self.patch.as_ref(),
+            Method::Trace => self.trace.as_ref(),
+        }
+    }
+
+    /// The mutable operation defined for a method, if any.
+    pub fn operation_mut(&mut self, method: Method) -> Option<&mut Operation> {
+        match method {
+            Method::Get => self.get.as_mut(),
+            Method::Put => self.put.as_mut(),
+            Method::Post => self.post.as_mut(),
+            Method::Delete => self.delete.as_mut(),
+            Method::Options => self.options.as_mut(),
+            Method::Head => self.head.as_mut(),
+            Method::Patch => self.patch.as_mut(),
+            Method::Trace => self.trace.as_mut(),
+        }
+    }
+
+    /// The field holding the operation for a method.
+    pub(crate) fn operation_slot(&mut self, method: Method) -> &mut Option<Operation> {
+        match method {
+            Method::Get => &mut self.get,
+            Method::Put => &mut self.put,
+            Method::Post => &mut self.post,
+            Method::Delete => &mut self.delete,
+            Method::Options => &mut self.options,
+            Method::Head => &mut self.head,
+            Method::Patch => &mut self.patch,
+            Method::Trace => &mut self.trace,
+        }
+    }
+
+    /// The operations defined on this path item, in the order of `Method::ALL`.
+    pub fn operations(&self) -> impl Iterator<Item = (Method, &Operation)> {
+        Method::ALL.iter().filter_map(move |method| {
+            self.operation(*method)
+                .map(|operation| (*method, operation))
+        })
+    }
+
+    /// The mutable operations defined on this path item, in the order of `Method::ALL`.
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (Method, &mut Operation)> {
+        vec![
+            (Method::Get, self.get.as_mut()),
+            (Method::Put, self.put.as_mut()),
+            (Method::Post, self.post.as_mut()),
+            (Method::Delete, self.delete.as_mut()),
+            (Method::Options, self.options.as_mut()),
+            (Method::Head, self.head.as_mut()),
+            (Method::Patch, self.patch.as_mut()),
+            (Method::Trace, self.trace.as_mut()),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.map(|operation| (method, operation)))
+    }
+}
+
+impl Operation {
+    /// The operations of this operation's callbacks, and of their callbacks in turn, along
+    /// with the callback expression they are defined for.
+    pub fn callback_operations(&self) -> impl Iterator<Item = (&str, Method, &Operation)> {
+        let mut operations = Vec::new();
+        collect_callback_operations(self, &mut operations);
+        operations.into_iter()
+    }
+}
+
+fn collect_callback_operations<'a>(
+    operation: &'a Operation,
+    operations: &mut Vec<(&'a str, Method, &'a Operation)>,
+) {
+    for callback in operation
+        .callbacks
+        .iter()
+        .flat_map(|callbacks| callbacks.values())
+    {
+        for (expression, path_item) in &callback.0 {
+            for (method, operation) in path_item.operations() {
+                operations.push((expression.as_str(), method, operation));
+                collect_callback_operations(operation, operations);
+            }
+        }
+    }
+}
+
+fn for_each_callback_operation_mut<F>(operation: &mut Operation, visit: &mut F)
+where
+    F: FnMut(&str, Method, &mut Operation),
+{
+    for callback in operation
+        .callbacks
+        .iter_mut()
+        .flat_map(|callbacks| callbacks.values_mut())
+    {
+        for (expression, path_item) in callback.0.iter_mut() {
+            for (method, operation) in path_item.operations_mut() {
+                visit(expression, method, operation);
+                for_each_callback_operation_mut(operation, visit);
+            }
+        }
+    }
+}
+
+impl Spec {
+    /// The operations of every path, along with their path and method.
+    pub fn operations(&self) -> impl Iterator<Item = (&str, Method, &Operation)> {
+        self.paths.iter().flat_map(|(path, path_item)| {
+            path_item
+                .operations()
+                .map(move |(method, operation)| (path.as_str(), method, operation))
+        })
+    }
+
+    /// The mutable operations of every path, along with their path and method.
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (&str, Method, &mut Operation)> {
+        self.paths.iter_mut().flat_map(|(path, path_item)| {
+            path_item
+                .operations_mut()
+                .map(move |(method, operation)| (path.as_str(), method, operation))
+        })
+    }
+
+    /// The operations of every path, each followed by the operations of its callbacks.
+    /// Callback operations are given with their callback expression in place of a path.
+    pub fn operations_with_callbacks(&self) -> impl Iterator<Item = (&str, Method, &Operation)> {
+        self.operations().flat_map(|(path, method, operation)| {
+            std::iter::once((path, method, operation)).chain(operation.callback_operations())
+        })
+    }
+
+    /// Calls `visit` with every mutable operation, optionally followed by the operations of
+    /// its callbacks, given with their callback expression in place of a path.
+    ///
+    /// Unlike an iterator, this allows callback operations to be modified as their
+    /// parent operations are themselves modifiable.
+    pub fn for_each_operation_mut<F>(&mut self, include_callbacks: bool, mut visit: F)
+    where
+        F: FnMut(&str, Method, &mut Operation),
+    {
+        for (path, method, operation) in self.operations_mut() {
+            visit(path, method, operation);
+            if include_callbacks {
+                for_each_callback_operation_mut(operation, &mut visit);
+            }
+        }
+    }
+
+    /// Indexes the operations of this spec, including those of callbacks, by their
+    /// `operationId`. Fails if an `operationId` is not unique.
+    pub fn operation_index<'a>(&'a self) -> Result<OperationIndex<'a, Operation>> {
+        let with_id = |(path, method, operation): (&'a str, Method, &'a Operation)| {
+            (path, method, operation, operation.operation_id.as_deref())
+        };
+        OperationIndex::new(
+            self.operations().map(with_id),
+            self.operations()
+                .flat_map(|(_, _, operation)| operation.callback_operations())
+                .map(with_id),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn spec(path: &str) -> Spec {
+        match from_path(path).unwrap() {
+            OpenApi::V3_0(spec) => spec,
+            _ => panic!("expected a v3.0 spec"),
+        }
+    }
+
+    #[test]
+    fn iterates_operations() {
+        let spec = spec("data/v3.0/petstore.yaml");
+        let operations = spec
+            .operations()
+            .map(|(path, method, operation)| {
+                (path, method, operation.operation_id.as_deref().unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                ("/pets", Method::Get, "listPets"),
+                ("/pets", Method::Post, "createPets"),
+                ("/pets/{petId}", Method::Get, "showPetById"),
+            ]
+        );
+    }
+
+    #[test]
+    fn iterates_callback_operations() {
+        let mut spec = spec("data/v3.0/callback-example.yaml");
+        assert_eq!(spec.operations().count(), 1);
+        let operations = spec
+            .operations_with_callbacks()
+            .map(|(path, method, _)| (path, method))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                ("/streams", Method::Post),
+                ("{$request.query.callbackUrl}/data", Method::Post),
+            ]
+        );
+
+        spec.for_each_operation_mut(true, |_, _, operation| {
+            operation.deprecated = Some(true);
+        });
+        assert!(spec
+            .operations_with_callbacks()
+            .all(|(_, _, operation)| operation.deprecated == Some(true)));
+    }
+
+    #[test]
+    fn indexes_operation_ids() {
+        let mut spec = spec("data/v3.0/petstore.yaml");
+        {
+            let index = spec.operation_index().unwrap();
+            assert_eq!(index.len(), 3);
+            let (path, method, operation) = index.get("showPetById").unwrap();
+            assert_eq!((path, method), ("/pets/{petId}", Method::Get));
+            assert_eq!(operation.operation_id.as_deref(), Some("showPetById"));
+            assert_eq!(
+                index.operation_id(Method::Post, "/pets"),
+                Some("createPets")
+            );
+            assert_eq!(index.operation_id(Method::Delete, "/pets"), None);
+            assert!(index.get("deletePet").is_none());
+        }
+
+        spec.paths
+            .get_mut("/pets")
+            .and_then(|path_item| path_item.post.as_mut())
+            .unwrap()
+            .operation_id = Some("listPets".into());
+        match spec.operation_index() {
+            Err(crate::Error::DuplicateOperationId(operation_id)) => {
+                assert_eq!(operation_id, "listPets")
+            }
+            other => panic!("unexpected result {:?}", other.map(|index| index.len())),
+        }
+    }
+
+    #[test]
+    fn indexes_callbacks_sharing_an_expression() {
+        let spec: Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Hooks
+  version: 1.0.0
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      responses:
+        '201':
+          description: Created
+      callbacks:
+        created:
+          '{$request.body#/callbackUrl}':
+            post:
+              operationId: petCreated
+              responses:
+                '200':
+                  description: Received
+  /owners:
+    post:
+      operationId: createOwner
+      responses:
+        '201':
+          description: Created
+      callbacks:
+        created:
+          '{$request.body#/callbackUrl}':
+            post:
+              operationId: ownerCreated
+              responses:
+                '200':
+                  description: Received
+"#,
+        )
+        .unwrap();
+        let index = spec.operation_index().unwrap();
+        assert_eq!(index.len(), 4);
+        assert_eq!(
+            index
+                .get("ownerCreated")
+                .map(|(path, method, _)| (path, method)),
+            Some(("{$request.body#/callbackUrl}", Method::Post))
+        );
+        assert!(index.contains("petCreated"));
+        assert_eq!(
+            index.operation_id(Method::Post, "{$request.body#/callbackUrl}"),
+            None
+        );
+        assert_eq!(
+            index.operation_id(Method::Post, "/owners"),
+            Some("createOwner")
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! Resolution and merging of the parameters of path items and operations, and resolution
+//! of request bodies.
+
+use crate::{
+    pointer,
+    v3_0::{ObjectOrReference, Operation, Parameter, PathItem, RequestBody, Spec},
+    Error, Result,
+};
+use std::collections::BTreeMap;
+
+/// The maximum number of references followed while resolving, so cycles terminate.
+pub(crate) const MAX_REFERENCE_DEPTH: usize = 32;
+
+impl Spec {
+    /// Resolves a parameter, following references to `#/components/parameters`.
+    pub fn resolve_parameter<'a>(
+        &'a self,
+        parameter: &'a ObjectOrReference<Parameter>,
+    ) -> Result<&'a Parameter> {
+        resolve(
+            self.components
+                .as_ref()
+                .and_then(|components| components.parameters.as_ref()),
+            "#/components/parameters/",
+            parameter,
+        )
+    }
+
+    /// Resolves a request body, following references to `#/components/requestBodies`.
+    pub fn resolve_request_body<'a>(
+        &'a self,
+        request_body: &'a ObjectOrReference<RequestBody>,
+    ) -> Result<&'a RequestBody> {
+        resolve(
+            self.components
+                .as_ref()
+                .and_then(|components| components.request_bodies.as_ref()),
+            "#/components/requestBodies/",
+            request_body,
+        )
+    }
+
+    /// The parameters which apply to an operation of a path item.
+    ///
+    /// These are the path item's parameters, overridden by the operation's parameters
+    /// with the same name and location, followed by the operation's remaining parameters.
+    /// References are resolved. Fails if either list holds the same parameter twice.
+    pub fn effective_parameters<'a>(
+        &'a self,
+        path_item: &'a PathItem,
+        operation: &'a Operation,
+    ) -> Result<Vec<&'a Parameter>> {
+        let mut parameters = self.unique_parameters(path_item.parameters.as_ref())?;
+        for parameter in self.unique_parameters(operation.parameters.as_ref())? {
+            match parameters
+                .iter_mut()
+                .find(|existing| same_parameter(existing, parameter))
+            {
+                Some(existing) => *existing = parameter,
+                None => parameters.push(parameter),
+            }
+        }
+        Ok(parameters)
+    }
+
+    fn unique_parameters<'a>(
+        &'a self,
+        parameters: Option<&'a Vec<ObjectOrReference<Parameter>>>,
+    ) -> Result<Vec<&'a Parameter>> {
+        let mut unique: Vec<&'a Parameter> = Vec::new();
+        for parameter in parameters.into_iter().flatten() {
+            let parameter = self.resolve_parameter(parameter)?;
+            if unique
+                .iter()
+                .any(|existing| same_parameter(existing, parameter))
+            {
+                return Err(Error::DuplicateParameter {
+                    name: parameter.name.clone(),
+                    location: parameter.location.clone(),
+                });
+            }
+            unique.push(parameter);
+        }
+        Ok(unique)
+    }
+}
+
+/// Follows references to the components under `prefix` until reaching an object.
+pub(crate) fn resolve<'a, T>(
+    components: Option<&'a BTreeMap<String, ObjectOrReference<T>>>,
+    prefix: &str,
+    object: &'a ObjectOrReference<T>,
+) -> Result<&'a T> {
+    let mut object = object;
+    for _ in 0..MAX_REFERENCE_DEPTH {
+        match object {
+            ObjectOrReference::Object(object) => return Ok(object),
+            ObjectOrReference::Ref { ref_path } => {
+                object = ref_path
+                    .strip_prefix(prefix)
+                    .and_then(|name| components?.get(pointer::unescape(name).as_ref()))
+                    .ok_or_else(|| Error::UnresolvedReference(ref_path.clone()))?;
+            }
+        }
+    }
+    match object {
+        ObjectOrReference::Object(object) => Ok(object),
+        ObjectOrReference::Ref { ref_path } => Err(Error::UnresolvedReference(ref_path.clone())),
+    }
+}
+
+/// Parameters are identified by their name and location.
+fn same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    a.name == b.name && a.location == b.location
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> Spec {
+        serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Parameters
+  version: 1.0.0
+paths:
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/components/parameters/PetId'
+      - name: verbose
+        in: query
+        description: path level
+    get:
+      parameters:
+        - name: verbose
+          in: query
+          description: operation level
+        - name: fields
+          in: query
+      responses:
+        '200':
+          description: a pet
+    put:
+      parameters:
+        - name: fields
+          in: query
+        - name: fields
+          in: query
+      responses:
+        '200':
+          description: a pet
+    delete:
+      parameters:
+        - $ref: '#/components/parameters/Missing'
+      responses:
+        '204':
+          description: deleted
+components:
+  parameters:
+    PetId:
+      name: petId
+      in: path
+      required: true
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn merges_and_resolves_parameters() {
+        let spec = spec();
+        let path_item = &spec.paths["/pets/{petId}"];
+        let parameters = spec
+            .effective_parameters(path_item, path_item.get.as_ref().unwrap())
+            .unwrap();
+        let names = parameters
+            .iter()
+            .map(|parameter| parameter.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["petId", "verbose", "fields"]);
+        assert_eq!(
+            parameters[1].description.as_deref(),
+            Some("operation level")
+        );
+    }
+
+    #[test]
+    fn rejects_duplicates_within_a_level() {
+        let spec = spec();
+        let path_item = &spec.paths["/pets/{petId}"];
+        match spec.effective_parameters(path_item, path_item.put.as_ref().unwrap()) {
+            Err(Error::DuplicateParameter { name, location }) => {
+                assert_eq!((name.as_str(), location.as_str()), ("fields", "query"))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolves_request_bodies() {
+        let mut spec = spec();
+        spec.components.as_mut().unwrap().request_bodies = Some(
+            vec![(
+                "Pet".to_string(),
+                ObjectOrReference::Object(RequestBody {
+                    description: Some("a pet".into()),
+                    ..RequestBody::default()
+                }),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        let reference = ObjectOrReference::Ref {
+            ref_path: "#/components/requestBodies/Pet".into(),
+        };
+        assert_eq!(
+            spec.resolve_request_body(&reference)
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("a pet")
+        );
+    }
+
+    #[test]
+    fn rejects_unresolved_references() {
+        let spec = spec();
+        let path_item = &spec.paths["/pets/{petId}"];
+        assert!(matches!(
+            spec.effective_parameters(path_item, path_item.delete.as_ref().unwrap()),
+            Err(Error::UnresolvedReference(_))
+        ));
+    }
+}
@@ -0,0 +1,308 @@
+//! The components a spec's paths use, directly or through other components.
+
+use crate::{
+    pointer,
+    v3_0::{
+        visit::{self, Visit},
+        Link, Operation, Schema, SecurityRequirement, Spec,
+    },
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Spec {
+    /// The pointers of the components, such as `/components/schemas/Pet`, which neither the
+    /// paths nor the top-level `security` of this spec use, whether directly or through
+    /// other components, callbacks and links.
+    pub fn unused_components(&self) -> Vec<String> {
+        let reachable = reachable_components(self);
+        component_pointers(self)
+            .into_iter()
+            .filter(|pointer| !reachable.contains(pointer))
+            .collect()
+    }
+
+    /// Removes the components [`unused_components`](Spec::unused_components) lists,
+    /// returning their pointers.
+    pub fn remove_unused_components(&mut self) -> Vec<String> {
+        let reachable = reachable_components(self);
+        retain_components(self, &reachable)
+    }
+}
+
+/// The pointers of the components, such as `/components/schemas/Pet`, which the paths and
+/// top-level `security` of a spec use, directly or through other components.
+pub(crate) fn reachable_components(spec: &Spec) -> BTreeSet<String> {
+    let mut uses = Uses::default();
+    uses.security("", spec.security.as_ref());
+    uses.visit_spec(spec);
+
+    let mut reachable = BTreeSet::new();
+    let mut pending = uses.0.remove(&None).unwrap_or_default();
+    while let Some(component) = pending.pop_first() {
+        if let Some(used) = uses.0.remove(&Some(component.clone())) {
+            pending.extend(used.into_iter().filter(|used| !reachable.contains(used)));
+        }
+        reachable.insert(component);
+    }
+    reachable
+}
+
+/// Removes the components of a spec whose pointers `keep` does not contain, returning the
+/// pointers of those removed.
+pub(crate) fn retain_components(spec: &mut Spec, keep: &BTreeSet<String>) -> Vec<String> {
+    fn retain<T>(
+        objects: &mut Option<BTreeMap<String, T>>,
+        kind: &str,
+        keep: &BTreeSet<String>,
+        removed: &mut Vec<String>,
+    ) {
+        if let Some(map) = objects {
+            let prefix = pointer::join("/components", kind);
+            map.retain(|name, _| {
+                let pointer = pointer::join(&prefix, name);
+                let kept = keep.contains(&pointer);
+                if !kept {
+                    removed.push(pointer);
+                }
+                kept
+            });
+            if map.is_empty() {
+                *objects = None;
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    if let Some(components) = &mut spec.components {
+        retain(&mut components.schemas, "schemas", keep, &mut removed);
+        retain(&mut components.responses, "responses", keep, &mut removed);
+        retain(&mut components.parameters, "parameters", keep, &mut removed);
+        retain(&mut components.examples, "examples", keep, &mut removed);
+        retain(
+            &mut components.request_bodies,
+            "requestBodies",
+            keep,
+            &mut removed,
+        );
+        retain(&mut components.headers, "headers", keep, &mut removed);
+        retain(
+            &mut components.security_schemes,
+            "securitySchemes",
+            keep,
+            &mut removed,
+        );
+        retain(&mut components.links, "links", keep, &mut removed);
+        retain(&mut components.callbacks, "callbacks", keep, &mut removed);
+    }
+    removed
+}
+
+/// The pointers of all the components of a spec.
+fn component_pointers(spec: &Spec) -> Vec<String> {
+    fn each<T>(objects: &Option<BTreeMap<String, T>>, kind: &str, pointers: &mut Vec<String>) {
+        let prefix = pointer::join("/components", kind);
+        pointers.extend(
+            objects
+                .iter()
+                .flatten()
+                .map(|(name, _)| pointer::join(&prefix, name)),
+        );
+    }
+
+    let mut pointers = Vec::new();
+    if let Some(components) = &spec.components {
+        each(&components.schemas, "schemas", &mut pointers);
+        each(&components.responses, "responses", &mut pointers);
+        each(&components.parameters, "parameters", &mut pointers);
+        each(&components.examples, "examples", &mut pointers);
+        each(&components.request_bodies, "requestBodies", &mut pointers);
+        each(&components.headers, "headers", &mut pointers);
+        each(
+            &components.security_schemes,
+            "securitySchemes",
+            &mut pointers,
+        );
+        each(&components.links, "links", &mut pointers);
+        each(&components.callbacks, "callbacks", &mut pointers);
+    }
+    pointers
+}
+
+/// The pointer of the component holding the node at `pointer`, if any.
+fn component_of(pointer: &str) -> Option<String> {
+    let mut segments = pointer.strip_prefix("/components/")?.splitn(3, '/');
+    match (segments.next(), segments.next()) {
+        (Some(kind), Some(name)) => Some(format!("/components/{}/{}", kind, name)),
+        _ => None,
+    }
+}
+
+/// The components used by each component, or by the rest of the spec under `None`.
+#[derive(Default)]
+struct Uses(BTreeMap<Option<String>, BTreeSet<String>>);
+
+impl Uses {
+    fn add(&mut self, pointer: &str, component: String) {
+        self.0
+            .entry(component_of(pointer))
+            .or_default()
+            .insert(component);
+    }
+
+    fn reference(&mut self, pointer: &str, reference: &str) {
+        if let Some(component) = reference.strip_prefix('#').and_then(component_of) {
+            self.add(pointer, component);
+        }
+    }
+
+    fn security(&mut self, pointer: &str, security: Option<&Vec<SecurityRequirement>>) {
+        for requirement in security.into_iter().flatten() {
+            for name in requirement.keys() {
+                let scheme = pointer::join("/components/securitySchemes", name);
+                self.add(pointer, scheme);
+            }
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Uses {
+    fn visit_operation(&mut self, pointer: &str, operation: &'a Operation) {
+        self.security(pointer, operation.security.as_ref());
+        visit::walk_operation(self, pointer, operation)
+    }
+
+    fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+        let mapping = schema
+            .discriminator
+            .iter()
+            .flat_map(|discriminator| discriminator.mapping.iter().flatten());
+        for (_, target) in mapping {
+            // mappings name schemas either by reference or by component name
+            let reference = if target.starts_with('#') {
+                target.clone()
+            } else {
+                format!("#{}", pointer::join("/components/schemas", target))
+            };
+            self.reference(pointer, &reference);
+        }
+        visit::walk_schema(self, pointer, schema)
+    }
+
+    fn visit_link(&mut self, pointer: &str, link: &'a Link) {
+        if let Link::Ref { operation_ref, .. } = link {
+            self.reference(pointer, operation_ref);
+        }
+    }
+
+    fn visit_reference(&mut self, pointer: &str, reference: &'a str) {
+        self.reference(pointer, reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_unused_components() {
+        let mut spec: Spec = serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    post:
+      parameters:
+        - $ref: "#/components/parameters/Limit"
+      callbacks:
+        created:
+          '{$request.body#/callback}':
+            post:
+              requestBody:
+                content:
+                  application/json:
+                    schema:
+                      $ref: "#/components/schemas/Event"
+              responses:
+                '200':
+                  description: Received
+      responses:
+        '201':
+          description: Created
+          links:
+            owner:
+              $ref: "#/components/links/Owner"
+components:
+  parameters:
+    Limit:
+      name: limit
+      in: query
+      schema:
+        $ref: "#/components/schemas/Limit"
+    Offset:
+      name: offset
+      in: query
+      schema:
+        $ref: "#/components/schemas/Offset"
+  schemas:
+    Limit:
+      type: integer
+    Offset:
+      type: integer
+    Event:
+      type: object
+    Cat:
+      type: object
+    Pet:
+      oneOf:
+        - $ref: "#/components/schemas/Cat"
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: Cat
+  callbacks:
+    Deleted:
+      '{$request.body#/callback}':
+        post:
+          requestBody:
+            content:
+              application/json:
+                schema:
+                  $ref: "#/components/schemas/Pet"
+          responses:
+            '200':
+              description: Received
+  links:
+    Owner:
+      operationId: getOwner
+"##,
+        )
+        .unwrap();
+        let unused = spec.unused_components();
+        assert_eq!(
+            unused,
+            vec![
+                "/components/schemas/Cat",
+                "/components/schemas/Offset",
+                "/components/schemas/Pet",
+                "/components/parameters/Offset",
+                "/components/callbacks/Deleted",
+            ]
+        );
+        assert_eq!(spec.remove_unused_components().len(), 5);
+        assert!(spec.unused_components().is_empty());
+        assert_eq!(
+            spec.components
+                .as_ref()
+                .unwrap()
+                .schemas
+                .as_ref()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["Event", "Limit"]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Routing of requests to the operations of a spec.
+
+use crate::{
+    router::Routes,
+    v3_0::{Operation, Parameter, PathItem, Spec},
+    Method, Result,
+};
+use std::collections::BTreeMap;
+
+/// Maps concrete paths, relative to a server URL, to the path items and operations of a
+/// spec.
+///
+/// Where several path templates match a path, the one with literal segments is preferred
+/// over templated ones, segment by segment, so that `/pets/mine` takes precedence over
+/// `/pets/{petId}`. Operations are looked up on the first of these templates whose path
+/// item defines the method.
+pub struct Router<'a> {
+    spec: &'a Spec,
+    routes: Routes<'a, PathItem>,
+}
+
+/// A path item whose template matched a path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathMatch<'a> {
+    /// The path template which matched.
+    pub path: &'a str,
+    /// The path item of the template.
+    pub path_item: &'a PathItem,
+    /// The percent-decoded values of the template's variables.
+    pub path_parameters: BTreeMap<String, String>,
+}
+
+/// An operation matched by a method and path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route<'a> {
+    pub(crate) spec: &'a Spec,
+    /// The path template which matched.
+    pub path: &'a str,
+    /// The path item of the template.
+    pub path_item: &'a PathItem,
+    /// The method of the operation.
+    pub method: Method,
+    /// The matched operation.
+    pub operation: &'a Operation,
+    /// The percent-decoded values of the template's variables.
+    pub path_parameters: BTreeMap<String, String>,
+}
+
+impl Spec {
+    /// A router over the paths of this spec.
+    pub fn router(&self) -> Router<'_> {
+        Router::new(self)
+    }
+}
+
+impl<'a> Router<'a> {
+    pub fn new(spec: &'a Spec) -> Self {
+        Router {
+            spec,
+            routes: Routes::new(&spec.paths),
+        }
+    }
+
+    /// Finds the path item matching a path. Any query string is ignored.
+    pub fn find_path(&self, path: &str) -> Option<PathMatch<'a>> {
+        let path = path.split('?').next().unwrap_or_default();
+        self.routes
+            .find(path)
+            .map(|(path, path_item, path_parameters)| PathMatch {
+                path,
+                path_item,
+                path_parameters,
+            })
+    }
+
+    /// Finds the operation matching a method and path. Any query string is ignored.
+    pub fn find(&self, method: Method, path: &str) -> Option<Route<'a>> {
+        let path = path.split('?').next().unwrap_or_default();
+        self.routes
+            .find_all(path)
+            .find_map(|(path, path_item, path_parameters)| {
+                Some(Route {
+                    spec: self.spec,
+                    path,
+                    path_item,
+                    method,
+                    operation: path_item.operation(method)?,
+                    path_parameters,
+                })
+            })
+    }
+}
+
+impl<'a> Route<'a> {
+    /// The parameters which apply to the operation, as given by
+    /// [`Spec::effective_parameters`](struct.Spec.html#method.effective_parameters).
+    pub fn parameters(&self) -> Result<Vec<&'a Parameter>> {
+        self.spec
+            .effective_parameters(self.path_item, self.operation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn spec() -> Spec {
+        match from_path("data/v3.0/petstore.yaml").unwrap() {
+            OpenApi::V3_0(spec) => spec,
+            _ => panic!("expected a v3.0 spec"),
+        }
+    }
+
+    #[test]
+    fn finds_operations() {
+        let spec = spec();
+        let router = spec.router();
+        let route = router.find(Method::Get, "/pets/42?verbose=true").unwrap();
+        assert_eq!(route.path, "/pets/{petId}");
+        assert_eq!(route.operation.operation_id.as_deref(), Some("showPetById"));
+        assert_eq!(route.path_parameters["petId"], "42");
+        let parameters = route.parameters().unwrap();
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(parameters[0].name, "petId");
+
+        assert!(router.find(Method::Delete, "/pets/42").is_none());
+        assert!(router.find_path("/pets/42").is_some());
+        assert!(router.find_path("/owners/42").is_none());
+    }
+
+    #[test]
+    fn prefers_literal_paths() {
+        let mut spec = spec();
+        let mine = spec.paths["/pets/{petId}"].clone();
+        spec.paths.insert("/pets/mine".into(), mine);
+        let router = spec.router();
+        assert_eq!(
+            router.find(Method::Get, "/pets/mine").unwrap().path,
+            "/pets/mine"
+        );
+        assert_eq!(
+            router.find(Method::Get, "/pets/yours").unwrap().path,
+            "/pets/{petId}"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_templates_defining_the_method() {
+        let mut spec = spec();
+        let mut mine = spec.paths["/pets/{petId}"].clone();
+        mine.post = mine.get.take();
+        spec.paths.insert("/pets/mine".into(), mine);
+        let router = spec.router();
+        assert_eq!(
+            router.find(Method::Post, "/pets/mine").unwrap().path,
+            "/pets/mine"
+        );
+        let route = router.find(Method::Get, "/pets/mine").unwrap();
+        assert_eq!(route.path, "/pets/{petId}");
+        assert_eq!(route.path_parameters["petId"], "mine");
+        assert_eq!(router.find_path("/pets/mine").unwrap().path, "/pets/mine");
+    }
+}
@@ -1,8 +1,8 @@
 //! Schema specification for [OpenAPI 3.0.0](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.0.md)
 
-use crate::v3_0::extension::Extensions;
+use crate::Extensions;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use url::Url;
 
 use crate::{
@@ -59,13 +59,13 @@ pub struct Spec {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used across the API.
-    // /// The list of  values includes alternative security requirement objects that can be used.
-    // /// Only one of the security requirement objects need to be satisfied to authorize a request.
-    // /// Individual operations can override this definition.
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used across the API.
+    /// The list of  values includes alternative security requirement objects that can be used.
+    /// Only one of the security requirement objects need to be satisfied to authorize a request.
+    /// Individual operations can override this definition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+
     /// A list of tags used by the specification with additional metadata.
     ///The order of the tags can be used to reflect on their order by the parsing tools.
     /// Not all tags that are used by the
@@ -328,14 +328,15 @@ pub struct Operation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used for this operation. The list of
-    // /// values includes alternative security requirement objects that can be used. Only one
-    // /// of the security requirement objects need to be satisfied to authorize a request.
-    // /// This definition overrides any declared top-level
-    // /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oasSecurity).
-    // /// To remove a top-level security declaration, an empty array can be used.
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used for this operation. The list of
+    /// values includes alternative security requirement objects that can be used. Only one
+    /// of the security requirement objects need to be satisfied to authorize a request.
+    /// This definition overrides any declared top-level
+    /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oasSecurity).
+    /// To remove a top-level security declaration, an empty array can be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+
     /// An alternative `server` array to service this operation. If an alternative `server`
     /// object is specified at the Path Item Object or Root level, it will be overridden by
     /// this value.
@@ -395,12 +396,28 @@ pub struct Parameter {
     /// value. Default values (based on value of in): for `query` - `form`; for `path` - `simple`; for
     /// `header` - `simple`; for cookie - `form`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    style: Option<ParameterStyle>,
+    pub style: Option<ParameterStyle>,
+    /// When this is true, parameter values of type `array` or `object` generate separate
+    /// parameters for each value of the array or key-value pair of the map. For other types
+    /// of parameters this property has no effect. When `style` is `form`, the default value
+    /// is `true`. For all other styles, the default value is `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    /// Determines whether the parameter value SHOULD allow reserved characters, as defined
+    /// by [RFC3986](https://tools.ietf.org/html/rfc3986#section-2.2) `:/?#[]@!$&'()*+,;=`
+    /// to be included without percent-encoding. This property only applies to parameters
+    /// with an `in` value of `query`. The default value is `false`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "allowReserved")]
+    pub allow_reserved: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// In order to support common ways of serializing simple parameters, a set of `style`
+/// values are defined.
+///
+/// See <https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#style-values>.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-enum ParameterStyle {
+pub enum ParameterStyle {
     Matrix,
     Label,
     Form,
@@ -455,6 +472,9 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none", rename = "readOnly")]
     pub read_only: Option<bool>,
 
+    #[serde(skip_serializing_if = "Option::is_none", rename = "writeOnly")]
+    pub write_only: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nullable: Option<bool>,
 
@@ -567,9 +587,29 @@ pub struct Schema {
     #[serde(rename = "not", skip_serializing_if = "Option::is_none")]
     pub not: Option<Vec<ObjectOrReference<Schema>>>,
 
+    /// Tells which of the `oneOf`, `anyOf` or `allOf` schemas a payload matches, by the
+    /// value of one of its properties.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator>,
+
     /// [Specification extensions](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.2.md#specificationExtensions)
     #[serde(flatten)]
-    pub extensions: HashMap<String, String>,
+    pub extensions: Extensions,
+}
+
+/// When request bodies or response payloads may be one of a number of different schemas, a
+/// `discriminator` object can be used to aid in serialization, deserialization, and validation.
+///
+/// See <https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#discriminatorObject>.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Discriminator {
+    /// The name of the property in the payload that will hold the discriminator value.
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+
+    /// An object to hold mappings between payload values and schema names or references.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<BTreeMap<String, String>>,
 }
 
 /// Describes a single response from an API Operation, including design-time, static `links`
@@ -799,10 +839,35 @@ pub enum MediaTypeExample {
     /// the `example` field. Furthermore, if referencing a `schema` which contains an
     /// example, the `examples` value SHALL override the example provided by the schema.
     Examples {
+        #[serde(deserialize_with = "examples_or_references")]
         examples: BTreeMap<String, ObjectOrReference<Example>>,
     },
 }
 
+/// Examples keyed by name, telling references apart from examples, which would otherwise
+/// accept a `$ref` as an example without fields since all of them are optional.
+fn examples_or_references<'de, D>(
+    deserializer: D,
+) -> std::result::Result<BTreeMap<String, ObjectOrReference<Example>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, example)| {
+            let example = match example.get("$ref").and_then(serde_json::Value::as_str) {
+                Some(ref_path) => ObjectOrReference::Ref {
+                    ref_path: ref_path.into(),
+                },
+                None => ObjectOrReference::Object(
+                    serde_json::from_value(example).map_err(serde::de::Error::custom)?,
+                ),
+            };
+            Ok((name, example))
+        })
+        .collect()
+}
+
 /// A single encoding definition applied to a single schema property.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Encoding {
@@ -878,6 +943,12 @@ pub struct Example {
     pub extensions: Extensions,
 }
 
+/// Maps the names of security schemes to the scopes they require, which are empty for
+/// schemes other than `oauth2` and `openIdConnect`.
+///
+/// See <https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#securityRequirementObject>.
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
+
 /// Defines a security scheme that can be used by the operations. Supported schemes are
 /// HTTP authentication, an API key (either as a header or as a query parameter),
 ///OAuth2's common flows (implicit, password, application and access code) as defined
@@ -887,6 +958,7 @@ pub struct Example {
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#securitySchemeObject>.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum SecurityScheme {
     #[serde(rename = "apiKey")]
     ApiKey {
@@ -974,7 +1046,6 @@ pub struct AuthorizationCodeFlow {
     pub scopes: BTreeMap<String, String>,
 }
 
-// TODO: Implement
 /// A map of possible out-of band callbacks related to the parent operation. Each value in
 /// the map is a Path Item Object that describes a set of requests that may be initiated by
 /// the API provider and the expected responses. The key value used to identify the callback
@@ -984,8 +1055,9 @@ pub struct AuthorizationCodeFlow {
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#callbackObject>.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Callback(
-    /// A Path Item Object used to define a callback request and expected responses.
-    serde_json::Value, // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+    /// Path Item Objects used to define a callback request and expected responses, keyed by
+    /// their runtime expression.
+    pub BTreeMap<String, PathItem>, // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
 );
 
 // FIXME: Implement
@@ -1058,7 +1130,7 @@ mod tests {
             }
           }
         }"#;
-        let obj: SecurityScheme = serde_json::from_str(&IMPLICIT_OAUTH2_SAMPLE).unwrap();
+        let obj: SecurityScheme = serde_json::from_str(IMPLICIT_OAUTH2_SAMPLE).unwrap();
         match obj {
             SecurityScheme::OAuth2 { flows } => {
                 assert!(flows.implicit.is_some());
@@ -1083,7 +1155,7 @@ mod tests {
                 assert!(implicit.scopes.contains_key("write:pets"));
                 assert!(implicit.scopes.contains_key("read:pets"));
             }
-            _ => assert!(false, "wrong security scheme type"),
+            _ => panic!("wrong security scheme type"),
         }
     }
 }
@@ -0,0 +1,332 @@
+//! Expansion and matching of [server](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#serverObject)
+//! URL templates.
+
+use crate::{
+    template::{self, Part},
+    v3_0::{Server, ServerVariable, Spec},
+    Error, Result,
+};
+use std::{collections::BTreeMap, sync::OnceLock};
+
+/// The result of matching a URL against a server URL template.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerMatch<'a> {
+    /// The server whose URL matched.
+    pub server: &'a Server,
+    /// The values of the server's variables, as found in the URL.
+    pub variables: BTreeMap<String, String>,
+    /// The remainder of the URL following the server URL. This is either empty or starts
+    /// with `/` or `?`.
+    pub path: String,
+}
+
+impl ServerVariable {
+    /// Returns true if the value may be substituted for this variable, that is, if there is
+    /// no `enum` or the value is one of its members.
+    pub fn allows(&self, value: &str) -> bool {
+        self.substitutions_enum
+            .as_ref()
+            .is_none_or(|values| values.iter().any(|allowed| allowed == value))
+    }
+}
+
+impl Server {
+    /// The names of the variables referenced by the URL template, in order of appearance.
+    pub fn template_variables(&self) -> Result<Vec<&str>> {
+        Ok(template::variables(&self.parts()?))
+    }
+
+    /// The URL with every variable substituted by its default value.
+    pub fn default_url(&self) -> Result<String> {
+        self.expand(&BTreeMap::new())
+    }
+
+    /// The URL with every variable substituted by the given value or, if there is none, by
+    /// its default value.
+    ///
+    /// Fails if a value is given for a variable which is not defined, if the URL references
+    /// a variable which is not defined, or if a value is not a member of the variable's
+    /// `enum`.
+    pub fn expand(&self, values: &BTreeMap<String, String>) -> Result<String> {
+        for name in values.keys() {
+            self.variable(name)?;
+        }
+        let mut url = String::new();
+        for part in self.parts()? {
+            match part {
+                Part::Literal(literal) => url.push_str(literal),
+                Part::Variable(name) => {
+                    let variable = self.variable(name)?;
+                    let value = values.get(name).unwrap_or(&variable.default);
+                    if !variable.allows(value) {
+                        return Err(Error::InvalidServerVariableValue {
+                            name: name.to_string(),
+                            value: value.clone(),
+                        });
+                    }
+                    url.push_str(value);
+                }
+            }
+        }
+        Ok(url)
+    }
+
+    /// Every concrete URL this server describes: one per combination of the members of the
+    /// variables' `enum`s. Variables without an `enum` take their default value.
+    pub fn urls(&self) -> Result<Vec<String>> {
+        let mut names = self.template_variables()?;
+        names.sort_unstable();
+        names.dedup();
+        let mut combinations = vec![BTreeMap::new()];
+        for name in names {
+            let variable = self.variable(name)?;
+            let choices = match &variable.substitutions_enum {
+                Some(values) if !values.is_empty() => values.clone(),
+                _ => vec![variable.default.clone()],
+            };
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination: BTreeMap<String, String>| {
+                    choices.iter().map(move |choice| {
+                        let mut combination = combination.clone();
+                        combination.insert(name.to_string(), choice.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+        combinations
+            .iter()
+            .map(|values| self.expand(values))
+            .collect()
+    }
+
+    /// Matches a URL against this server's URL template, extracting the values of its
+    /// variables and the remaining path.
+    ///
+    /// Relative server URLs are matched against the path of the given URL. Values of
+    /// variables with an `enum` must be one of its members.
+    pub fn match_url(&self, url: &str) -> Option<ServerMatch<'_>> {
+        let parts = template::parse(self.url.trim_end_matches('/'))?;
+        let target = if self.url.contains("://") {
+            url
+        } else {
+            path_of(url)
+        };
+        let mut variables = BTreeMap::new();
+        let path = self.match_parts(&parts, target, &mut variables)?;
+        Some(ServerMatch {
+            server: self,
+            variables,
+            path: path.to_string(),
+        })
+    }
+
+    fn parts(&self) -> Result<Vec<Part<'_>>> {
+        template::parse(&self.url).ok_or_else(|| Error::MalformedTemplate(self.url.clone()))
+    }
+
+    fn variable(&self, name: &str) -> Result<&ServerVariable> {
+        self.variables
+            .as_ref()
+            .and_then(|variables| variables.get(name))
+            .ok_or_else(|| Error::UndefinedServerVariable(name.to_string()))
+    }
+
+    /// Matches template parts against the start of `input`, returning the unmatched rest.
+    /// Variables match as little as possible.
+    fn match_parts<'u>(
+        &self,
+        parts: &[Part<'_>],
+        input: &'u str,
+        variables: &mut BTreeMap<String, String>,
+    ) -> Option<&'u str> {
+        match parts.split_first() {
+            None => {
+                if input.is_empty() || input.starts_with('/') || input.starts_with('?') {
+                    Some(input)
+                } else {
+                    None
+                }
+            }
+            Some((Part::Literal(literal), parts)) => {
+                self.match_parts(parts, input.strip_prefix(literal)?, variables)
+            }
+            Some((Part::Variable(name), parts)) => {
+                let lengths: Vec<usize> = match self
+                    .variables
+                    .as_ref()
+                    .and_then(|variables| variables.get(*name))
+                    .and_then(|variable| variable.substitutions_enum.as_ref())
+                {
+                    Some(values) => values
+                        .iter()
+                        .filter(|value| input.starts_with(value.as_str()))
+                        .map(String::len)
+                        .collect(),
+                    None => (0..=input.len())
+                        .filter(|index| input.is_char_boundary(*index))
+                        .collect(),
+                };
+                for length in lengths {
+                    let value = &input[..length];
+                    let bound = match variables.get(*name) {
+                        Some(existing) if existing != value => continue,
+                        Some(_) => false,
+                        None => {
+                            variables.insert(name.to_string(), value.to_string());
+                            true
+                        }
+                    };
+                    if let Some(rest) = self.match_parts(parts, &input[length..], variables) {
+                        return Some(rest);
+                    }
+                    if bound {
+                        variables.remove(*name);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Spec {
+    /// Matches a URL against the servers of this spec, returning the match leaving the
+    /// shortest remaining path.
+    ///
+    /// If the spec declares no servers, the URL is matched against a default server with
+    /// a URL of `/`.
+    pub fn match_server(&self, url: &str) -> Option<ServerMatch<'_>> {
+        match &self.servers {
+            Some(servers) if !servers.is_empty() => servers
+                .iter()
+                .filter_map(|server| server.match_url(url))
+                .min_by_key(|matched| matched.path.len()),
+            _ => default_server().match_url(url),
+        }
+    }
+}
+
+/// The server assumed when a spec declares none.
+fn default_server() -> &'static Server {
+    static DEFAULT: OnceLock<Server> = OnceLock::new();
+    DEFAULT.get_or_init(|| Server {
+        url: "/".into(),
+        ..Server::default()
+    })
+}
+
+/// The path, query and fragment of an absolute URL, or the URL itself if it is relative.
+fn path_of(url: &str) -> &str {
+    match url.find("://") {
+        Some(scheme) => {
+            let authority = scheme + 3;
+            url[authority..]
+                .find(['/', '?'])
+                .map_or("", |start| &url[authority + start..])
+        }
+        None => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server {
+        serde_yaml::from_str(
+            r#"
+url: "https://{region}.api.example.com/{basePath}"
+variables:
+  region:
+    default: us
+    enum: [us, eu]
+  basePath:
+    default: v2
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn expands_defaults_and_values() {
+        let server = server();
+        assert_eq!(
+            server.default_url().unwrap(),
+            "https://us.api.example.com/v2"
+        );
+        let mut values = BTreeMap::new();
+        values.insert("region".to_string(), "eu".to_string());
+        assert_eq!(
+            server.expand(&values).unwrap(),
+            "https://eu.api.example.com/v2"
+        );
+    }
+
+    #[test]
+    fn rejects_values_outside_enum() {
+        let mut values = BTreeMap::new();
+        values.insert("region".to_string(), "ap".to_string());
+        match server().expand(&values) {
+            Err(Error::InvalidServerVariableValue { name, value }) => {
+                assert_eq!((name.as_str(), value.as_str()), ("region", "ap"))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        values.clear();
+        values.insert("port".to_string(), "8443".to_string());
+        assert!(matches!(
+            server().expand(&values),
+            Err(Error::UndefinedServerVariable(_))
+        ));
+    }
+
+    #[test]
+    fn lists_all_urls() {
+        assert_eq!(
+            server().urls().unwrap(),
+            vec![
+                "https://us.api.example.com/v2",
+                "https://eu.api.example.com/v2"
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_urls() {
+        let server = server();
+        let matched = server
+            .match_url("https://eu.api.example.com/v3/pets/42")
+            .unwrap();
+        assert_eq!(matched.variables["region"], "eu");
+        assert_eq!(matched.variables["basePath"], "v3");
+        assert_eq!(matched.path, "/pets/42");
+        assert_eq!(server.match_url("https://ap.api.example.com/v3/pets"), None);
+    }
+
+    #[test]
+    fn matches_relative_and_default_servers() {
+        let spec = Spec {
+            servers: Some(vec![
+                Server {
+                    url: "/".into(),
+                    ..Server::default()
+                },
+                Server {
+                    url: "/v1".into(),
+                    ..Server::default()
+                },
+            ]),
+            ..Spec::default()
+        };
+        let matched = spec.match_server("http://localhost:8080/v1/pets").unwrap();
+        assert_eq!(matched.server.url, "/v1");
+        assert_eq!(matched.path, "/pets");
+
+        let spec = Spec::default();
+        let matched = spec.match_server("/pets?limit=1").unwrap();
+        assert_eq!(matched.server.url, "/");
+        assert_eq!(matched.path, "/pets?limit=1");
+    }
+}
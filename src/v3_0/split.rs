@@ -0,0 +1,176 @@
+//! Slicing of specs into the operations of interest to a team or client.
+
+use crate::{
+    v3_0::{
+        reachable::{reachable_components, retain_components},
+        Operation, Spec,
+    },
+    Method,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Spec {
+    /// A copy of this spec with only the operations `keep` selects, given their path,
+    /// method and operation. Paths left without operations are dropped, as are the tags
+    /// and components the remaining operations do not use, directly or transitively.
+    pub fn select<F>(&self, mut keep: F) -> Spec
+    where
+        F: FnMut(&str, Method, &Operation) -> bool,
+    {
+        let mut spec = self.clone();
+        spec.paths.retain(|path, path_item| {
+            for method in Method::ALL.iter().copied() {
+                let slot = path_item.operation_slot(method);
+                if slot
+                    .as_ref()
+                    .is_some_and(|operation| !keep(path, method, operation))
+                {
+                    *slot = None;
+                }
+            }
+            path_item.operations().next().is_some()
+        });
+
+        let used = spec
+            .operations()
+            .flat_map(|(_, _, operation)| operation.tags.iter().flatten())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if let Some(tags) = &mut spec.tags {
+            tags.retain(|tag| used.contains(&tag.name));
+        }
+        let reachable = reachable_components(&spec);
+        retain_components(&mut spec, &reachable);
+        spec
+    }
+
+    /// A copy of this spec with only the operations tagged with one of `tags`.
+    pub fn with_tags(&self, tags: &[&str]) -> Spec {
+        self.select(|_, _, operation| {
+            operation
+                .tags
+                .iter()
+                .flatten()
+                .any(|tag| tags.contains(&tag.as_str()))
+        })
+    }
+
+    /// A copy of this spec with only the operations of paths under one of `prefixes`.
+    /// Prefixes match whole segments, so `/pets` selects `/pets` and `/pets/{petId}` but
+    /// not `/petstore`.
+    pub fn with_path_prefixes(&self, prefixes: &[&str]) -> Spec {
+        self.select(|path, _, _| {
+            prefixes.iter().any(|prefix| {
+                let prefix = prefix.trim_end_matches('/');
+                path.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+        })
+    }
+
+    /// Splits this spec into one copy per tag its operations use, by tag name, each with
+    /// only the operations tagged with it. Untagged operations are left out.
+    pub fn split_by_tag(&self) -> BTreeMap<String, Spec> {
+        self.operations()
+            .flat_map(|(_, _, operation)| operation.tags.iter().flatten())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|tag| (tag.clone(), self.with_tags(&[tag])))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_referenced_components() {
+        let spec: Spec = serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+tags:
+  - name: pets
+  - name: store
+paths:
+  /pets:
+    post:
+      tags: [pets]
+      security:
+        - key: []
+      requestBody:
+        $ref: "#/components/requestBodies/Pets"
+      responses:
+        '204':
+          description: Created
+  /petstore/orders:
+    get:
+      tags: [store]
+      responses:
+        '200':
+          description: Orders
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Order"
+components:
+  requestBodies:
+    Pets:
+      content:
+        application/json:
+          schema:
+            type: array
+            items:
+              $ref: "#/components/schemas/Pet"
+  schemas:
+    Pet:
+      properties:
+        owner:
+          $ref: "#/components/schemas/Owner"
+    Owner:
+      type: object
+    Order:
+      type: object
+  securitySchemes:
+    key:
+      type: apiKey
+      name: key
+      in: header
+"##,
+        )
+        .unwrap();
+        let pets = spec.with_path_prefixes(&["/pets"]);
+        assert_eq!(pets, spec.split_by_tag()["pets"]);
+        assert_eq!(pets.paths.keys().collect::<Vec<_>>(), vec!["/pets"]);
+        assert_eq!(pets.tags.as_ref().unwrap().len(), 1);
+        let components = pets.components.as_ref().unwrap();
+        assert_eq!(
+            components
+                .schemas
+                .as_ref()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["Owner", "Pet"]
+        );
+        assert!(components.request_bodies.is_some());
+        assert!(components.security_schemes.is_some());
+
+        let store = spec.with_tags(&["store"]);
+        let components = store.components.as_ref().unwrap();
+        assert_eq!(
+            components
+                .schemas
+                .as_ref()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["Order"]
+        );
+        assert!(components.request_bodies.is_none());
+        assert!(components.security_schemes.is_none());
+    }
+}
@@ -0,0 +1,883 @@
+//! Serialization of parameter values according to their
+//! [style](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#style-values),
+//! `explode` and `allowReserved` settings.
+//!
+//! Values are serialized following [RFC6570](https://tools.ietf.org/html/rfc6570) style
+//! expansion as described by the specification, producing the text of a path segment,
+//! a query string fragment, a header value or a cookie.
+
+use crate::{
+    v3_0::{Parameter, ParameterStyle, Schema},
+    Error, Result,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// Characters which are percent-encoded unless they are RFC3986 `unreserved`.
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Characters which are percent-encoded unless they are RFC3986 `unreserved` or `reserved`.
+const RESERVED: &AsciiSet = &UNRESERVED
+    .remove(b':')
+    .remove(b'/')
+    .remove(b'?')
+    .remove(b'#')
+    .remove(b'[')
+    .remove(b']')
+    .remove(b'@')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=');
+
+impl fmt::Display for ParameterStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ParameterStyle::Matrix => "matrix",
+            ParameterStyle::Label => "label",
+            ParameterStyle::Form => "form",
+            ParameterStyle::Simple => "simple",
+            ParameterStyle::SpaceDelimited => "spaceDelimited",
+            ParameterStyle::PipeDelimited => "pipeDelimited",
+            ParameterStyle::DeepObject => "deepObject",
+        })
+    }
+}
+
+impl ParameterStyle {
+    /// The style used for parameters in the given location when none is declared:
+    /// `form` for `query` and `cookie`, `simple` for `path` and `header`.
+    pub fn default_for(location: &str) -> Option<Self> {
+        match location {
+            "query" | "cookie" => Some(ParameterStyle::Form),
+            "path" | "header" => Some(ParameterStyle::Simple),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this style may be used for parameters in the given location.
+    pub fn supports(self, location: &str) -> bool {
+        match self {
+            ParameterStyle::Matrix | ParameterStyle::Label => location == "path",
+            ParameterStyle::Form => location == "query" || location == "cookie",
+            ParameterStyle::Simple => location == "path" || location == "header",
+            ParameterStyle::SpaceDelimited
+            | ParameterStyle::PipeDelimited
+            | ParameterStyle::DeepObject => location == "query",
+        }
+    }
+}
+
+/// A value broken down into its percent-encoded components.
+enum Parts {
+    Empty,
+    Primitive(String),
+    Array(Vec<String>),
+    Object(Vec<(String, String)>),
+}
+
+/// The kind of value a parameter's schema describes.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Primitive,
+    Array,
+    Object,
+}
+
+impl Parameter {
+    /// The declared `style` of this parameter or the default for its location.
+    pub fn effective_style(&self) -> Option<ParameterStyle> {
+        self.style
+            .or_else(|| ParameterStyle::default_for(&self.location))
+    }
+
+    /// The declared `explode` of this parameter or the default for its style.
+    pub fn effective_explode(&self) -> bool {
+        self.explode
+            .unwrap_or_else(|| self.effective_style() == Some(ParameterStyle::Form))
+    }
+
+    /// Returns true if reserved characters are left unencoded. This only ever applies to
+    /// `query` parameters.
+    pub fn effective_allow_reserved(&self) -> bool {
+        self.location == "query" && self.allow_reserved.unwrap_or(false)
+    }
+
+    /// Serializes a value for this parameter.
+    ///
+    /// Depending on the parameter location, the result is the text substituted for the
+    /// parameter's template expression in a path, a fragment of a query string
+    /// (`name=value` pairs joined by `&`), a header value or the contents of a `Cookie`
+    /// header (pairs joined by `; `). A `null` value is serialized as an empty value.
+    pub fn encode_value(&self, value: &Value) -> Result<String> {
+        let style = self.checked_style()?;
+        let explode = self.effective_explode();
+        let parts = self.parts(value)?;
+        let name = self.encode(&self.name);
+        let pairs = if self.location == "cookie" { "; " } else { "&" };
+        let style = match style {
+            ParameterStyle::SpaceDelimited | ParameterStyle::PipeDelimited if explode => {
+                ParameterStyle::Form
+            }
+            style => style,
+        };
+        let encoded = match (style, parts) {
+            (ParameterStyle::Matrix, Parts::Empty) => format!(";{}", name),
+            (ParameterStyle::Matrix, Parts::Primitive(value)) => format!(";{}={}", name, value),
+            (ParameterStyle::Matrix, Parts::Array(values)) if explode => values
+                .iter()
+                .map(|value| format!(";{}={}", name, value))
+                .collect(),
+            (ParameterStyle::Matrix, Parts::Array(values)) => {
+                format!(";{}={}", name, values.join(","))
+            }
+            (ParameterStyle::Matrix, Parts::Object(entries)) if explode => entries
+                .iter()
+                .map(|(key, value)| format!(";{}={}", key, value))
+                .collect(),
+            (ParameterStyle::Matrix, Parts::Object(entries)) => {
+                format!(";{}={}", name, flatten(&entries).join(","))
+            }
+            (ParameterStyle::Label, Parts::Empty) => ".".into(),
+            (ParameterStyle::Label, Parts::Primitive(value)) => format!(".{}", value),
+            (ParameterStyle::Label, Parts::Array(values)) => {
+                format!(".{}", values.join(if explode { "." } else { "," }))
+            }
+            (ParameterStyle::Label, Parts::Object(entries)) if explode => entries
+                .iter()
+                .map(|(key, value)| format!(".{}={}", key, value))
+                .collect(),
+            (ParameterStyle::Label, Parts::Object(entries)) => {
+                format!(".{}", flatten(&entries).join(","))
+            }
+            (ParameterStyle::Form, Parts::Empty) => format!("{}=", name),
+            (ParameterStyle::Form, Parts::Primitive(value)) => format!("{}={}", name, value),
+            (ParameterStyle::Form, Parts::Array(values)) if explode => values
+                .iter()
+                .map(|value| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(pairs),
+            (ParameterStyle::Form, Parts::Array(values)) => {
+                format!("{}={}", name, values.join(","))
+            }
+            (ParameterStyle::Form, Parts::Object(entries)) if explode => entries
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(pairs),
+            (ParameterStyle::Form, Parts::Object(entries)) => {
+                format!("{}={}", name, flatten(&entries).join(","))
+            }
+            (ParameterStyle::Simple, Parts::Empty) => String::new(),
+            (ParameterStyle::Simple, Parts::Primitive(value)) => value,
+            (ParameterStyle::Simple, Parts::Array(values)) => values.join(","),
+            (ParameterStyle::Simple, Parts::Object(entries)) if explode => entries
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(","),
+            (ParameterStyle::Simple, Parts::Object(entries)) => flatten(&entries).join(","),
+            (ParameterStyle::SpaceDelimited, Parts::Array(values)) => {
+                format!("{}={}", name, values.join("%20"))
+            }
+            (ParameterStyle::SpaceDelimited, Parts::Object(entries)) => {
+                format!("{}={}", name, flatten(&entries).join("%20"))
+            }
+            (ParameterStyle::PipeDelimited, Parts::Array(values)) => {
+                format!("{}={}", name, values.join("|"))
+            }
+            (ParameterStyle::PipeDelimited, Parts::Object(entries)) => {
+                format!("{}={}", name, flatten(&entries).join("|"))
+            }
+            (ParameterStyle::DeepObject, Parts::Object(entries)) => entries
+                .iter()
+                .map(|(key, value)| format!("{}[{}]={}", name, key, value))
+                .collect::<Vec<_>>()
+                .join("&"),
+            (style, _) => {
+                return Err(self.invalid(format!(
+                    "`{}` style only applies to {}",
+                    style,
+                    if style == ParameterStyle::DeepObject {
+                        "objects"
+                    } else {
+                        "arrays and objects"
+                    }
+                )))
+            }
+        };
+        Ok(encoded)
+    }
+
+    /// Parses a serialized value for this parameter, the inverse of
+    /// [`encode_value`](#method.encode_value).
+    ///
+    /// `raw` is the text matched by the parameter's template expression for `path`
+    /// parameters, the complete query string (without the leading `?`) for `query`
+    /// parameters, the header value for `header` parameters and the complete `Cookie`
+    /// header for `cookie` parameters. Values are typed according to the parameter's
+    /// `schema`. Returns `None` if the parameter is not present.
+    pub fn decode_value(&self, raw: &str) -> Result<Option<Value>> {
+        let style = self.checked_style()?;
+        let explode = self.effective_explode();
+        let kind = self.kind();
+        // exploded delimited values are written as repeated pairs, as with `form`
+        let style = match style {
+            ParameterStyle::SpaceDelimited | ParameterStyle::PipeDelimited if explode => {
+                ParameterStyle::Form
+            }
+            style => style,
+        };
+        match style {
+            ParameterStyle::Simple => self.decode_delimited(kind, raw, ",", explode).map(Some),
+            ParameterStyle::Label => {
+                let raw = self.strip(raw, ".")?;
+                let delimiter = if explode { "." } else { "," };
+                self.decode_delimited(kind, raw, delimiter, explode)
+                    .map(Some)
+            }
+            ParameterStyle::Matrix => self.decode_matrix(kind, self.strip(raw, ";")?, explode),
+            ParameterStyle::Form if explode => {
+                let pairs = self.pairs(raw)?;
+                match kind {
+                    Kind::Object => {
+                        let properties = self
+                            .schema
+                            .as_ref()
+                            .and_then(|schema| schema.properties.as_ref());
+                        let entries = pairs
+                            .into_iter()
+                            .filter(|(key, _)| {
+                                properties.is_none_or(|properties| properties.contains_key(key))
+                            })
+                            .collect::<Vec<_>>();
+                        if entries.is_empty() {
+                            return Ok(None);
+                        }
+                        self.object(entries).map(Some)
+                    }
+                    _ => {
+                        let values = pairs
+                            .into_iter()
+                            .filter(|(key, _)| key == &self.name)
+                            .map(|(_, value)| value)
+                            .collect::<Vec<_>>();
+                        if values.is_empty() {
+                            Ok(None)
+                        } else if kind == Kind::Array {
+                            self.array(values).map(Some)
+                        } else {
+                            self.primitive(self.schema.as_ref(), &values[0]).map(Some)
+                        }
+                    }
+                }
+            }
+            ParameterStyle::Form
+            | ParameterStyle::SpaceDelimited
+            | ParameterStyle::PipeDelimited => {
+                let delimiter = match style {
+                    ParameterStyle::SpaceDelimited => " ",
+                    ParameterStyle::PipeDelimited => "|",
+                    _ => ",",
+                };
+                let raw = self.pairs_raw(raw)?;
+                match raw.into_iter().find(|(key, _)| key == &self.name) {
+                    Some((_, value)) => self
+                        .decode_delimited(kind, &value, delimiter, false)
+                        .map(Some),
+                    None => Ok(None),
+                }
+            }
+            ParameterStyle::DeepObject => {
+                let prefix = format!("{}[", self.name);
+                let entries = self
+                    .pairs(raw)?
+                    .into_iter()
+                    .filter_map(|(key, value)| {
+                        if key.starts_with(&prefix) && key.ends_with(']') {
+                            Some((key[prefix.len()..key.len() - 1].to_string(), value))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if entries.is_empty() {
+                    Ok(None)
+                } else {
+                    self.object(entries).map(Some)
+                }
+            }
+        }
+    }
+
+    /// The effective style, checked against the parameter location.
+    fn checked_style(&self) -> Result<ParameterStyle> {
+        match self.effective_style() {
+            Some(style) if style.supports(&self.location) => Ok(style),
+            style => Err(Error::UnsupportedParameterStyle {
+                style: style.unwrap_or(ParameterStyle::Simple),
+                location: self.location.clone(),
+            }),
+        }
+    }
+
+    fn kind(&self) -> Kind {
+        match self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.schema_type.as_deref())
+        {
+            Some("array") => Kind::Array,
+            Some("object") => Kind::Object,
+            _ => Kind::Primitive,
+        }
+    }
+
+    fn invalid(&self, reason: String) -> Error {
+        Error::InvalidParameterValue {
+            name: self.name.clone(),
+            reason,
+        }
+    }
+
+    /// Percent-encodes text according to the parameter location.
+    fn encode(&self, text: &str) -> String {
+        match self.location.as_str() {
+            "query" if self.effective_allow_reserved() => {
+                utf8_percent_encode(text, RESERVED).to_string()
+            }
+            "query" | "path" => utf8_percent_encode(text, UNRESERVED).to_string(),
+            _ => text.to_string(),
+        }
+    }
+
+    /// Percent-decodes text according to the parameter location.
+    fn decode(&self, text: &str) -> Result<String> {
+        match self.location.as_str() {
+            "query" | "path" => percent_decode_str(text)
+                .decode_utf8()
+                .map(|decoded| decoded.into_owned())
+                .map_err(|_| self.invalid(format!("`{}` is not valid UTF-8", text))),
+            _ => Ok(text.to_string()),
+        }
+    }
+
+    fn parts(&self, value: &Value) -> Result<Parts> {
+        Ok(match value {
+            Value::Null => Parts::Empty,
+            Value::Array(values) => Parts::Array(
+                values
+                    .iter()
+                    .map(|value| self.scalar(value).map(|value| self.encode(&value)))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(entries) => Parts::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| {
+                        self.scalar(value)
+                            .map(|value| (self.encode(key), self.encode(&value)))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            value => Parts::Primitive(self.encode(&self.scalar(value)?)),
+        })
+    }
+
+    fn scalar(&self, value: &Value) -> Result<String> {
+        match value {
+            Value::Null => Ok(String::new()),
+            Value::String(value) => Ok(value.clone()),
+            Value::Bool(_) | Value::Number(_) => Ok(value.to_string()),
+            Value::Array(_) | Value::Object(_) => {
+                Err(self.invalid("nested arrays and objects can not be serialized".into()))
+            }
+        }
+    }
+
+    fn strip<'a>(&self, raw: &'a str, prefix: &str) -> Result<&'a str> {
+        if let Some(stripped) = raw.strip_prefix(prefix) {
+            Ok(stripped)
+        } else {
+            Err(self.invalid(format!("expected `{}` to start with `{}`", raw, prefix)))
+        }
+    }
+
+    /// Splits a query string or cookie header into its still encoded pairs.
+    fn pairs_raw(&self, raw: &str) -> Result<Vec<(String, String)>> {
+        let separator = if self.location == "cookie" { ';' } else { '&' };
+        raw.split(separator)
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let mut split = pair.splitn(2, '=');
+                let key = split.next().unwrap_or_default();
+                let value = split.next().unwrap_or_default();
+                Ok((self.decode(key)?, value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Splits a query string or cookie header into its decoded pairs.
+    fn pairs(&self, raw: &str) -> Result<Vec<(String, String)>> {
+        self.pairs_raw(raw)?
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.decode(&value)?)))
+            .collect()
+    }
+
+    /// Decodes `raw` which holds a single value, a `delimiter` separated array or
+    /// `delimiter` separated object entries, either as `key=value` when `explode` is
+    /// set, or as alternating keys and values.
+    fn decode_delimited(
+        &self,
+        kind: Kind,
+        raw: &str,
+        delimiter: &str,
+        explode: bool,
+    ) -> Result<Value> {
+        match kind {
+            Kind::Primitive => self.primitive(self.schema.as_ref(), &self.decode(raw)?),
+            Kind::Array => {
+                let raw = self.split(raw, delimiter);
+                self.array(
+                    raw.iter()
+                        .map(|value| self.decode(value))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            Kind::Object if explode => {
+                let entries = self
+                    .split(raw, delimiter)
+                    .into_iter()
+                    .map(|entry| self.entry(entry))
+                    .collect::<Result<_>>()?;
+                self.object(entries)
+            }
+            Kind::Object => {
+                let values = self
+                    .split(raw, delimiter)
+                    .iter()
+                    .map(|value| self.decode(value))
+                    .collect::<Result<Vec<_>>>()?;
+                if values.len() % 2 != 0 {
+                    return Err(self.invalid(format!(
+                        "expected `{}` to hold pairs of keys and values",
+                        raw
+                    )));
+                }
+                self.object(
+                    values
+                        .chunks(2)
+                        .map(|pair| (pair[0].clone(), pair[1].clone()))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    fn decode_matrix(&self, kind: Kind, raw: &str, explode: bool) -> Result<Option<Value>> {
+        if explode && kind != Kind::Primitive {
+            let entries = raw
+                .split(';')
+                .map(|entry| self.entry(entry))
+                .collect::<Result<Vec<_>>>()?;
+            return if kind == Kind::Array {
+                self.array(
+                    entries
+                        .into_iter()
+                        .filter(|(key, _)| key == &self.name)
+                        .map(|(_, value)| value)
+                        .collect(),
+                )
+                .map(Some)
+            } else {
+                self.object(entries).map(Some)
+            };
+        }
+        let (key, value) = match raw.find('=') {
+            Some(index) => (&raw[..index], &raw[index + 1..]),
+            None => (raw, ""),
+        };
+        if self.decode(key)? != self.name {
+            return Ok(None);
+        }
+        self.decode_delimited(kind, value, ",", false).map(Some)
+    }
+
+    fn split<'a>(&self, raw: &'a str, delimiter: &'a str) -> Vec<&'a str> {
+        if raw.is_empty() {
+            return Vec::new();
+        }
+        // delimiters may also arrive percent-encoded
+        let delimiters = match delimiter {
+            " " => vec![" ", "+", "%20"],
+            "|" => vec!["|", "%7C", "%7c"],
+            delimiter => vec![delimiter],
+        };
+        delimiters.into_iter().fold(vec![raw], |values, delimiter| {
+            values
+                .into_iter()
+                .flat_map(|value| value.split(delimiter))
+                .collect()
+        })
+    }
+
+    /// Splits and decodes a `key=value` entry.
+    fn entry(&self, entry: &str) -> Result<(String, String)> {
+        let mut split = entry.splitn(2, '=');
+        let key = split.next().unwrap_or_default();
+        let value = split.next().unwrap_or_default();
+        Ok((self.decode(key)?, self.decode(value)?))
+    }
+
+    fn array(&self, values: Vec<String>) -> Result<Value> {
+        let items = self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.items.as_deref());
+        values
+            .iter()
+            .map(|value| self.primitive(items, value))
+            .collect::<Result<_>>()
+            .map(Value::Array)
+    }
+
+    fn object(&self, entries: Vec<(String, String)>) -> Result<Value> {
+        let properties = self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.properties.as_ref());
+        let mut object = Map::new();
+        for (key, value) in entries {
+            let schema = properties.and_then(|properties| properties.get(&key));
+            let value = self.primitive(schema, &value)?;
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Types a single decoded value according to its schema.
+    fn primitive(&self, schema: Option<&Schema>, value: &str) -> Result<Value> {
+        let schema_type = schema.and_then(|schema| schema.schema_type.as_deref());
+        if value.is_empty() && schema_type.is_some_and(|ty| ty != "string") {
+            return Ok(Value::Null);
+        }
+        let invalid = || {
+            self.invalid(format!(
+                "`{}` is not a valid {}",
+                value,
+                schema_type.unwrap_or_default()
+            ))
+        };
+        match schema_type {
+            Some("integer") => value.parse::<i64>().map(Value::from).map_err(|_| invalid()),
+            Some("number") => value
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(invalid),
+            Some("boolean") => value
+                .parse::<bool>()
+                .map(Value::Bool)
+                .map_err(|_| invalid()),
+            _ => Ok(Value::String(value.to_string())),
+        }
+    }
+}
+
+/// Flattens object entries into alternating keys and values.
+fn flatten(entries: &[(String, String)]) -> Vec<&str> {
+    entries
+        .iter()
+        .flat_map(|(key, value)| vec![key.as_str(), value.as_str()])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parameter(
+        location: &str,
+        style: ParameterStyle,
+        explode: bool,
+        schema_type: &str,
+    ) -> Parameter {
+        let schema = match schema_type {
+            "object" => serde_json::from_value(json!({
+                "type": "object",
+                "properties": {
+                    "R": { "type": "integer" },
+                    "G": { "type": "integer" },
+                    "B": { "type": "integer" }
+                }
+            })),
+            "array" => {
+                serde_json::from_value(json!({ "type": "array", "items": { "type": "string" } }))
+            }
+            _ => serde_json::from_value(json!({ "type": schema_type })),
+        }
+        .unwrap();
+        Parameter {
+            name: "color".into(),
+            location: location.into(),
+            style: Some(style),
+            explode: Some(explode),
+            schema: Some(schema),
+            ..Parameter::default()
+        }
+    }
+
+    /// The style examples table from the specification.
+    #[test]
+    fn encodes_and_decodes_style_examples() {
+        let string = json!("blue");
+        let array = json!(["blue", "black", "brown"]);
+        let object = json!({ "R": 100, "G": 200, "B": 150 });
+        let cases = vec![
+            (
+                "path",
+                ParameterStyle::Matrix,
+                false,
+                &string,
+                ";color=blue",
+            ),
+            (
+                "path",
+                ParameterStyle::Matrix,
+                false,
+                &array,
+                ";color=blue,black,brown",
+            ),
+            (
+                "path",
+                ParameterStyle::Matrix,
+                false,
+                &object,
+                ";color=B,150,G,200,R,100",
+            ),
+            (
+                "path",
+                ParameterStyle::Matrix,
+                true,
+                &array,
+                ";color=blue;color=black;color=brown",
+            ),
+            (
+                "path",
+                ParameterStyle::Matrix,
+                true,
+                &object,
+                ";B=150;G=200;R=100",
+            ),
+            ("path", ParameterStyle::Label, false, &string, ".blue"),
+            (
+                "path",
+                ParameterStyle::Label,
+                false,
+                &array,
+                ".blue,black,brown",
+            ),
+            (
+                "path",
+                ParameterStyle::Label,
+                true,
+                &array,
+                ".blue.black.brown",
+            ),
+            (
+                "path",
+                ParameterStyle::Label,
+                true,
+                &object,
+                ".B=150.G=200.R=100",
+            ),
+            (
+                "query",
+                ParameterStyle::Form,
+                false,
+                &array,
+                "color=blue,black,brown",
+            ),
+            (
+                "query",
+                ParameterStyle::Form,
+                false,
+                &object,
+                "color=B,150,G,200,R,100",
+            ),
+            (
+                "query",
+                ParameterStyle::Form,
+                true,
+                &array,
+                "color=blue&color=black&color=brown",
+            ),
+            (
+                "query",
+                ParameterStyle::Form,
+                true,
+                &object,
+                "B=150&G=200&R=100",
+            ),
+            ("header", ParameterStyle::Simple, false, &string, "blue"),
+            (
+                "header",
+                ParameterStyle::Simple,
+                false,
+                &array,
+                "blue,black,brown",
+            ),
+            (
+                "header",
+                ParameterStyle::Simple,
+                false,
+                &object,
+                "B,150,G,200,R,100",
+            ),
+            (
+                "header",
+                ParameterStyle::Simple,
+                true,
+                &object,
+                "B=150,G=200,R=100",
+            ),
+            (
+                "query",
+                ParameterStyle::SpaceDelimited,
+                false,
+                &array,
+                "color=blue%20black%20brown",
+            ),
+            (
+                "query",
+                ParameterStyle::PipeDelimited,
+                false,
+                &array,
+                "color=blue|black|brown",
+            ),
+            (
+                "query",
+                ParameterStyle::SpaceDelimited,
+                true,
+                &array,
+                "color=blue&color=black&color=brown",
+            ),
+            (
+                "query",
+                ParameterStyle::PipeDelimited,
+                true,
+                &array,
+                "color=blue&color=black&color=brown",
+            ),
+            (
+                "query",
+                ParameterStyle::DeepObject,
+                true,
+                &object,
+                "color[B]=150&color[G]=200&color[R]=100",
+            ),
+            (
+                "cookie",
+                ParameterStyle::Form,
+                true,
+                &array,
+                "color=blue; color=black; color=brown",
+            ),
+        ];
+        for (location, style, explode, value, expected) in cases {
+            let schema_type = match value {
+                Value::Array(_) => "array",
+                Value::Object(_) => "object",
+                _ => "string",
+            };
+            let parameter = parameter(location, style, explode, schema_type);
+            assert_eq!(
+                parameter.encode_value(value).unwrap(),
+                expected,
+                "{} {} explode={}",
+                location,
+                style,
+                explode
+            );
+            assert_eq!(
+                parameter.decode_value(expected).unwrap().as_ref(),
+                Some(value),
+                "{} {} explode={}",
+                location,
+                style,
+                explode
+            );
+        }
+    }
+
+    #[test]
+    fn defaults_follow_location() {
+        let query = Parameter {
+            name: "limit".into(),
+            location: "query".into(),
+            ..Parameter::default()
+        };
+        assert_eq!(query.effective_style(), Some(ParameterStyle::Form));
+        assert!(query.effective_explode());
+        let path = Parameter {
+            location: "path".into(),
+            ..query
+        };
+        assert_eq!(path.effective_style(), Some(ParameterStyle::Simple));
+        assert!(!path.effective_explode());
+    }
+
+    #[test]
+    fn percent_encodes_unless_reserved_allowed() {
+        let mut parameter = parameter("query", ParameterStyle::Form, true, "string");
+        let value = json!("a/b c");
+        assert_eq!(parameter.encode_value(&value).unwrap(), "color=a%2Fb%20c");
+        parameter.allow_reserved = Some(true);
+        assert_eq!(parameter.encode_value(&value).unwrap(), "color=a/b%20c");
+        assert_eq!(
+            parameter.decode_value("other=1&color=a/b%20c").unwrap(),
+            Some(value)
+        );
+    }
+
+    #[test]
+    fn decodes_typed_values() {
+        let parameter = parameter("path", ParameterStyle::Simple, false, "integer");
+        assert_eq!(parameter.decode_value("42").unwrap(), Some(json!(42)));
+        assert!(parameter.decode_value("forty-two").is_err());
+    }
+
+    #[test]
+    fn missing_query_parameters_decode_to_none() {
+        let parameter = parameter("query", ParameterStyle::Form, true, "string");
+        assert_eq!(parameter.decode_value("other=1").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_styles_unsupported_by_location() {
+        let parameter = parameter("header", ParameterStyle::Matrix, false, "string");
+        match parameter.encode_value(&json!("blue")) {
+            Err(Error::UnsupportedParameterStyle { style, location }) => {
+                assert_eq!(style, ParameterStyle::Matrix);
+                assert_eq!(location, "header");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Description of Rust types as [`Schema`]s.
+//!
+//! With the `derive` feature, `#[derive(ToSchema)]` implements [`ToSchema`] for structs and
+//! enums, following their serde attributes.
+
+use crate::{
+    pointer,
+    v3_0::{BooleanObjectOrReference, Components, ObjectOrReference, Schema},
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A type which can describe its serialized form as a [`Schema`].
+pub trait ToSchema {
+    /// The name under which the type's schema is registered in `components.schemas`, or
+    /// `None` if it is always inlined.
+    fn schema_name() -> Option<String> {
+        None
+    }
+
+    /// The schema of the type, registering the named types it refers to in `components`.
+    fn schema(components: &mut Components) -> Schema;
+
+    /// The schema to use where the type is referred to: for a named type, a `$ref` to its
+    /// schema, which is registered in `components`; otherwise its schema.
+    fn schema_ref(components: &mut Components) -> Schema {
+        match Self::schema_name() {
+            Some(name) => {
+                if !components
+                    .schemas
+                    .as_ref()
+                    .is_some_and(|schemas| schemas.contains_key(&name))
+                {
+                    // a placeholder stops recursive types from registering themselves forever
+                    components
+                        .schemas
+                        .get_or_insert_with(BTreeMap::new)
+                        .insert(name.clone(), ObjectOrReference::Object(Schema::default()));
+                    let schema = Self::schema(components);
+                    components
+                        .schemas
+                        .get_or_insert_with(BTreeMap::new)
+                        .insert(name.clone(), ObjectOrReference::Object(schema));
+                }
+                Schema {
+                    ref_path: Some(format!("#{}", pointer::join("/components/schemas", &name))),
+                    ..Schema::default()
+                }
+            }
+            None => Self::schema(components),
+        }
+    }
+}
+
+impl Components {
+    /// Registers the schema of a type, and of the types it refers to, returning the schema
+    /// to refer to it with.
+    pub fn add_schema<T: ToSchema + ?Sized>(&mut self) -> Schema {
+        T::schema_ref(self)
+    }
+}
+
+fn typed(schema_type: &str, format: Option<&str>) -> Schema {
+    Schema {
+        schema_type: Some(schema_type.into()),
+        format: format.map(Into::into),
+        ..Schema::default()
+    }
+}
+
+macro_rules! primitive {
+    ($($ty:ty => $schema_type:expr, $format:expr;)*) => {
+        $(
+            impl ToSchema for $ty {
+                fn schema(_: &mut Components) -> Schema {
+                    typed($schema_type, $format)
+                }
+            }
+        )*
+    };
+}
+
+primitive! {
+    bool => "boolean", None;
+    i8 => "integer", Some("int32");
+    i16 => "integer", Some("int32");
+    i32 => "integer", Some("int32");
+    i64 => "integer", Some("int64");
+    isize => "integer", Some("int64");
+    u8 => "integer", Some("int32");
+    u16 => "integer", Some("int32");
+    u32 => "integer", Some("int64");
+    u64 => "integer", Some("int64");
+    usize => "integer", Some("int64");
+    f32 => "number", Some("float");
+    f64 => "number", Some("double");
+    char => "string", None;
+    str => "string", None;
+    String => "string", None;
+}
+
+impl ToSchema for serde_json::Value {
+    fn schema(_: &mut Components) -> Schema {
+        Schema::default()
+    }
+}
+
+impl<T: ToSchema + ?Sized> ToSchema for &T {
+    fn schema(components: &mut Components) -> Schema {
+        T::schema_ref(components)
+    }
+}
+
+impl<T: ToSchema + ?Sized> ToSchema for Box<T> {
+    fn schema(components: &mut Components) -> Schema {
+        T::schema_ref(components)
+    }
+}
+
+/// Optional values are described by the schema of their content, made `nullable` as `None`
+/// serializes as `null`; fields holding them are not required. A `$ref`, whose siblings
+/// are ignored, is wrapped in an `allOf` to carry `nullable`.
+impl<T: ToSchema> ToSchema for Option<T> {
+    fn schema(components: &mut Components) -> Schema {
+        let schema = T::schema_ref(components);
+        if schema.ref_path.is_some() {
+            Schema {
+                all_of: Some(vec![ObjectOrReference::Object(schema)]),
+                nullable: Some(true),
+                ..Schema::default()
+            }
+        } else {
+            Schema {
+                nullable: Some(true),
+                ..schema
+            }
+        }
+    }
+}
+
+fn array<T: ToSchema + ?Sized>(components: &mut Components, unique: bool) -> Schema {
+    Schema {
+        items: Some(Box::new(T::schema_ref(components))),
+        unique_items: if unique { Some(true) } else { None },
+        ..typed("array", None)
+    }
+}
+
+impl<T: ToSchema> ToSchema for [T] {
+    fn schema(components: &mut Components) -> Schema {
+        array::<T>(components, false)
+    }
+}
+
+impl<T: ToSchema> ToSchema for Vec<T> {
+    fn schema(components: &mut Components) -> Schema {
+        array::<T>(components, false)
+    }
+}
+
+impl<T: ToSchema> ToSchema for BTreeSet<T> {
+    fn schema(components: &mut Components) -> Schema {
+        array::<T>(components, true)
+    }
+}
+
+impl<T: ToSchema, S> ToSchema for HashSet<T, S> {
+    fn schema(components: &mut Components) -> Schema {
+        array::<T>(components, true)
+    }
+}
+
+fn map<V: ToSchema>(components: &mut Components) -> Schema {
+    Schema {
+        additional_properties: Some(BooleanObjectOrReference::Object(Box::new(V::schema_ref(
+            components,
+        )))),
+        ..typed("object", None)
+    }
+}
+
+impl<K, V: ToSchema> ToSchema for BTreeMap<K, V> {
+    fn schema(components: &mut Components) -> Schema {
+        map::<V>(components)
+    }
+}
+
+impl<K, V: ToSchema, S> ToSchema for HashMap<K, V, S> {
+    fn schema(components: &mut Components) -> Schema {
+        map::<V>(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node;
+
+    impl ToSchema for Node {
+        fn schema_name() -> Option<String> {
+            Some("Node".into())
+        }
+
+        fn schema(components: &mut Components) -> Schema {
+            let mut properties = BTreeMap::new();
+            properties.insert("children".into(), Vec::<Node>::schema_ref(components));
+            Schema {
+                properties: Some(properties),
+                ..typed("object", None)
+            }
+        }
+    }
+
+    #[test]
+    fn registers_named_types() {
+        let mut components = Components::default();
+        let schema = components.add_schema::<Option<Vec<Node>>>();
+        assert_eq!(
+            schema.items.unwrap().ref_path.as_deref(),
+            Some("#/components/schemas/Node")
+        );
+        match &components.schemas.unwrap()["Node"] {
+            ObjectOrReference::Object(node) => assert_eq!(
+                node.properties.as_ref().unwrap()["children"]
+                    .items
+                    .as_ref()
+                    .unwrap()
+                    .ref_path
+                    .as_deref(),
+                Some("#/components/schemas/Node")
+            ),
+            other => panic!("unexpected schema {:?}", other),
+        }
+    }
+}
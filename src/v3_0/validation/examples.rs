@@ -0,0 +1,254 @@
+//! Validation of the examples and defaults of a spec against their schemas.
+
+use crate::{
+    pointer,
+    v3_0::{
+        parameters::resolve,
+        validation::{SchemaValidator, SchemaViolation},
+        visit::{self, Visit},
+        Example, MediaType, MediaTypeExample, ObjectOrReference, Schema, Spec,
+    },
+    Error, Result,
+};
+use std::{collections::HashSet, fmt};
+
+/// An example or default value which does not match its schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExampleViolation {
+    /// The JSON pointer of the example or default in the spec.
+    pub pointer: String,
+    /// The JSON pointer of the schema it was validated against.
+    pub schema: String,
+    pub violation: SchemaViolation,
+}
+
+impl fmt::Display for ExampleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}{}`: {}",
+            self.pointer, self.violation.pointer, self.violation.message
+        )
+    }
+}
+
+impl Spec {
+    /// Every way the examples and defaults of this spec do not match their schemas.
+    ///
+    /// These are the `example` and `default` of each schema, and the `example` and
+    /// `examples` of each media type with a schema. Examples of `components.examples` are
+    /// validated against the schemas of the media types referencing them, and reported at
+    /// their own pointer, once for each distinct schema. Parameters carry no examples of their
+    /// own in this model, so only the `example` and `default` of their schemas are checked.
+    ///
+    /// Fails if a schema or example holds an unresolvable reference.
+    pub fn validate_examples(&self) -> Result<Vec<ExampleViolation>> {
+        let mut visitor = Examples {
+            spec: self,
+            validator: self.schema_validator(),
+            violations: Vec::new(),
+            checked: HashSet::new(),
+            error: None,
+        };
+        visitor.visit_spec(self);
+        match visitor.error {
+            Some(error) => Err(error),
+            None => Ok(visitor.violations),
+        }
+    }
+}
+
+struct Examples<'a> {
+    spec: &'a Spec,
+    validator: SchemaValidator<'a>,
+    violations: Vec<ExampleViolation>,
+    /// The component examples checked, with the schema they were checked against.
+    checked: HashSet<(String, String)>,
+    /// The first failure, after which the traversal carries on without effect.
+    error: Option<Error>,
+}
+
+impl Examples<'_> {
+    fn check(&mut self, schema: &str, pointer: String, result: Result<Vec<SchemaViolation>>) {
+        if self.error.is_some() {
+            return;
+        }
+        match result {
+            Ok(violations) => self
+                .violations
+                .extend(violations.into_iter().map(|violation| ExampleViolation {
+                    pointer: pointer.clone(),
+                    schema: schema.to_string(),
+                    violation,
+                })),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Checks an example of a media type, which may be a reference to a component example.
+    fn check_media_example(
+        &mut self,
+        pointer: &str,
+        schema_pointer: &str,
+        schema: &ObjectOrReference<Schema>,
+        example: &ObjectOrReference<Example>,
+    ) {
+        let examples = self
+            .spec
+            .components
+            .as_ref()
+            .and_then(|components| components.examples.as_ref());
+        let resolved = match resolve(examples, "#/components/examples/", example) {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                self.error.get_or_insert(error);
+                return;
+            }
+        };
+        let pointer = match example {
+            ObjectOrReference::Object(_) => pointer.to_string(),
+            ObjectOrReference::Ref { ref_path } => {
+                let pointer = pointer::from_fragment(ref_path).into_owned();
+                // media types sharing a schema would report the same violations again
+                let reference = match schema {
+                    ObjectOrReference::Object(schema) => schema.ref_path.as_deref(),
+                    ObjectOrReference::Ref { ref_path } => Some(ref_path.as_str()),
+                };
+                let schema = match reference {
+                    Some(reference) => pointer::from_fragment(reference).into_owned(),
+                    None => schema_pointer.to_string(),
+                };
+                if !self.checked.insert((pointer.clone(), schema)) {
+                    return;
+                }
+                pointer
+            }
+        };
+        if let Some(value) = &resolved.value {
+            let result = self.validator.validate_reference(schema, value);
+            self.check(schema_pointer, pointer::join(&pointer, "value"), result);
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Examples<'a> {
+    fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+        for (keyword, value) in [("example", &schema.example), ("default", &schema.default)] {
+            if let Some(value) = value {
+                let result = self.validator.validate(schema, value);
+                self.check(pointer, pointer::join(pointer, keyword), result);
+            }
+        }
+        visit::walk_schema(self, pointer, schema)
+    }
+
+    fn visit_media_type(&mut self, pointer: &str, media_type: &'a MediaType) {
+        if let Some(schema) = &media_type.schema {
+            let schema_pointer = pointer::join(pointer, "schema");
+            match &media_type.examples {
+                Some(MediaTypeExample::Example { example }) => {
+                    let result = self.validator.validate_reference(schema, example);
+                    self.check(&schema_pointer, pointer::join(pointer, "example"), result);
+                }
+                Some(MediaTypeExample::Examples { examples }) => {
+                    for (name, example) in examples {
+                        let pointer = pointer::join(&pointer::join(pointer, "examples"), name);
+                        self.check_media_example(&pointer, &schema_pointer, schema, example);
+                    }
+                }
+                None => {}
+            }
+        }
+        visit::walk_media_type(self, pointer, media_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_examples_and_defaults() {
+        let spec: Spec = serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Examples
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+              examples:
+                rex:
+                  value: { name: Rex }
+                stale:
+                  $ref: "#/components/examples/Stale"
+            application/xml:
+              schema:
+                $ref: "#/components/schemas/Pet"
+              examples:
+                stale:
+                  $ref: "#/components/examples/Stale"
+            text/plain:
+              schema:
+                type: string
+              example: 42
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+          example: Rex
+        age:
+          type: integer
+          minimum: 0
+          default: -1
+      example:
+        name: 7
+  examples:
+    Stale:
+      value: { age: 3 }
+"##,
+        )
+        .unwrap();
+        let violations = spec.validate_examples().unwrap();
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| (violation.to_string(), violation.schema.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "`/components/examples/Stale/value`: missing property `name`".to_string(),
+                    "/paths/~1pets/get/responses/200/content/application~1json/schema"
+                ),
+                (
+                    "`/paths/~1pets/get/responses/200/content/text~1plain/example`: \
+                     42 is not of type `string`"
+                        .to_string(),
+                    "/paths/~1pets/get/responses/200/content/text~1plain/schema"
+                ),
+                (
+                    "`/components/schemas/Pet/example/name`: 7 is not of type `string`".to_string(),
+                    "/components/schemas/Pet"
+                ),
+                (
+                    "`/components/schemas/Pet/properties/age/default`: \
+                     -1 is less than the minimum of 0"
+                        .to_string(),
+                    "/components/schemas/Pet/properties/age"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! Checks of requests and responses against the operation they were routed to.
+
+use crate::{
+    v3_0::{
+        parameters::resolve,
+        validation::{Direction, Location, SchemaValidator, SchemaViolation, Violation},
+        MediaType, Parameter, Route, Spec,
+    },
+    Error, Method, Result,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// An HTTP request to check against a spec.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub method: Method,
+    /// The path relative to a server URL, with any query string.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    /// The media type of the body, which may carry parameters such as a `charset`.
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// An HTTP response to check against the operation of its request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// The media type of the body, which may carry parameters such as a `charset`.
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// A request without headers or body.
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Request {
+            method,
+            path: path.into(),
+            headers: Vec::new(),
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body and its media type.
+    pub fn body(mut self, content_type: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        self.content_type = Some(content_type.into());
+        self.body = body.into();
+        self
+    }
+}
+
+impl Response {
+    /// A response without headers or body.
+    pub fn new(status: u16) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body and its media type.
+    pub fn body(mut self, content_type: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        self.content_type = Some(content_type.into());
+        self.body = body.into();
+        self
+    }
+}
+
+impl Spec {
+    /// Checks a request against the operation it is routed to, as by
+    /// [`Route::validate_request`](../struct.Route.html#method.validate_request). A request
+    /// no operation matches is a violation.
+    pub fn validate_request(&self, request: &Request) -> Result<Vec<Violation>> {
+        match self.router().find(request.method, &request.path) {
+            Some(route) => route.validate_request(request),
+            None => Ok(vec![Violation::new(
+                Location::Operation,
+                format!("no operation matches {} {}", request.method, request.path),
+            )]),
+        }
+    }
+}
+
+impl<'a> Route<'a> {
+    /// Every way a request to this route does not conform to its operation: missing
+    /// required parameters and body, parameter values which cannot be parsed or do not
+    /// match their schema, and bodies of undocumented media types or which do not match
+    /// their schema. Only JSON bodies are checked against their schema.
+    ///
+    /// Fails if the operation itself is invalid, as with unresolvable references.
+    pub fn validate_request(&self, request: &Request) -> Result<Vec<Violation>> {
+        let validator = self.spec.schema_validator().direction(Direction::Request);
+        let mut violations = Vec::new();
+        let query = request
+            .path
+            .split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default();
+        for parameter in self.parameters()? {
+            let raw = match parameter.location.as_str() {
+                "path" => self.path_parameters.get(&parameter.name).cloned(),
+                "query" => Some(query.to_string()),
+                "header" => header_value(&request.headers, &parameter.name, ","),
+                "cookie" => header_value(&request.headers, "Cookie", "; "),
+                _ => continue,
+            };
+            let location = Location::Parameter {
+                location: parameter.location.clone(),
+                name: parameter.name.clone(),
+            };
+            check_parameter(
+                &validator,
+                parameter,
+                raw.as_deref(),
+                location,
+                &mut violations,
+            )?;
+        }
+
+        let request_body = match &self.operation.request_body {
+            Some(request_body) => Some(self.spec.resolve_request_body(request_body)?),
+            None => None,
+        };
+        let absent = request.body.is_empty() && request.content_type.is_none();
+        match request_body {
+            Some(request_body) if absent && request_body.required == Some(true) => {
+                violations.push(Violation::new(Location::Body, "missing required body"))
+            }
+            Some(_) if absent => {}
+            Some(request_body) => check_body(
+                &validator,
+                &request_body.content,
+                request.content_type.as_deref(),
+                &request.body,
+                &mut violations,
+            )?,
+            None if !absent => violations.push(Violation::new(
+                Location::Body,
+                "the operation does not accept a body",
+            )),
+            None => {}
+        }
+        Ok(violations)
+    }
+
+    /// Every way a response to a request to this route does not conform to its operation:
+    /// an undocumented status, missing required headers, header values which cannot be
+    /// parsed or do not match their schema, and missing bodies, bodies of undocumented
+    /// media types or which do not match their schema. Only JSON bodies are checked
+    /// against their schema.
+    ///
+    /// Fails if the operation itself is invalid, as with unresolvable references.
+    pub fn validate_response(&self, response: &Response) -> Result<Vec<Violation>> {
+        let status = response.status.to_string();
+        let range = format!("{}XX", response.status / 100);
+        let responses = &self.operation.responses;
+        let documented = responses
+            .get(&status)
+            .or_else(|| {
+                responses
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&range))
+                    .map(|(_, documented)| documented)
+            })
+            .or_else(|| responses.get("default"));
+        let documented = match documented {
+            Some(documented) => documented,
+            None => {
+                return Ok(vec![Violation::new(
+                    Location::Status,
+                    format!("status {} is not documented", response.status),
+                )])
+            }
+        };
+
+        let validator = self.spec.schema_validator().direction(Direction::Response);
+        let mut violations = Vec::new();
+        let components = self.spec.components.as_ref();
+        for (name, header) in documented.headers.iter().flatten() {
+            if name.eq_ignore_ascii_case("Content-Type") {
+                continue;
+            }
+            let header = resolve(
+                components.and_then(|components| components.headers.as_ref()),
+                "#/components/headers/",
+                header,
+            )?;
+            let parameter = Parameter {
+                name: name.clone(),
+                location: "header".into(),
+                required: header.required,
+                schema: header.schema.clone(),
+                param_type: header.param_type.clone(),
+                format: header.format.clone(),
+                ..Parameter::default()
+            };
+            check_parameter(
+                &validator,
+                &parameter,
+                header_value(&response.headers, name, ",").as_deref(),
+                Location::Header(name.clone()),
+                &mut violations,
+            )?;
+        }
+
+        let empty = BTreeMap::new();
+        let content = documented.content.as_ref().unwrap_or(&empty);
+        if response.body.is_empty() && response.content_type.is_none() {
+            if !content.is_empty() {
+                violations.push(Violation::new(Location::Body, "missing body"));
+            }
+        } else if content.is_empty() {
+            violations.push(Violation::new(
+                Location::Body,
+                format!("status {} documents no body", response.status),
+            ));
+        } else {
+            check_body(
+                &validator,
+                content,
+                response.content_type.as_deref(),
+                &response.body,
+                &mut violations,
+            )?;
+        }
+        Ok(violations)
+    }
+}
+
+/// The values of a header, joined by `separator`, or `None` if there are none.
+fn header_value(headers: &[(String, String)], name: &str, separator: &str) -> Option<String> {
+    let values = headers
+        .iter()
+        .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(separator))
+    }
+}
+
+/// Checks the raw value of a parameter, if present.
+fn check_parameter(
+    validator: &SchemaValidator,
+    parameter: &Parameter,
+    raw: Option<&str>,
+    location: Location,
+    violations: &mut Vec<Violation>,
+) -> Result<()> {
+    let value = match raw.map(|raw| parameter.decode_value(raw)).transpose() {
+        Ok(value) => value.flatten(),
+        Err(Error::InvalidParameterValue { reason, .. }) => {
+            violations.push(Violation::new(location, reason));
+            return Ok(());
+        }
+        Err(error) => return Err(error),
+    };
+    match (value, &parameter.schema) {
+        (None, _) => {
+            if parameter.required == Some(true) || parameter.location == "path" {
+                violations.push(Violation::new(location, "missing required value"));
+            }
+        }
+        (Some(value), Some(schema)) => {
+            push_errors(violations, &location, validator.validate(schema, &value)?)
+        }
+        (Some(_), None) => {}
+    }
+    Ok(())
+}
+
+/// Checks a body against the media type of `content` matching its content type.
+fn check_body(
+    validator: &SchemaValidator,
+    content: &BTreeMap<String, MediaType>,
+    content_type: Option<&str>,
+    body: &[u8],
+    violations: &mut Vec<Violation>,
+) -> Result<()> {
+    let content_type = content_type
+        .unwrap_or_default()
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let media = match media_type(content, &content_type) {
+        Some(media) => media,
+        None => {
+            violations.push(Violation::new(
+                Location::Body,
+                format!("media type `{}` is not documented", content_type),
+            ));
+            return Ok(());
+        }
+    };
+    let schema = match &media.schema {
+        Some(schema) if content_type == "application/json" || content_type.ends_with("+json") => {
+            schema
+        }
+        _ => return Ok(()),
+    };
+    let value = match serde_json::from_slice::<Value>(body) {
+        Ok(value) => value,
+        Err(error) => {
+            violations.push(Violation::new(
+                Location::Body,
+                format!("invalid JSON: {}", error),
+            ));
+            return Ok(());
+        }
+    };
+    push_errors(
+        violations,
+        &Location::Body,
+        validator.validate_reference(schema, &value)?,
+    );
+    Ok(())
+}
+
+/// The most specific media type of `content` matching a content type: an exact match,
+/// then a `type/*` range, then `*/*`.
+fn media_type<'a>(
+    content: &'a BTreeMap<String, MediaType>,
+    content_type: &str,
+) -> Option<&'a MediaType> {
+    let range = format!("{}/*", content_type.split('/').next().unwrap_or_default());
+    [content_type, &range, "*/*"].iter().find_map(|key| {
+        content
+            .iter()
+            .find(|(media_type, _)| media_type.eq_ignore_ascii_case(key))
+            .map(|(_, media)| media)
+    })
+}
+
+fn push_errors(violations: &mut Vec<Violation>, location: &Location, errors: Vec<SchemaViolation>) {
+    violations.extend(errors.into_iter().map(|error| Violation {
+        location: location.clone(),
+        pointer: error.pointer,
+        message: error.message,
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    fn spec() -> Spec {
+        match from_path("data/v3.0/petstore.yaml").unwrap() {
+            OpenApi::V3_0(spec) => spec,
+            _ => panic!("expected a v3.0 spec"),
+        }
+    }
+
+    fn messages(violations: Vec<Violation>) -> Vec<String> {
+        violations.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn validates_requests() {
+        let spec = spec();
+        assert!(spec
+            .validate_request(&Request::new(Method::Get, "/pets?limit=10"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            messages(
+                spec.validate_request(&Request::new(Method::Get, "/pets?limit=many"))
+                    .unwrap()
+            ),
+            vec!["query parameter `limit`: `many` is not a valid integer"]
+        );
+        assert_eq!(
+            messages(
+                spec.validate_request(&Request::new(Method::Delete, "/pets"))
+                    .unwrap()
+            ),
+            vec!["operation: no operation matches DELETE /pets"]
+        );
+        assert_eq!(
+            messages(
+                spec.validate_request(
+                    &Request::new(Method::Post, "/pets").body("application/json", "{}")
+                )
+                .unwrap()
+            ),
+            vec!["body: the operation does not accept a body"]
+        );
+    }
+
+    #[test]
+    fn validates_responses() {
+        let spec = spec();
+        let router = spec.router();
+        let route = router.find(Method::Get, "/pets").unwrap();
+        let ok = Response::new(200).body(
+            "application/json; charset=utf-8",
+            r#"[{"id": 1, "name": "Rex"}]"#,
+        );
+        assert!(route.validate_response(&ok).unwrap().is_empty());
+        let wrong = Response::new(200).body(
+            "application/json",
+            r#"[{"id": "one", "name": "Rex"}, {"id": 2}]"#,
+        );
+        assert_eq!(
+            messages(route.validate_response(&wrong).unwrap()),
+            vec![
+                "body at `/0/id`: \"one\" is not of type `integer`",
+                "body at `/1`: missing property `name`",
+            ]
+        );
+        assert_eq!(
+            messages(
+                route
+                    .validate_response(&Response::new(500).body("text/html", "oops"))
+                    .unwrap()
+            ),
+            vec!["body: media type `text/html` is not documented"]
+        );
+
+        let route = router.find(Method::Post, "/pets").unwrap();
+        assert!(route
+            .validate_response(&Response::new(201))
+            .unwrap()
+            .is_empty());
+        let mut spec = spec.clone();
+        spec.paths
+            .get_mut("/pets")
+            .unwrap()
+            .post
+            .as_mut()
+            .unwrap()
+            .responses
+            .remove("default");
+        let router = spec.router();
+        let route = router.find(Method::Post, "/pets").unwrap();
+        assert_eq!(
+            messages(route.validate_response(&Response::new(404)).unwrap()),
+            vec!["status: status 404 is not documented"]
+        );
+    }
+}
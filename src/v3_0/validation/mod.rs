@@ -0,0 +1,92 @@
+//! Validation of specs against the rules of the specification, of values against
+//! schemas, and of HTTP exchanges against the operations of a spec.
+//!
+//! ```
+//! use openapi::{v3_0::validation::Request, Method, OpenApi};
+//!
+//! # fn main() -> openapi::Result<()> {
+//! let spec = match openapi::from_path("data/v3.0/petstore.yaml")? {
+//!     OpenApi::V3_0(spec) => spec,
+//!     _ => unreachable!(),
+//! };
+//! let violations = spec.validate_request(&Request::new(Method::Get, "/pets?limit=ten"))?;
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(
+//!     violations[0].to_string(),
+//!     "query parameter `limit`: `ten` is not a valid integer"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+mod examples;
+mod exchange;
+mod schema;
+mod spec;
+
+pub use crate::v3_0::validation::{examples::*, exchange::*, schema::*};
+
+use std::fmt;
+
+/// Where in an exchange a violation was found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Location {
+    /// The method and path of the request.
+    Operation,
+    /// A parameter of the request, by location and name.
+    Parameter { location: String, name: String },
+    /// A header of the response.
+    Header(String),
+    /// The body of the request or response.
+    Body,
+    /// The status code of the response.
+    Status,
+}
+
+/// A way in which a request or response does not conform to its operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub location: Location,
+    /// The JSON pointer of the offending part of the value at `location`, empty for the
+    /// whole value.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub(crate) fn new(location: Location, message: impl Into<String>) -> Self {
+        Violation {
+            location,
+            pointer: String::new(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Operation => f.write_str("operation"),
+            Location::Parameter { location, name } => {
+                write!(f, "{} parameter `{}`", location, name)
+            }
+            Location::Header(name) => write!(f, "header `{}`", name),
+            Location::Body => f.write_str("body"),
+            Location::Status => f.write_str("status"),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}: {}", self.location, self.message)
+        } else {
+            write!(
+                f,
+                "{} at `{}`: {}",
+                self.location, self.pointer, self.message
+            )
+        }
+    }
+}
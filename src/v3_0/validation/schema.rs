@@ -0,0 +1,826 @@
+//! Validation of JSON values against the schemas of a spec, following the OpenAPI 3.0
+//! dialect of JSON Schema.
+
+use crate::{
+    pointer,
+    v3_0::{
+        parameters::{resolve, MAX_REFERENCE_DEPTH},
+        BooleanObjectOrReference, Components, ObjectOrReference, Schema, Spec,
+    },
+    Error, Result,
+};
+use regex::Regex;
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+/// Which way a value travels. `readOnly` properties may not be sent in requests and
+/// `writeOnly` properties may not be returned in responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Request,
+    Response,
+}
+
+/// A way in which a value does not match a schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaViolation {
+    /// The JSON pointer of the offending part of the value, empty for the whole value.
+    pub pointer: String,
+    /// The location of the failed keyword: a JSON pointer into the validated schema, or
+    /// into a component schema when it starts with `#/components/schemas/`.
+    pub keyword: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.pointer.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "`{}`: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Validates values against schemas, resolving references to `#/components/schemas`.
+///
+/// Formats other than `int32`, `int64`, `byte`, `date`, `date-time`, `email`, `uuid`,
+/// `uri`, `ipv4` and `ipv6` are not checked.
+///
+/// ```
+/// use openapi::v3_0::{validation::SchemaValidator, Schema};
+/// use serde_json::json;
+///
+/// let schema: Schema = serde_yaml::from_str(
+///     "{type: object, properties: {age: {type: integer, minimum: 0}}}",
+/// )
+/// .unwrap();
+/// let violations = SchemaValidator::new(None)
+///     .validate(&schema, &json!({"age": -1}))
+///     .unwrap();
+/// assert_eq!(violations[0].to_string(), "`/age`: -1 is less than the minimum of 0");
+/// assert_eq!(violations[0].keyword, "/properties/age/minimum");
+/// ```
+pub struct SchemaValidator<'a> {
+    schemas: Option<&'a BTreeMap<String, ObjectOrReference<Schema>>>,
+    direction: Option<Direction>,
+    patterns: RefCell<HashMap<String, Regex>>,
+}
+
+impl Spec {
+    /// A validator resolving references to the schemas of this spec.
+    pub fn schema_validator(&self) -> SchemaValidator<'_> {
+        SchemaValidator::new(self.components.as_ref())
+    }
+}
+
+impl<'a> SchemaValidator<'a> {
+    /// A validator resolving references to `components`. Without a direction,
+    /// `readOnly` and `writeOnly` are ignored.
+    pub fn new(components: Option<&'a Components>) -> Self {
+        SchemaValidator {
+            schemas: components.and_then(|components| components.schemas.as_ref()),
+            direction: None,
+            patterns: RefCell::default(),
+        }
+    }
+
+    /// Validates values travelling in `direction`.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Every way `value` does not match `schema`.
+    ///
+    /// Fails if `schema` holds unresolvable references or invalid patterns.
+    pub fn validate(&self, schema: &Schema, value: &Value) -> Result<Vec<SchemaViolation>> {
+        let mut violations = Vec::new();
+        self.check(schema, value, "", "", &mut violations)?;
+        Ok(violations)
+    }
+
+    /// Every way `value` does not match a schema which may be a reference.
+    pub fn validate_reference(
+        &self,
+        schema: &ObjectOrReference<Schema>,
+        value: &Value,
+    ) -> Result<Vec<SchemaViolation>> {
+        match schema {
+            ObjectOrReference::Object(schema) => self.validate(schema, value),
+            ObjectOrReference::Ref { ref_path } => self.validate(
+                &Schema {
+                    ref_path: Some(ref_path.clone()),
+                    ..Schema::default()
+                },
+                value,
+            ),
+        }
+    }
+
+    /// Whether `value` matches `schema`.
+    pub fn is_valid(&self, schema: &Schema, value: &Value) -> Result<bool> {
+        Ok(self.validate(schema, value)?.is_empty())
+    }
+
+    /// Follows the references of a schema, returning the schema reached and its keyword
+    /// location.
+    fn target<'s>(&'s self, schema: &'s Schema, keyword: &str) -> Result<(&'s Schema, String)> {
+        let mut schema = schema;
+        let mut keyword = keyword.to_string();
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            let ref_path = match &schema.ref_path {
+                Some(ref_path) => ref_path,
+                None => return Ok((schema, keyword)),
+            };
+            let component = ref_path
+                .strip_prefix(SCHEMAS)
+                .and_then(|name| self.schemas?.get(pointer::unescape(name).as_ref()))
+                .ok_or_else(|| Error::UnresolvedReference(ref_path.clone()))?;
+            keyword = ref_path.clone();
+            schema = resolve(self.schemas, SCHEMAS, component)?;
+        }
+        Err(Error::UnresolvedReference(keyword))
+    }
+
+    /// Follows the references of a subschema, as found in `allOf`, `oneOf`, `anyOf` and
+    /// `not`.
+    fn subschema<'s>(
+        &'s self,
+        schema: &'s ObjectOrReference<Schema>,
+        keyword: String,
+    ) -> Result<(&'s Schema, String)> {
+        match schema {
+            ObjectOrReference::Object(schema) => self.target(schema, &keyword),
+            ObjectOrReference::Ref { ref_path } => {
+                let schema = resolve(self.schemas, SCHEMAS, schema)?;
+                self.target(schema, ref_path)
+            }
+        }
+    }
+
+    fn check(
+        &self,
+        schema: &Schema,
+        value: &Value,
+        at: &str,
+        keyword: &str,
+        violations: &mut Vec<SchemaViolation>,
+    ) -> Result<()> {
+        let (schema, keyword) = self.target(schema, keyword)?;
+        let mut violation = |name: &str, message: String| {
+            violations.push(SchemaViolation {
+                pointer: at.to_string(),
+                keyword: pointer::join(&keyword, name),
+                message,
+            })
+        };
+        if value.is_null() && schema.nullable == Some(true) {
+            return Ok(());
+        }
+        if let Some(schema_type) = &schema.schema_type {
+            if !has_type(value, schema_type) {
+                violation(
+                    "type",
+                    format!("{} is not of type `{}`", value, schema_type),
+                );
+                return Ok(());
+            }
+        }
+        if let Some(enum_values) = &schema.enum_values {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                value => value.to_string(),
+            };
+            if !enum_values.contains(&text) {
+                violation(
+                    "enum",
+                    format!("{} is not one of {}", value, enum_values.join(", ")),
+                );
+            }
+        }
+        if let Some(format) = &schema.format {
+            if !has_format(value, format) {
+                violation("format", format!("{} is not a valid `{}`", value, format));
+            }
+        }
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(maximum) = schema.maximum {
+                    let maximum = f64::from(maximum);
+                    if schema.exclusive_maximum == Some(true) && number >= maximum {
+                        violation(
+                            "exclusiveMaximum",
+                            format!("{} is not less than {}", value, maximum),
+                        );
+                    } else if number > maximum {
+                        violation(
+                            "maximum",
+                            format!("{} is greater than the maximum of {}", value, maximum),
+                        );
+                    }
+                }
+                if let Some(minimum) = schema.minimum {
+                    let minimum = f64::from(minimum);
+                    if schema.exclusive_minimum == Some(true) && number <= minimum {
+                        violation(
+                            "exclusiveMinimum",
+                            format!("{} is not greater than {}", value, minimum),
+                        );
+                    } else if number < minimum {
+                        violation(
+                            "minimum",
+                            format!("{} is less than the minimum of {}", value, minimum),
+                        );
+                    }
+                }
+                if let Some(multiple_of) = schema.multiple_of.filter(|multiple| *multiple > 0) {
+                    if (number / f64::from(multiple_of)).fract() != 0.0 {
+                        violation(
+                            "multipleOf",
+                            format!("{} is not a multiple of {}", value, multiple_of),
+                        );
+                    }
+                }
+            }
+            Value::String(text) => {
+                let length = text.chars().count();
+                if let Some(max_length) = schema.max_length {
+                    if length > max_length as usize {
+                        violation(
+                            "maxLength",
+                            format!("{} is longer than {} characters", value, max_length),
+                        );
+                    }
+                }
+                if let Some(min_length) = schema.min_length {
+                    if length < min_length as usize {
+                        violation(
+                            "minLength",
+                            format!("{} is shorter than {} characters", value, min_length),
+                        );
+                    }
+                }
+                if let Some(pattern) = &schema.pattern {
+                    if !self.matches(pattern, text)? {
+                        violation("pattern", format!("{} does not match `{}`", value, pattern));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(max_items) = schema.max_items {
+                    if items.len() > max_items as usize {
+                        violation("maxItems", format!("has more than {} items", max_items));
+                    }
+                }
+                if let Some(min_items) = schema.min_items {
+                    if items.len() < min_items as usize {
+                        violation("minItems", format!("has fewer than {} items", min_items));
+                    }
+                }
+                if schema.unique_items == Some(true) {
+                    let duplicate = items
+                        .iter()
+                        .enumerate()
+                        .any(|(index, item)| items[..index].contains(item));
+                    if duplicate {
+                        violation("uniqueItems", "has duplicate items".into());
+                    }
+                }
+            }
+            Value::Object(object) => {
+                if let Some(max_properties) = schema.max_properties {
+                    if object.len() > max_properties as usize {
+                        violation(
+                            "maxProperties",
+                            format!("has more than {} properties", max_properties),
+                        );
+                    }
+                }
+                if let Some(min_properties) = schema.min_properties {
+                    if object.len() < min_properties as usize {
+                        violation(
+                            "minProperties",
+                            format!("has fewer than {} properties", min_properties),
+                        );
+                    }
+                }
+                for name in schema.required.iter().flatten() {
+                    if object.contains_key(name) {
+                        continue;
+                    }
+                    // properties which may not travel in this direction are not required
+                    let excused = match schema
+                        .properties
+                        .as_ref()
+                        .and_then(|properties| properties.get(name))
+                    {
+                        Some(property) => self.excluded(self.target(property, "")?.0),
+                        None => false,
+                    };
+                    if !excused {
+                        violation("required", format!("missing property `{}`", name));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        match value {
+            Value::Array(items) => {
+                if let Some(item_schema) = &schema.items {
+                    let keyword = pointer::join(&keyword, "items");
+                    for (index, item) in items.iter().enumerate() {
+                        let at = pointer::join(at, &index.to_string());
+                        self.check(item_schema, item, &at, &keyword, violations)?;
+                    }
+                }
+            }
+            Value::Object(object) => {
+                for (name, property) in object {
+                    let at = pointer::join(at, name);
+                    let declared = schema
+                        .properties
+                        .as_ref()
+                        .and_then(|properties| properties.get(name));
+                    if let Some(declared) = declared {
+                        let keyword = pointer::join(&pointer::join(&keyword, "properties"), name);
+                        let (target, _) = self.target(declared, &keyword)?;
+                        if self.excluded(target) {
+                            let (name, message) = match self.direction {
+                                Some(Direction::Request) => ("readOnly", "property is read-only"),
+                                _ => ("writeOnly", "property is write-only"),
+                            };
+                            violations.push(SchemaViolation {
+                                pointer: at,
+                                keyword: pointer::join(&keyword, name),
+                                message: message.into(),
+                            });
+                        } else {
+                            self.check(declared, property, &at, &keyword, violations)?;
+                        }
+                        continue;
+                    }
+                    let keyword = pointer::join(&keyword, "additionalProperties");
+                    match &schema.additional_properties {
+                        Some(BooleanObjectOrReference::Boolean(false)) => {
+                            violations.push(SchemaViolation {
+                                pointer: at,
+                                keyword,
+                                message: "property is not allowed".into(),
+                            })
+                        }
+                        Some(BooleanObjectOrReference::Object(additional)) => {
+                            self.check(additional, property, &at, &keyword, violations)?
+                        }
+                        Some(BooleanObjectOrReference::Ref { ref_path }) => self.check(
+                            &Schema {
+                                ref_path: Some(ref_path.clone()),
+                                ..Schema::default()
+                            },
+                            property,
+                            &at,
+                            &keyword,
+                            violations,
+                        )?,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for (index, part) in schema.all_of.iter().flatten().enumerate() {
+            let (part, keyword) =
+                self.subschema(part, pointer::join(&keyword, &format!("allOf/{}", index)))?;
+            self.check(part, value, at, &keyword, violations)?;
+        }
+        for (name, alternatives) in [("oneOf", &schema.one_of), ("anyOf", &schema.any_of)] {
+            let alternatives = match alternatives {
+                Some(alternatives) if !alternatives.is_empty() => alternatives,
+                _ => continue,
+            };
+            let mut matched = 0;
+            for (index, alternative) in alternatives.iter().enumerate() {
+                let (alternative, keyword) = self.subschema(
+                    alternative,
+                    pointer::join(&keyword, &format!("{}/{}", name, index)),
+                )?;
+                let mut alternative_violations = Vec::new();
+                self.check(
+                    alternative,
+                    value,
+                    at,
+                    &keyword,
+                    &mut alternative_violations,
+                )?;
+                matched += alternative_violations.is_empty() as usize;
+            }
+            let message = if matched == 0 {
+                format!("matches none of the schemas of `{}`", name)
+            } else if name == "oneOf" && matched > 1 {
+                format!("matches {} of the schemas of `oneOf`", matched)
+            } else {
+                continue;
+            };
+            violations.push(SchemaViolation {
+                pointer: at.to_string(),
+                keyword: pointer::join(&keyword, name),
+                message,
+            });
+        }
+        for (index, not) in schema.not.iter().flatten().enumerate() {
+            let (not, not_keyword) =
+                self.subschema(not, pointer::join(&keyword, &format!("not/{}", index)))?;
+            let mut not_violations = Vec::new();
+            self.check(not, value, at, &not_keyword, &mut not_violations)?;
+            if not_violations.is_empty() {
+                violations.push(SchemaViolation {
+                    pointer: at.to_string(),
+                    keyword: pointer::join(&keyword, "not"),
+                    message: "matches a schema of `not`".into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a property may not travel in the validator's direction.
+    fn excluded(&self, property: &Schema) -> bool {
+        match self.direction {
+            Some(Direction::Request) => property.read_only == Some(true),
+            Some(Direction::Response) => property.write_only == Some(true),
+            None => false,
+        }
+    }
+
+    /// Whether a pattern matches anywhere in `text`, compiling it once.
+    fn matches(&self, pattern: &str, text: &str) -> Result<bool> {
+        if let Some(regex) = self.patterns.borrow().get(pattern) {
+            return Ok(regex.is_match(text));
+        }
+        let regex = Regex::new(pattern).map_err(|error| Error::InvalidPattern {
+            pattern: pattern.into(),
+            reason: error.to_string(),
+        })?;
+        let matches = regex.is_match(text);
+        self.patterns.borrow_mut().insert(pattern.into(), regex);
+        Ok(matches)
+    }
+}
+
+fn has_type(value: &Value, schema_type: &str) -> bool {
+    match schema_type {
+        "integer" => match value {
+            Value::Number(number) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().is_some_and(|number| number.fract() == 0.0)
+            }
+            _ => false,
+        },
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Whether a value has a format. Formats which do not apply to the value's type, and
+/// unknown formats, always match.
+fn has_format(value: &Value, format: &str) -> bool {
+    if let Value::Number(number) = value {
+        return match format {
+            "int32" => number.as_i64().is_some_and(|number| {
+                number >= i64::from(i32::MIN) && number <= i64::from(i32::MAX)
+            }),
+            "int64" => number.is_i64(),
+            _ => true,
+        };
+    }
+    let text = match value {
+        Value::String(text) => text,
+        _ => return true,
+    };
+    match format {
+        "byte" => {
+            text.len() % 4 == 0
+                && text
+                    .trim_end_matches('=')
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'/')
+        }
+        "date" => is_date(text),
+        "date-time" => is_date_time(text),
+        "email" => match text.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+            }
+            None => false,
+        },
+        "uuid" => {
+            text.len() == 36
+                && text.char_indices().all(|(index, c)| match index {
+                    8 | 13 | 18 | 23 => c == '-',
+                    _ => c.is_ascii_hexdigit(),
+                })
+        }
+        "uri" => url::Url::parse(text).is_ok(),
+        "ipv4" => text.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => text.parse::<Ipv6Addr>().is_ok(),
+        _ => true,
+    }
+}
+
+/// Whether the characters of `text` between `start` and `end` are digits forming a
+/// number within `range`.
+fn digits(text: &str, start: usize, end: usize, range: std::ops::RangeInclusive<u32>) -> bool {
+    text.get(start..end).is_some_and(|digits| {
+        digits.bytes().all(|byte| byte.is_ascii_digit())
+            && digits
+                .parse()
+                .is_ok_and(|number: u32| range.contains(&number))
+    })
+}
+
+/// Whether text is an RFC 3339 `full-date`, such as `2020-12-31`.
+fn is_date(text: &str) -> bool {
+    text.len() == 10
+        && digits(text, 0, 4, 0..=9999)
+        && text.get(4..5) == Some("-")
+        && digits(text, 5, 7, 1..=12)
+        && text.get(7..8) == Some("-")
+        && digits(text, 8, 10, 1..=31)
+}
+
+/// Whether text is an RFC 3339 `date-time`, such as `2020-12-31T23:59:59.5+01:00`.
+fn is_date_time(text: &str) -> bool {
+    let (date, time) = match (text.get(..10), text.get(10..)) {
+        (Some(date), Some(time)) => (date, time),
+        _ => return false,
+    };
+    let rest = match time.get(..1) {
+        Some(separator) if separator.eq_ignore_ascii_case("t") => &time[1..],
+        _ => return false,
+    };
+    let is_time = digits(rest, 0, 2, 0..=23)
+        && rest.get(2..3) == Some(":")
+        && digits(rest, 3, 5, 0..=59)
+        && rest.get(5..6) == Some(":")
+        && digits(rest, 6, 8, 0..=60);
+    if !is_date(date) || !is_time {
+        return false;
+    }
+    let mut offset = &rest[8..];
+    if let Some(fraction) = offset.strip_prefix('.') {
+        let length = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if length == 0 {
+            return false;
+        }
+        offset = &fraction[length..];
+    }
+    offset.eq_ignore_ascii_case("z")
+        || (offset.len() == 6
+            && (offset.starts_with('+') || offset.starts_with('-'))
+            && digits(offset, 1, 3, 0..=23)
+            && offset.get(3..4) == Some(":")
+            && digits(offset, 4, 6, 0..=59))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Spec {
+        serde_yaml::from_str(
+            r##"
+openapi: 3.0.0
+info:
+  title: Validation
+  version: 1.0.0
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      additionalProperties: false
+      properties:
+        id:
+          type: integer
+          format: int64
+          readOnly: true
+        name:
+          type: string
+          minLength: 1
+          pattern: "^[A-Z]"
+        password:
+          type: string
+          writeOnly: true
+        born:
+          type: string
+          format: date
+        tags:
+          type: array
+          uniqueItems: true
+          items:
+            type: string
+            nullable: true
+    Shape:
+      oneOf:
+        - $ref: "#/components/schemas/Square"
+        - $ref: "#/components/schemas/Circle"
+    Square:
+      type: object
+      required: [side]
+      properties:
+        side:
+          type: number
+          exclusiveMinimum: true
+          minimum: 0
+    Circle:
+      type: object
+      required: [radius]
+      properties:
+        radius:
+          type: number
+"##,
+        )
+        .unwrap()
+    }
+
+    fn reference(name: &str) -> Schema {
+        Schema {
+            ref_path: Some(format!("{}{}", SCHEMAS, name)),
+            ..Schema::default()
+        }
+    }
+
+    fn details(violations: Vec<SchemaViolation>) -> Vec<(String, String, String)> {
+        violations
+            .into_iter()
+            .map(|violation| (violation.pointer, violation.keyword, violation.message))
+            .collect()
+    }
+
+    fn detail(pointer: &str, keyword: &str, message: &str) -> (String, String, String) {
+        (pointer.into(), keyword.into(), message.into())
+    }
+
+    #[test]
+    fn validates_keywords_with_paths() {
+        let spec = spec();
+        let validator = spec.schema_validator();
+        let pet = reference("Pet");
+        assert!(validator
+            .is_valid(
+                &pet,
+                &json!({"id": 1, "name": "Rex", "born": "2020-02-29", "tags": ["a", null]})
+            )
+            .unwrap());
+        let violations = validator
+            .validate(
+                &pet,
+                &json!({
+                    "id": 1.5,
+                    "name": "rex",
+                    "born": "yesterday",
+                    "tags": ["a", "a"],
+                    "age": 3
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            details(violations),
+            vec![
+                detail(
+                    "/age",
+                    "#/components/schemas/Pet/additionalProperties",
+                    "property is not allowed"
+                ),
+                detail(
+                    "/born",
+                    "#/components/schemas/Pet/properties/born/format",
+                    "\"yesterday\" is not a valid `date`"
+                ),
+                detail(
+                    "/id",
+                    "#/components/schemas/Pet/properties/id/type",
+                    "1.5 is not of type `integer`"
+                ),
+                detail(
+                    "/name",
+                    "#/components/schemas/Pet/properties/name/pattern",
+                    "\"rex\" does not match `^[A-Z]`"
+                ),
+                detail(
+                    "/tags",
+                    "#/components/schemas/Pet/properties/tags/uniqueItems",
+                    "has duplicate items"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_by_direction() {
+        let spec = spec();
+        let pet = reference("Pet");
+        let request = spec.schema_validator().direction(Direction::Request);
+        assert!(request
+            .is_valid(&pet, &json!({"name": "Rex", "password": "secret"}))
+            .unwrap());
+        assert_eq!(
+            request
+                .validate(&pet, &json!({"id": 1, "name": "Rex"}))
+                .unwrap()[0]
+                .to_string(),
+            "`/id`: property is read-only"
+        );
+        let response = spec.schema_validator().direction(Direction::Response);
+        assert_eq!(
+            details(
+                response
+                    .validate(&pet, &json!({"name": "Rex", "password": "secret"}))
+                    .unwrap()
+            ),
+            vec![
+                detail(
+                    "",
+                    "#/components/schemas/Pet/required",
+                    "missing property `id`"
+                ),
+                detail(
+                    "/password",
+                    "#/components/schemas/Pet/properties/password/writeOnly",
+                    "property is write-only"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_combinations() {
+        let spec = spec();
+        let validator = spec.schema_validator();
+        let shape = reference("Shape");
+        assert!(validator.is_valid(&shape, &json!({"side": 2})).unwrap());
+        assert_eq!(
+            details(validator.validate(&shape, &json!({"side": 0})).unwrap()),
+            vec![detail(
+                "",
+                "#/components/schemas/Shape/oneOf",
+                "matches none of the schemas of `oneOf`"
+            )]
+        );
+        assert_eq!(
+            validator
+                .validate(&shape, &json!({"side": 1, "radius": 1}))
+                .unwrap()[0]
+                .message,
+            "matches 2 of the schemas of `oneOf`"
+        );
+
+        let not: Schema = serde_yaml::from_str("{not: [{type: string}]}").unwrap();
+        assert!(validator.is_valid(&not, &json!(1)).unwrap());
+        assert_eq!(
+            validator.validate(&not, &json!("one")).unwrap()[0].keyword,
+            "/not"
+        );
+        assert!(matches!(
+            validator.validate(&reference("Missing"), &json!(1)),
+            Err(Error::UnresolvedReference(_))
+        ));
+    }
+
+    #[test]
+    fn validates_formats() {
+        assert!(has_format(
+            &json!("2020-12-31T23:59:59.5+01:00"),
+            "date-time"
+        ));
+        assert!(has_format(&json!("2020-12-31t23:59:59Z"), "date-time"));
+        assert!(!has_format(&json!("2020-12-31 23:59:59Z"), "date-time"));
+        assert!(!has_format(&json!("2020-13-01"), "date"));
+        assert!(has_format(&json!("aGk="), "byte"));
+        assert!(!has_format(&json!("aGk"), "byte"));
+        assert!(has_format(
+            &json!("123e4567-e89b-12d3-a456-426614174000"),
+            "uuid"
+        ));
+        assert!(!has_format(&json!(3_000_000_000u64), "int32"));
+        assert!(has_format(&json!("::1"), "ipv6"));
+        assert!(has_format(&json!("anything"), "password"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Checks of the rules of the specification which parsing does not enforce.
+
+use crate::{
+    pointer, template,
+    v3_0::{
+        visit::{self, Visit},
+        Components, Operation, PathItem, SecurityRequirement, Server, Spec,
+    },
+    Error, Problem, Result,
+};
+use std::collections::BTreeMap;
+
+impl Spec {
+    /// Every rule of the specification this spec breaks which parsing does not enforce:
+    ///
+    /// * paths start with `/` and every template expression of a path has a required path
+    ///   parameter, and every path parameter a template expression
+    /// * `operationId`s are unique, including those of callbacks
+    /// * operations have at least one response
+    /// * the names of components match `^[a-zA-Z0-9\.\-_]+$`
+    /// * the variables referenced by server URLs are defined
+    /// * security requirements name defined security schemes
+    pub fn problems(&self) -> Vec<Problem> {
+        let mut rules = Rules {
+            spec: self,
+            operation_ids: BTreeMap::new(),
+            problems: Vec::new(),
+        };
+        rules.paths();
+        rules.visit_spec(self);
+        rules.problems
+    }
+
+    /// Checks the rules listed by [`problems`](#method.problems), failing with
+    /// [`Error::InvalidSpec`] if any are broken.
+    pub fn validate(&self) -> Result<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSpec(problems))
+        }
+    }
+}
+
+struct Rules<'a> {
+    spec: &'a Spec,
+    /// The pointers of the operations with each `operationId`, by the first one found.
+    operation_ids: BTreeMap<&'a str, String>,
+    problems: Vec<Problem>,
+}
+
+impl<'a> Rules<'a> {
+    fn problem(&mut self, pointer: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem::new(pointer, message));
+    }
+
+    fn paths(&mut self) {
+        for (path, path_item) in &self.spec.paths {
+            let pointer = pointer::join("/paths", path);
+            if !path.starts_with('/') {
+                self.problem(&pointer, "the path does not start with `/`");
+            }
+            let variables = match template::parse(path) {
+                Some(parts) => template::variables(&parts),
+                None => {
+                    self.problem(&pointer, "the path is not a valid template");
+                    continue;
+                }
+            };
+            for (method, operation) in path_item.operations() {
+                let pointer = pointer::join(&pointer, method.as_str());
+                self.path_parameters(&pointer, &variables, path_item, operation);
+            }
+        }
+    }
+
+    fn path_parameters(
+        &mut self,
+        pointer: &str,
+        variables: &[&str],
+        path_item: &PathItem,
+        operation: &Operation,
+    ) {
+        let parameters = match self.spec.effective_parameters(path_item, operation) {
+            Ok(parameters) => parameters,
+            Err(error) => return self.problem(pointer, error.to_string()),
+        };
+        for name in variables {
+            let parameter = parameters
+                .iter()
+                .find(|parameter| parameter.location == "path" && parameter.name == *name);
+            match parameter {
+                None => self.problem(
+                    pointer,
+                    format!("path parameter `{}` is not declared", name),
+                ),
+                Some(parameter) if parameter.required != Some(true) => self.problem(
+                    pointer,
+                    format!("path parameter `{}` is not required", name),
+                ),
+                Some(_) => {}
+            }
+        }
+        for parameter in parameters {
+            if parameter.location == "path" && !variables.contains(&parameter.name.as_str()) {
+                self.problem(
+                    pointer,
+                    format!(
+                        "path parameter `{}` does not appear in the path",
+                        parameter.name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn servers(&mut self, pointer: &str, servers: Option<&Vec<Server>>) {
+        for (index, server) in servers.into_iter().flatten().enumerate() {
+            let pointer = pointer::join(&pointer::join(pointer, "servers"), &index.to_string());
+            let names = match server.template_variables() {
+                Ok(names) => names,
+                Err(_) => {
+                    self.problem(pointer, "the URL is not a valid template");
+                    continue;
+                }
+            };
+            for name in names {
+                let defined = server
+                    .variables
+                    .as_ref()
+                    .is_some_and(|variables| variables.contains_key(name));
+                if !defined {
+                    self.problem(
+                        pointer::join(&pointer, "url"),
+                        format!("server variable `{}` is not defined", name),
+                    );
+                }
+            }
+        }
+    }
+
+    fn security(&mut self, pointer: &str, security: Option<&Vec<SecurityRequirement>>) {
+        let schemes = self
+            .spec
+            .components
+            .as_ref()
+            .and_then(|components| components.security_schemes.as_ref());
+        for (index, requirement) in security.into_iter().flatten().enumerate() {
+            let pointer = pointer::join(&pointer::join(pointer, "security"), &index.to_string());
+            for name in requirement.keys() {
+                if !schemes.is_some_and(|schemes| schemes.contains_key(name)) {
+                    self.problem(
+                        pointer::join(&pointer, name),
+                        format!("security scheme `{}` is not defined", name),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Rules<'a> {
+    fn visit_spec(&mut self, spec: &'a Spec) {
+        self.servers("", spec.servers.as_ref());
+        self.security("", spec.security.as_ref());
+        visit::walk_spec(self, spec)
+    }
+
+    fn visit_path_item(&mut self, pointer: &str, path_item: &'a PathItem) {
+        self.servers(pointer, path_item.servers.as_ref());
+        visit::walk_path_item(self, pointer, path_item)
+    }
+
+    fn visit_operation(&mut self, pointer: &str, operation: &'a Operation) {
+        if let Some(operation_id) = &operation.operation_id {
+            match self.operation_ids.get(operation_id.as_str()) {
+                Some(first) => {
+                    let message = format!(
+                        "operationId `{}` is already used by `{}`",
+                        operation_id, first
+                    );
+                    self.problem(pointer::join(pointer, "operationId"), message)
+                }
+                None => {
+                    self.operation_ids.insert(operation_id, pointer.to_string());
+                }
+            }
+        }
+        if operation.responses.is_empty() {
+            self.problem(
+                pointer::join(pointer, "responses"),
+                "the operation has no responses",
+            );
+        }
+        self.servers(pointer, operation.servers.as_ref());
+        self.security(pointer, operation.security.as_ref());
+        visit::walk_operation(self, pointer, operation)
+    }
+
+    fn visit_components(&mut self, pointer: &str, components: &'a Components) {
+        let names = [
+            ("schemas", keys(&components.schemas)),
+            ("responses", keys(&components.responses)),
+            ("parameters", keys(&components.parameters)),
+            ("examples", keys(&components.examples)),
+            ("requestBodies", keys(&components.request_bodies)),
+            ("headers", keys(&components.headers)),
+            ("securitySchemes", keys(&components.security_schemes)),
+            ("links", keys(&components.links)),
+            ("callbacks", keys(&components.callbacks)),
+        ];
+        for (kind, names) in names {
+            for name in names {
+                let valid = !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || ".-_".contains(c));
+                if !valid {
+                    self.problem(
+                        pointer::join(&pointer::join(pointer, kind), name),
+                        format!(
+                            "component name `{}` does not match `^[a-zA-Z0-9\\.\\-_]+$`",
+                            name
+                        ),
+                    );
+                }
+            }
+        }
+        visit::walk_components(self, pointer, components)
+    }
+}
+
+fn keys<T>(map: &Option<BTreeMap<String, T>>) -> Vec<&str> {
+    map.iter().flatten().map(|(key, _)| key.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_path, OpenApi};
+
+    #[test]
+    fn accepts_sample_specs() {
+        for sample in &[
+            "petstore",
+            "petstore-expanded",
+            "uspto",
+            "link-example",
+            "callback-example",
+            "api-with-examples",
+        ] {
+            match from_path(format!("data/v3.0/{}.yaml", sample)).unwrap() {
+                OpenApi::V3_0(spec) => assert_eq!(spec.problems(), vec![], "{}", sample),
+                _ => panic!("expected a v3.0 spec"),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_broken_rules() {
+        let spec: Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Problems
+  version: 1.0.0
+servers:
+  - url: https://{region}.example.com/{version}
+    variables:
+      region:
+        default: eu
+security:
+  - apiKey: []
+paths:
+  pets/{petId}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: petId
+          in: path
+          schema:
+            type: string
+        - name: ownerId
+          in: path
+          required: true
+          schema:
+            type: string
+      responses: {}
+  /owners/{ownerId}:
+    get:
+      operationId: getPet
+      security:
+        - oauth: [read]
+      responses:
+        '200':
+          description: An owner
+components:
+  schemas:
+    Owner Name:
+      type: string
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      name: key
+      in: header
+"#,
+        )
+        .unwrap();
+        let problems = spec
+            .problems()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                "`/paths/~1owners~1{ownerId}/get`: path parameter `ownerId` is not declared",
+                "`/paths/pets~1{petId}`: the path does not start with `/`",
+                "`/paths/pets~1{petId}/get`: path parameter `petId` is not required",
+                "`/paths/pets~1{petId}/get`: path parameter `ownerId` does not appear in the path",
+                "`/servers/0/url`: server variable `version` is not defined",
+                "`/paths/~1owners~1{ownerId}/get/security/0/oauth`: \
+                 security scheme `oauth` is not defined",
+                "`/paths/pets~1{petId}/get/operationId`: \
+                 operationId `getPet` is already used by `/paths/~1owners~1{ownerId}/get`",
+                "`/paths/pets~1{petId}/get/responses`: the operation has no responses",
+                "`/components/schemas/Owner Name`: \
+                 component name `Owner Name` does not match `^[a-zA-Z0-9\\.\\-_]+$`",
+            ]
+        );
+        assert!(
+            matches!(spec.validate(), Err(Error::InvalidSpec(problems)) if problems.len() == 9)
+        );
+    }
+}
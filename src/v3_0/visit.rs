@@ -0,0 +1,739 @@
+//! Traversal of a whole spec.
+//!
+//! [`Visit`] walks a spec by shared reference and [`VisitMut`] by mutable reference. Each
+//! method is called with the [JSON pointer](crate::pointer) of the node it visits, and by
+//! default walks the node's children with the matching `walk_*` function. Override only the
+//! methods for the nodes of interest, calling the `walk_*` function from an override to keep
+//! descending into the node.
+//!
+//! `$ref`s are not followed, but are passed to [`Visit::visit_reference`] along with the
+//! pointer of the object holding them.
+//!
+//! ```
+//! use openapi::v3_0::{visit::Visit, Schema, Spec};
+//!
+//! struct Properties(Vec<String>);
+//!
+//! impl<'a> Visit<'a> for Properties {
+//!     fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+//!         if pointer.ends_with("/properties/id") {
+//!             self.0.push(pointer.to_string());
+//!         }
+//!         openapi::v3_0::visit::walk_schema(self, pointer, schema);
+//!     }
+//! }
+//!
+//! let spec: Spec = serde_yaml::from_str(
+//!     r#"
+//! openapi: 3.0.0
+//! info: { title: Pets, version: "1" }
+//! paths: {}
+//! components:
+//!   schemas:
+//!     Pet:
+//!       properties:
+//!         id: { type: integer }
+//! "#,
+//! )
+//! .unwrap();
+//! let mut properties = Properties(Vec::new());
+//! properties.visit_spec(&spec);
+//! assert_eq!(properties.0, vec!["/components/schemas/Pet/properties/id"]);
+//! ```
+
+use crate::{
+    pointer,
+    v3_0::{
+        BooleanObjectOrReference, Callback, Components, Example, Header, Link, MediaType,
+        MediaTypeExample, ObjectOrReference, Operation, Parameter, PathItem, RequestBody, Response,
+        Schema, SecurityScheme, Spec,
+    },
+};
+
+/// Traverses a spec by shared reference.
+pub trait Visit<'a> {
+    /// Visits the spec, at the root pointer `""`.
+    fn visit_spec(&mut self, spec: &'a Spec) {
+        walk_spec(self, spec)
+    }
+
+    fn visit_path_item(&mut self, pointer: &str, path_item: &'a PathItem) {
+        walk_path_item(self, pointer, path_item)
+    }
+
+    fn visit_operation(&mut self, pointer: &str, operation: &'a Operation) {
+        walk_operation(self, pointer, operation)
+    }
+
+    fn visit_parameter(&mut self, pointer: &str, parameter: &'a Parameter) {
+        walk_parameter(self, pointer, parameter)
+    }
+
+    fn visit_request_body(&mut self, pointer: &str, request_body: &'a RequestBody) {
+        walk_request_body(self, pointer, request_body)
+    }
+
+    fn visit_response(&mut self, pointer: &str, response: &'a Response) {
+        walk_response(self, pointer, response)
+    }
+
+    fn visit_media_type(&mut self, pointer: &str, media_type: &'a MediaType) {
+        walk_media_type(self, pointer, media_type)
+    }
+
+    fn visit_header(&mut self, pointer: &str, header: &'a Header) {
+        walk_header(self, pointer, header)
+    }
+
+    fn visit_schema(&mut self, pointer: &str, schema: &'a Schema) {
+        walk_schema(self, pointer, schema)
+    }
+
+    fn visit_callback(&mut self, pointer: &str, callback: &'a Callback) {
+        walk_callback(self, pointer, callback)
+    }
+
+    fn visit_components(&mut self, pointer: &str, components: &'a Components) {
+        walk_components(self, pointer, components)
+    }
+
+    fn visit_example(&mut self, _pointer: &str, _example: &'a Example) {}
+
+    fn visit_link(&mut self, _pointer: &str, _link: &'a Link) {}
+
+    fn visit_security_scheme(&mut self, _pointer: &str, _security_scheme: &'a SecurityScheme) {}
+
+    /// Visits a `$ref`, where `pointer` addresses the object holding it.
+    fn visit_reference(&mut self, _pointer: &str, _reference: &'a str) {}
+}
+
+/// Traverses a spec by mutable reference.
+pub trait VisitMut {
+    /// Visits the spec, at the root pointer `""`.
+    fn visit_spec_mut(&mut self, spec: &mut Spec) {
+        walk_spec_mut(self, spec)
+    }
+
+    fn visit_path_item_mut(&mut self, pointer: &str, path_item: &mut PathItem) {
+        walk_path_item_mut(self, pointer, path_item)
+    }
+
+    fn visit_operation_mut(&mut self, pointer: &str, operation: &mut Operation) {
+        walk_operation_mut(self, pointer, operation)
+    }
+
+    fn visit_parameter_mut(&mut self, pointer: &str, parameter: &mut Parameter) {
+        walk_parameter_mut(self, pointer, parameter)
+    }
+
+    fn visit_request_body_mut(&mut self, pointer: &str, request_body: &mut RequestBody) {
+        walk_request_body_mut(self, pointer, request_body)
+    }
+
+    fn visit_response_mut(&mut self, pointer: &str, response: &mut Response) {
+        walk_response_mut(self, pointer, response)
+    }
+
+    fn visit_media_type_mut(&mut self, pointer: &str, media_type: &mut MediaType) {
+        walk_media_type_mut(self, pointer, media_type)
+    }
+
+    fn visit_header_mut(&mut self, pointer: &str, header: &mut Header) {
+        walk_header_mut(self, pointer, header)
+    }
+
+    fn visit_schema_mut(&mut self, pointer: &str, schema: &mut Schema) {
+        walk_schema_mut(self, pointer, schema)
+    }
+
+    fn visit_callback_mut(&mut self, pointer: &str, callback: &mut Callback) {
+        walk_callback_mut(self, pointer, callback)
+    }
+
+    fn visit_components_mut(&mut self, pointer: &str, components: &mut Components) {
+        walk_components_mut(self, pointer, components)
+    }
+
+    fn visit_example_mut(&mut self, _pointer: &str, _example: &mut Example) {}
+
+    fn visit_link_mut(&mut self, _pointer: &str, _link: &mut Link) {}
+
+    fn visit_security_scheme_mut(&mut self, _pointer: &str, _security_scheme: &mut SecurityScheme) {
+    }
+
+    /// Visits a `$ref`, where `pointer` addresses the object holding it. The reference may be
+    /// rewritten in place.
+    fn visit_reference_mut(&mut self, _pointer: &str, _reference: &mut String) {}
+}
+
+pub fn walk_spec<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, spec: &'a Spec) {
+    for (path, path_item) in &spec.paths {
+        visitor.visit_path_item(&pointer::join("/paths", path), path_item);
+    }
+    if let Some(components) = &spec.components {
+        visitor.visit_components("/components", components);
+    }
+}
+
+pub fn walk_path_item<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    path_item: &'a PathItem,
+) {
+    if let Some(reference) = &path_item.reference {
+        visitor.visit_reference(pointer, reference);
+    }
+    for (index, parameter) in path_item.parameters.iter().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "parameters"), &index.to_string());
+        object_or_reference(visitor, &pointer, parameter, V::visit_parameter);
+    }
+    for (method, operation) in path_item.operations() {
+        visitor.visit_operation(&pointer::join(pointer, method.as_str()), operation);
+    }
+}
+
+pub fn walk_operation<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    operation: &'a Operation,
+) {
+    for (index, parameter) in operation.parameters.iter().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "parameters"), &index.to_string());
+        object_or_reference(visitor, &pointer, parameter, V::visit_parameter);
+    }
+    if let Some(request_body) = &operation.request_body {
+        let pointer = pointer::join(pointer, "requestBody");
+        object_or_reference(visitor, &pointer, request_body, V::visit_request_body);
+    }
+    for (status, response) in &operation.responses {
+        let pointer = pointer::join(&pointer::join(pointer, "responses"), status);
+        visitor.visit_response(&pointer, response);
+    }
+    for (name, callback) in operation.callbacks.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "callbacks"), name);
+        visitor.visit_callback(&pointer, callback);
+    }
+}
+
+pub fn walk_parameter<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    parameter: &'a Parameter,
+) {
+    if let Some(schema) = &parameter.schema {
+        visitor.visit_schema(&pointer::join(pointer, "schema"), schema);
+    }
+}
+
+pub fn walk_request_body<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    request_body: &'a RequestBody,
+) {
+    for (media_type_name, media_type) in &request_body.content {
+        let pointer = pointer::join(&pointer::join(pointer, "content"), media_type_name);
+        visitor.visit_media_type(&pointer, media_type);
+    }
+}
+
+pub fn walk_response<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    response: &'a Response,
+) {
+    for (name, header) in response.headers.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "headers"), name);
+        object_or_reference(visitor, &pointer, header, V::visit_header);
+    }
+    for (media_type_name, media_type) in response.content.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "content"), media_type_name);
+        visitor.visit_media_type(&pointer, media_type);
+    }
+    for (name, link) in response.links.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "links"), name);
+        object_or_reference(visitor, &pointer, link, V::visit_link);
+    }
+}
+
+pub fn walk_media_type<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    media_type: &'a MediaType,
+) {
+    if let Some(schema) = &media_type.schema {
+        let pointer = pointer::join(pointer, "schema");
+        object_or_reference(visitor, &pointer, schema, V::visit_schema);
+    }
+    if let Some(MediaTypeExample::Examples { examples }) = &media_type.examples {
+        for (name, example) in examples {
+            let pointer = pointer::join(&pointer::join(pointer, "examples"), name);
+            object_or_reference(visitor, &pointer, example, V::visit_example);
+        }
+    }
+    for (property, encoding) in media_type.encoding.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "encoding"), property);
+        for (name, header) in encoding.headers.iter().flatten() {
+            let pointer = pointer::join(&pointer::join(&pointer, "headers"), name);
+            object_or_reference(visitor, &pointer, header, V::visit_header);
+        }
+    }
+}
+
+pub fn walk_header<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, pointer: &str, header: &'a Header) {
+    if let Some(schema) = &header.schema {
+        visitor.visit_schema(&pointer::join(pointer, "schema"), schema);
+    }
+}
+
+pub fn walk_schema<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, pointer: &str, schema: &'a Schema) {
+    if let Some(reference) = &schema.ref_path {
+        visitor.visit_reference(pointer, reference);
+    }
+    if let Some(items) = &schema.items {
+        visitor.visit_schema(&pointer::join(pointer, "items"), items);
+    }
+    for (name, property) in schema.properties.iter().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "properties"), name);
+        visitor.visit_schema(&pointer, property);
+    }
+    let pointer_to_additional = pointer::join(pointer, "additionalProperties");
+    match &schema.additional_properties {
+        Some(BooleanObjectOrReference::Object(additional)) => {
+            visitor.visit_schema(&pointer_to_additional, additional)
+        }
+        Some(BooleanObjectOrReference::Ref { ref_path }) => {
+            visitor.visit_reference(&pointer_to_additional, ref_path)
+        }
+        Some(BooleanObjectOrReference::Boolean(_)) | None => (),
+    }
+    for (keyword, subschemas) in [
+        ("allOf", &schema.all_of),
+        ("oneOf", &schema.one_of),
+        ("anyOf", &schema.any_of),
+        ("not", &schema.not),
+    ] {
+        for (index, subschema) in subschemas.iter().flatten().enumerate() {
+            let pointer = pointer::join(&pointer::join(pointer, keyword), &index.to_string());
+            object_or_reference(visitor, &pointer, subschema, V::visit_schema);
+        }
+    }
+}
+
+pub fn walk_callback<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    callback: &'a Callback,
+) {
+    for (expression, path_item) in &callback.0 {
+        visitor.visit_path_item(&pointer::join(pointer, expression), path_item);
+    }
+}
+
+pub fn walk_components<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    components: &'a Components,
+) {
+    fn each<'a, V: Visit<'a> + ?Sized, T>(
+        visitor: &mut V,
+        pointer: &str,
+        kind: &str,
+        objects: &'a Option<std::collections::BTreeMap<String, ObjectOrReference<T>>>,
+        visit: fn(&mut V, &str, &'a T),
+    ) {
+        for (name, object) in objects.iter().flatten() {
+            let pointer = pointer::join(&pointer::join(pointer, kind), name);
+            object_or_reference(visitor, &pointer, object, visit);
+        }
+    }
+    each(
+        visitor,
+        pointer,
+        "schemas",
+        &components.schemas,
+        V::visit_schema,
+    );
+    each(
+        visitor,
+        pointer,
+        "responses",
+        &components.responses,
+        V::visit_response,
+    );
+    each(
+        visitor,
+        pointer,
+        "parameters",
+        &components.parameters,
+        V::visit_parameter,
+    );
+    each(
+        visitor,
+        pointer,
+        "examples",
+        &components.examples,
+        V::visit_example,
+    );
+    each(
+        visitor,
+        pointer,
+        "requestBodies",
+        &components.request_bodies,
+        V::visit_request_body,
+    );
+    each(
+        visitor,
+        pointer,
+        "headers",
+        &components.headers,
+        V::visit_header,
+    );
+    each(
+        visitor,
+        pointer,
+        "securitySchemes",
+        &components.security_schemes,
+        V::visit_security_scheme,
+    );
+    each(visitor, pointer, "links", &components.links, V::visit_link);
+    each(
+        visitor,
+        pointer,
+        "callbacks",
+        &components.callbacks,
+        V::visit_callback,
+    );
+}
+
+fn object_or_reference<'a, V: Visit<'a> + ?Sized, T>(
+    visitor: &mut V,
+    pointer: &str,
+    node: &'a ObjectOrReference<T>,
+    visit: fn(&mut V, &str, &'a T),
+) {
+    match node {
+        ObjectOrReference::Object(object) => visit(visitor, pointer, object),
+        ObjectOrReference::Ref { ref_path } => visitor.visit_reference(pointer, ref_path),
+    }
+}
+
+pub fn walk_spec_mut<V: VisitMut + ?Sized>(visitor: &mut V, spec: &mut Spec) {
+    for (path, path_item) in &mut spec.paths {
+        visitor.visit_path_item_mut(&pointer::join("/paths", path), path_item);
+    }
+    if let Some(components) = &mut spec.components {
+        visitor.visit_components_mut("/components", components);
+    }
+}
+
+pub fn walk_path_item_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    path_item: &mut PathItem,
+) {
+    if let Some(reference) = &mut path_item.reference {
+        visitor.visit_reference_mut(pointer, reference);
+    }
+    for (index, parameter) in path_item.parameters.iter_mut().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "parameters"), &index.to_string());
+        object_or_reference_mut(visitor, &pointer, parameter, V::visit_parameter_mut);
+    }
+    for (method, operation) in path_item.operations_mut() {
+        visitor.visit_operation_mut(&pointer::join(pointer, method.as_str()), operation);
+    }
+}
+
+pub fn walk_operation_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    operation: &mut Operation,
+) {
+    for (index, parameter) in operation.parameters.iter_mut().flatten().enumerate() {
+        let pointer = pointer::join(&pointer::join(pointer, "parameters"), &index.to_string());
+        object_or_reference_mut(visitor, &pointer, parameter, V::visit_parameter_mut);
+    }
+    if let Some(request_body) = &mut operation.request_body {
+        let pointer = pointer::join(pointer, "requestBody");
+        object_or_reference_mut(visitor, &pointer, request_body, V::visit_request_body_mut);
+    }
+    for (status, response) in &mut operation.responses {
+        let pointer = pointer::join(&pointer::join(pointer, "responses"), status);
+        visitor.visit_response_mut(&pointer, response);
+    }
+    for (name, callback) in operation.callbacks.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "callbacks"), name);
+        visitor.visit_callback_mut(&pointer, callback);
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    parameter: &mut Parameter,
+) {
+    if let Some(schema) = &mut parameter.schema {
+        visitor.visit_schema_mut(&pointer::join(pointer, "schema"), schema);
+    }
+}
+
+pub fn walk_request_body_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    request_body: &mut RequestBody,
+) {
+    for (media_type_name, media_type) in &mut request_body.content {
+        let pointer = pointer::join(&pointer::join(pointer, "content"), media_type_name);
+        visitor.visit_media_type_mut(&pointer, media_type);
+    }
+}
+
+pub fn walk_response_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    response: &mut Response,
+) {
+    for (name, header) in response.headers.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "headers"), name);
+        object_or_reference_mut(visitor, &pointer, header, V::visit_header_mut);
+    }
+    for (media_type_name, media_type) in response.content.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "content"), media_type_name);
+        visitor.visit_media_type_mut(&pointer, media_type);
+    }
+    for (name, link) in response.links.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "links"), name);
+        object_or_reference_mut(visitor, &pointer, link, V::visit_link_mut);
+    }
+}
+
+pub fn walk_media_type_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    media_type: &mut MediaType,
+) {
+    if let Some(schema) = &mut media_type.schema {
+        let pointer = pointer::join(pointer, "schema");
+        object_or_reference_mut(visitor, &pointer, schema, V::visit_schema_mut);
+    }
+    if let Some(MediaTypeExample::Examples { examples }) = &mut media_type.examples {
+        for (name, example) in examples {
+            let pointer = pointer::join(&pointer::join(pointer, "examples"), name);
+            object_or_reference_mut(visitor, &pointer, example, V::visit_example_mut);
+        }
+    }
+    for (property, encoding) in media_type.encoding.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "encoding"), property);
+        for (name, header) in encoding.headers.iter_mut().flatten() {
+            let pointer = pointer::join(&pointer::join(&pointer, "headers"), name);
+            object_or_reference_mut(visitor, &pointer, header, V::visit_header_mut);
+        }
+    }
+}
+
+pub fn walk_header_mut<V: VisitMut + ?Sized>(visitor: &mut V, pointer: &str, header: &mut Header) {
+    if let Some(schema) = &mut header.schema {
+        visitor.visit_schema_mut(&pointer::join(pointer, "schema"), schema);
+    }
+}
+
+pub fn walk_schema_mut<V: VisitMut + ?Sized>(visitor: &mut V, pointer: &str, schema: &mut Schema) {
+    if let Some(reference) = &mut schema.ref_path {
+        visitor.visit_reference_mut(pointer, reference);
+    }
+    if let Some(items) = &mut schema.items {
+        visitor.visit_schema_mut(&pointer::join(pointer, "items"), items);
+    }
+    for (name, property) in schema.properties.iter_mut().flatten() {
+        let pointer = pointer::join(&pointer::join(pointer, "properties"), name);
+        visitor.visit_schema_mut(&pointer, property);
+    }
+    let pointer_to_additional = pointer::join(pointer, "additionalProperties");
+    match &mut schema.additional_properties {
+        Some(BooleanObjectOrReference::Object(additional)) => {
+            visitor.visit_schema_mut(&pointer_to_additional, additional)
+        }
+        Some(BooleanObjectOrReference::Ref { ref_path }) => {
+            visitor.visit_reference_mut(&pointer_to_additional, ref_path)
+        }
+        Some(BooleanObjectOrReference::Boolean(_)) | None => (),
+    }
+    for (keyword, subschemas) in [
+        ("allOf", &mut schema.all_of),
+        ("oneOf", &mut schema.one_of),
+        ("anyOf", &mut schema.any_of),
+        ("not", &mut schema.not),
+    ] {
+        for (index, subschema) in subschemas.iter_mut().flatten().enumerate() {
+            let pointer = pointer::join(&pointer::join(pointer, keyword), &index.to_string());
+            object_or_reference_mut(visitor, &pointer, subschema, V::visit_schema_mut);
+        }
+    }
+}
+
+pub fn walk_callback_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    callback: &mut Callback,
+) {
+    for (expression, path_item) in &mut callback.0 {
+        visitor.visit_path_item_mut(&pointer::join(pointer, expression), path_item);
+    }
+}
+
+pub fn walk_components_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer: &str,
+    components: &mut Components,
+) {
+    fn each<V: VisitMut + ?Sized, T>(
+        visitor: &mut V,
+        pointer: &str,
+        kind: &str,
+        objects: &mut Option<std::collections::BTreeMap<String, ObjectOrReference<T>>>,
+        visit: fn(&mut V, &str, &mut T),
+    ) {
+        for (name, object) in objects.iter_mut().flatten() {
+            let pointer = pointer::join(&pointer::join(pointer, kind), name);
+            object_or_reference_mut(visitor, &pointer, object, visit);
+        }
+    }
+    each(
+        visitor,
+        pointer,
+        "schemas",
+        &mut components.schemas,
+        V::visit_schema_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "responses",
+        &mut components.responses,
+        V::visit_response_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "parameters",
+        &mut components.parameters,
+        V::visit_parameter_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "examples",
+        &mut components.examples,
+        V::visit_example_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "requestBodies",
+        &mut components.request_bodies,
+        V::visit_request_body_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "headers",
+        &mut components.headers,
+        V::visit_header_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "securitySchemes",
+        &mut components.security_schemes,
+        V::visit_security_scheme_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "links",
+        &mut components.links,
+        V::visit_link_mut,
+    );
+    each(
+        visitor,
+        pointer,
+        "callbacks",
+        &mut components.callbacks,
+        V::visit_callback_mut,
+    );
+}
+
+fn object_or_reference_mut<V: VisitMut + ?Sized, T>(
+    visitor: &mut V,
+    pointer: &str,
+    node: &mut ObjectOrReference<T>,
+    visit: fn(&mut V, &str, &mut T),
+) {
+    match node {
+        ObjectOrReference::Object(object) => visit(visitor, pointer, object),
+        ObjectOrReference::Ref { ref_path } => visitor.visit_reference_mut(pointer, ref_path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, fs::File};
+
+    fn petstore() -> Spec {
+        serde_yaml::from_reader(File::open("data/v3.0/petstore.yaml").unwrap()).unwrap()
+    }
+
+    #[derive(Default)]
+    struct References<'a>(BTreeMap<String, &'a str>);
+
+    impl<'a> Visit<'a> for References<'a> {
+        fn visit_reference(&mut self, pointer: &str, reference: &'a str) {
+            self.0.insert(pointer.to_string(), reference);
+        }
+    }
+
+    #[test]
+    fn visits_references_with_pointers() {
+        let spec = petstore();
+        let mut references = References::default();
+        references.visit_spec(&spec);
+        assert_eq!(
+            references.0["/paths/~1pets/get/responses/200/content/application~1json/schema"],
+            "#/components/schemas/Pets"
+        );
+        assert_eq!(
+            references.0["/components/schemas/Pets/items"],
+            "#/components/schemas/Pet"
+        );
+    }
+
+    struct Prefix;
+
+    impl VisitMut for Prefix {
+        fn visit_reference_mut(&mut self, _pointer: &str, reference: &mut String) {
+            *reference = reference.replace("/schemas/", "/schemas/Pets_");
+        }
+
+        fn visit_operation_mut(&mut self, pointer: &str, operation: &mut Operation) {
+            operation.operation_id = Some(pointer.to_string());
+            walk_operation_mut(self, pointer, operation);
+        }
+    }
+
+    #[test]
+    fn rewrites_nodes() {
+        let mut spec = petstore();
+        Prefix.visit_spec_mut(&mut spec);
+        let operation = spec.paths["/pets/{petId}"].get.as_ref().unwrap();
+        assert_eq!(
+            operation.operation_id.as_deref(),
+            Some("/paths/~1pets~1{petId}/get")
+        );
+        let mut references = References::default();
+        references.visit_spec(&spec);
+        assert_eq!(
+            references.0["/components/schemas/Pets/items"],
+            "#/components/schemas/Pets_Pet"
+        );
+    }
+}
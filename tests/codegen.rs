@@ -0,0 +1,246 @@
+//! Compiles the code generated for `tests/codegen/spec.yaml`, which is checked in next to
+//! it, and round-trips values through it.
+//!
+//! Run with `REGENERATE=1` to update the generated code after changing the generators.
+
+use openapi::{codegen, v3_0::Spec};
+use serde_json::json;
+use std::{env, fs};
+
+fn spec() -> Spec {
+    serde_yaml::from_str(include_str!("codegen/spec.yaml")).unwrap()
+}
+
+/// Checks that the checked-in code at `path` is what the generator produces now.
+fn check(path: &str, source: String) {
+    let path = format!("{}/tests/codegen/{}", env!("CARGO_MANIFEST_DIR"), path);
+    if env::var_os("REGENERATE").is_some() {
+        fs::write(&path, source).unwrap();
+    } else {
+        assert!(
+            fs::read_to_string(&path).unwrap() == source,
+            "{} is out of date, run the tests with REGENERATE=1",
+            path
+        );
+    }
+}
+
+#[allow(dead_code)]
+mod models {
+    include!("codegen/models.rs");
+}
+
+#[test]
+fn generated_models_are_up_to_date() {
+    check("models.rs", codegen::rust_models(&spec()).unwrap());
+}
+
+#[test]
+fn round_trips_discriminated_unions() {
+    use models::{Cat, Dog, Pet};
+
+    let cat = json!({"petType": "cat", "name": "Tom", "owner": null});
+    let pet: Pet = serde_json::from_value(cat.clone()).unwrap();
+    assert_eq!(
+        pet,
+        Pet::Cat(Cat {
+            pet_type: "cat".into(),
+            name: "Tom".into(),
+            owner: None,
+        })
+    );
+    assert_eq!(serde_json::to_value(&pet).unwrap(), cat);
+
+    // a dog is named by its component, as the mapping does not name it
+    let dog = json!({"petType": "Dog", "bark": true});
+    let pet: Pet = serde_json::from_value(dog.clone()).unwrap();
+    assert_eq!(
+        pet,
+        Pet::Dog(Dog {
+            pet_type: "Dog".into(),
+            bark: true,
+        })
+    );
+    assert_eq!(serde_json::to_value(&pet).unwrap(), dog);
+
+    assert!(serde_json::from_value::<Pet>(json!({"petType": "bird"})).is_err());
+    assert!(serde_json::from_value::<Pet>(json!({"bark": true})).is_err());
+}
+
+#[test]
+fn serializes_required_nullable_fields() {
+    let cat = models::Cat {
+        pet_type: "cat".into(),
+        name: "Tom".into(),
+        owner: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&cat).unwrap(),
+        json!({"petType": "cat", "name": "Tom", "owner": null})
+    );
+}
+
+#[allow(dead_code)]
+mod client {
+    include!("codegen/client.rs");
+
+    #[test]
+    fn encodes_parameters_in_their_style() {
+        let ids = [1, 2, 3];
+        assert_eq!(
+            encode_parameter(&ids, "ids", "path", "label", false, false).unwrap(),
+            ".1,2,3"
+        );
+        assert_eq!(
+            encode_parameter(&ids, "ids", "path", "label", true, false).unwrap(),
+            ".1.2.3"
+        );
+        let tags = ["a b", "c"];
+        assert_eq!(
+            encode_parameter(&tags, "tags", "query", "form", false, false).unwrap(),
+            "tags=a%20b,c"
+        );
+        assert_eq!(
+            encode_parameter(&tags, "tags", "query", "form", true, false).unwrap(),
+            "tags=a%20b&tags=c"
+        );
+        let filter = GetPetsFilter {
+            color: Some("red".into()),
+        };
+        assert_eq!(
+            encode_parameter(&filter, "filter", "query", "deepObject", false, false).unwrap(),
+            "filter[color]=red"
+        );
+        assert_eq!(
+            encode_parameter(&tags, "X-Trace", "header", "simple", false, false).unwrap(),
+            "a b,c"
+        );
+        assert_eq!(
+            encode_parameter("abc", "session", "cookie", "form", true, false).unwrap(),
+            "session=abc"
+        );
+        assert!(encode_parameter(&[ids], "ids", "path", "simple", false, false).is_err());
+    }
+
+    #[test]
+    fn decodes_form_responses() {
+        let dog: Dog = from_form(b"petType=Dog&bark=true").unwrap();
+        assert_eq!(
+            dog,
+            Dog {
+                pet_type: "Dog".into(),
+                bark: true,
+            }
+        );
+    }
+}
+
+#[test]
+fn generated_client_is_up_to_date() {
+    check("client.rs", codegen::rust_client(&spec()).unwrap());
+}
+
+#[allow(dead_code)]
+mod server {
+    include!("codegen/server.rs");
+
+    use std::{
+        future::{ready, Future},
+        pin::pin,
+        sync::Mutex,
+        task::{Context, Poll, Waker},
+    };
+
+    /// Records the requests it receives.
+    #[derive(Default)]
+    struct Recorder {
+        get_pets: Mutex<Option<GetPetsRequest>>,
+    }
+
+    impl Api for Recorder {
+        fn create_pet(
+            &self,
+            request: CreatePetRequest,
+        ) -> impl Future<Output = CreatePetResponse> + Send {
+            ready(CreatePetResponse::Created(request.body))
+        }
+
+        fn get_pets(
+            &self,
+            request: GetPetsRequest,
+        ) -> impl Future<Output = GetPetsResponse> + Send {
+            *self.get_pets.lock().unwrap() = Some(request);
+            ready(GetPetsResponse::Ok(Vec::new()))
+        }
+
+        fn search(&self, _: SearchRequest) -> impl Future<Output = SearchResponse> + Send {
+            ready(SearchResponse::NoContent)
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_parameters_in_their_style() {
+        let api = Recorder::default();
+        let request = http::Request::get(
+            "/pets/.1,2,3?tags=a%20b,c&colors=red&colors=blue&filter[color]=red",
+        )
+        .header("X-Trace", "x,y")
+        .header("Cookie", "theme=dark; session=abc")
+        .body(Vec::new())
+        .unwrap();
+        let response = block_on(dispatch(&api, request));
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            api.get_pets.lock().unwrap().take(),
+            Some(GetPetsRequest {
+                ids: vec![1, 2, 3],
+                tags: Some(vec!["a b".into(), "c".into()]),
+                colors: Some(vec!["red".into(), "blue".into()]),
+                filter: Some(GetPetsFilter {
+                    color: Some("red".into()),
+                }),
+                x_trace: Some(vec!["x".into(), "y".into()]),
+                session: Some("abc".into()),
+            })
+        );
+
+        let request = http::Request::get("/pets/1,2").body(Vec::new()).unwrap();
+        assert_eq!(block_on(dispatch(&api, request)).status(), 400);
+    }
+
+    #[test]
+    fn serializes_form_responses() {
+        let request = http::Request::post("/pets")
+            .body(b"petType=Dog&bark=true".to_vec())
+            .unwrap();
+        let response = block_on(dispatch(&Recorder::default(), request));
+        assert_eq!(response.status(), 201);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        let dog: Dog = from_form(response.body()).unwrap();
+        assert_eq!(
+            dog,
+            Dog {
+                pet_type: "Dog".into(),
+                bark: true,
+            }
+        );
+    }
+}
+
+#[test]
+fn generated_server_is_up_to_date() {
+    check("server.rs", codegen::rust_server(&spec()).unwrap());
+}
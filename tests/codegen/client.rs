@@ -0,0 +1,423 @@
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    pub owner: Option<String>,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub bark: bool,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Pet {
+    Cat(Cat),
+    Dog(Dog),
+}
+
+impl<'de> serde::Deserialize<'de> for Pet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        match value.get("petType").and_then(serde_json::Value::as_str) {
+            Some("cat") => serde_json::from_value(value).map(Pet::Cat),
+            Some("Dog") => serde_json::from_value(value).map(Pet::Dog),
+            Some(tag) => return Err(D::Error::unknown_variant(tag, &["cat", "Dog"])),
+            None => return Err(D::Error::missing_field("petType")),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetPetsFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// The responses of `create_pet`.
+#[derive(Debug)]
+pub enum CreatePetResponse {
+    /// The pet
+    Created(Dog),
+    /// A response the spec does not describe.
+    Unexpected(reqwest::Response),
+}
+
+/// The responses of `get_pets`.
+#[derive(Debug)]
+pub enum GetPetsResponse {
+    /// The pets
+    Ok(Vec<Pet>),
+    /// A response the spec does not describe.
+    Unexpected(reqwest::Response),
+}
+
+/// The responses of `search`.
+#[derive(Debug)]
+pub enum SearchResponse {
+    /// Searched
+    NoContent,
+    /// A response the spec does not describe.
+    Unexpected(reqwest::Response),
+}
+
+/// An error sending a request or reading its response.
+#[derive(Debug)]
+pub enum ClientError {
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Http(error) => write!(f, "HTTP error: {}", error),
+            ClientError::Json(error) => write!(f, "JSON error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Http(error) => Some(error),
+            ClientError::Json(error) => Some(error),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Json(error)
+    }
+}
+
+/// A client for the Pets API.
+#[derive(Clone, Debug)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl Client {
+    /// A client for the API at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// A client for the API at `base_url`, sending requests with `http`.
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Client { base_url, http }
+    }
+
+    pub async fn create_pet(
+        &self,
+        body: &Dog,
+    ) -> Result<CreatePetResponse, ClientError> {
+        let url = format!("{}/pets", self.base_url);
+        #[allow(unused_mut)]
+        let mut request = self.http.request(reqwest::Method::POST, url);
+        request = request.form(body);
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        Ok(match status {
+            201 => CreatePetResponse::Created(from_form(&response.bytes().await?)?),
+            _ => CreatePetResponse::Unexpected(response),
+        })
+    }
+
+    pub async fn get_pets(
+        &self,
+        ids: &[i64],
+        tags: Option<&[String]>,
+        colors: Option<&[String]>,
+        filter: Option<&GetPetsFilter>,
+        x_trace: Option<&[String]>,
+        session: Option<&str>,
+    ) -> Result<GetPetsResponse, ClientError> {
+        let mut url = format!("{}/pets/{}", self.base_url, encode_parameter(&ids, "ids", "path", "label", false, false)?);
+        let mut query = Vec::new();
+        if let Some(tags) = tags {
+            query.push(encode_parameter(&tags, "tags", "query", "form", false, false)?);
+        }
+        if let Some(colors) = colors {
+            query.push(encode_parameter(&colors, "colors", "query", "pipeDelimited", true, false)?);
+        }
+        if let Some(filter) = filter {
+            query.push(encode_parameter(&filter, "filter", "query", "deepObject", false, false)?);
+        }
+        query.retain(|pair: &String| !pair.is_empty());
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        #[allow(unused_mut)]
+        let mut request = self.http.request(reqwest::Method::GET, url);
+        let mut cookies = Vec::new();
+        if let Some(x_trace) = x_trace {
+            request = request.header("X-Trace", encode_parameter(&x_trace, "X-Trace", "header", "simple", false, false)?);
+        }
+        if let Some(session) = session {
+            cookies.push(encode_parameter(&session, "session", "cookie", "form", true, false)?);
+        }
+        if !cookies.is_empty() {
+            request = request.header("Cookie", cookies.join("; "));
+        }
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        Ok(match status {
+            200 => GetPetsResponse::Ok(response.json().await?),
+            _ => GetPetsResponse::Unexpected(response),
+        })
+    }
+
+    pub async fn search(
+        &self,
+        query2: &str,
+        url2: Option<&str>,
+        status2: Option<i64>,
+        cookies2: Option<&str>,
+    ) -> Result<SearchResponse, ClientError> {
+        let mut url = format!("{}/search", self.base_url);
+        let mut query = Vec::new();
+        query.push(encode_parameter(&query2, "query", "query", "form", true, false)?);
+        if let Some(url2) = url2 {
+            query.push(encode_parameter(&url2, "url", "query", "form", true, false)?);
+        }
+        query.retain(|pair: &String| !pair.is_empty());
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        #[allow(unused_mut)]
+        let mut request = self.http.request(reqwest::Method::GET, url);
+        let mut cookies = Vec::new();
+        if let Some(status2) = status2 {
+            request = request.header("status", encode_parameter(&status2, "status", "header", "simple", false, false)?);
+        }
+        if let Some(cookies2) = cookies2 {
+            cookies.push(encode_parameter(&cookies2, "cookies", "cookie", "form", true, false)?);
+        }
+        if !cookies.is_empty() {
+            request = request.header("Cookie", cookies.join("; "));
+        }
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        Ok(match status {
+            204 => SearchResponse::NoContent,
+            _ => SearchResponse::Unexpected(response),
+        })
+    }
+}
+
+/// Serializes a parameter value in its `style`, as the text substituted for its template
+/// expression in a path, a query string fragment, a header value or a cookie.
+#[allow(dead_code)]
+fn encode_parameter<T: serde::Serialize + ?Sized>(
+    value: &T,
+    name: &str,
+    location: &str,
+    style: &str,
+    explode: bool,
+    allow_reserved: bool,
+) -> Result<String, serde_json::Error> {
+    let invalid = |reason: &str| {
+        <serde_json::Error as serde::ser::Error>::custom(format!(
+            "invalid value for parameter `{}`: {}",
+            name, reason
+        ))
+    };
+    let encode = |text: &str| match location {
+        "query" | "path" => percent_encode(text, location == "query" && allow_reserved),
+        _ => text.to_string(),
+    };
+    let scalar = |value: &serde_json::Value| match value {
+        serde_json::Value::Null => Ok(String::new()),
+        serde_json::Value::String(value) => Ok(encode(value)),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            Err(invalid("nested arrays and objects can not be serialized"))
+        }
+        value => Ok(encode(&value.to_string())),
+    };
+    let name = encode(name);
+    let pairs = if location == "cookie" { "; " } else { "&" };
+    let style = match style {
+        "spaceDelimited" | "pipeDelimited" if explode => "form",
+        style => style,
+    };
+    let pair = |(key, value): &(String, String)| format!("{}={}", key, value);
+    let flatten = |entries: &[(String, String)], delimiter: &str| {
+        entries
+            .iter()
+            .flat_map(|(key, value)| [key.as_str(), value.as_str()])
+            .collect::<Vec<_>>()
+            .join(delimiter)
+    };
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::Array(items) => {
+            let items = items.iter().map(scalar).collect::<Result<Vec<_>, _>>()?;
+            match (style, explode) {
+                ("matrix", true) => items
+                    .iter()
+                    .map(|item| format!(";{}={}", name, item))
+                    .collect(),
+                ("matrix", false) => format!(";{}={}", name, items.join(",")),
+                ("label", true) => format!(".{}", items.join(".")),
+                ("label", false) => format!(".{}", items.join(",")),
+                ("form", true) => items
+                    .iter()
+                    .map(|item| format!("{}={}", name, item))
+                    .collect::<Vec<_>>()
+                    .join(pairs),
+                ("form", false) => format!("{}={}", name, items.join(",")),
+                ("simple", _) => items.join(","),
+                ("spaceDelimited", _) => format!("{}={}", name, items.join("%20")),
+                ("pipeDelimited", _) => format!("{}={}", name, items.join("|")),
+                _ => return Err(invalid("`deepObject` style only applies to objects")),
+            }
+        }
+        serde_json::Value::Object(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| Ok((encode(key), scalar(value)?)))
+                .collect::<Result<Vec<_>, _>>()?;
+            match (style, explode) {
+                ("matrix", true) => entries
+                    .iter()
+                    .map(|entry| format!(";{}", pair(entry)))
+                    .collect(),
+                ("matrix", false) => format!(";{}={}", name, flatten(&entries, ",")),
+                ("label", true) => entries
+                    .iter()
+                    .map(|entry| format!(".{}", pair(entry)))
+                    .collect(),
+                ("label", false) => format!(".{}", flatten(&entries, ",")),
+                ("form", true) => entries.iter().map(pair).collect::<Vec<_>>().join(pairs),
+                ("form", false) => format!("{}={}", name, flatten(&entries, ",")),
+                ("simple", true) => entries.iter().map(pair).collect::<Vec<_>>().join(","),
+                ("simple", false) => flatten(&entries, ","),
+                ("spaceDelimited", _) => format!("{}={}", name, flatten(&entries, "%20")),
+                ("pipeDelimited", _) => format!("{}={}", name, flatten(&entries, "|")),
+                _ => entries
+                    .iter()
+                    .map(|(key, value)| format!("{}[{}]={}", name, key, value))
+                    .collect::<Vec<_>>()
+                    .join("&"),
+            }
+        }
+        value => {
+            let null = value.is_null();
+            let value = scalar(&value)?;
+            match style {
+                "matrix" if null => format!(";{}", name),
+                "matrix" => format!(";{}={}", name, value),
+                "label" => format!(".{}", value),
+                "form" => format!("{}={}", name, value),
+                "simple" => value,
+                "deepObject" => return Err(invalid("`deepObject` style only applies to objects")),
+                style => {
+                    return Err(invalid(&format!(
+                        "`{}` style only applies to arrays and objects",
+                        style
+                    )))
+                }
+            }
+        }
+    })
+}
+
+/// Percent-encodes everything but unreserved characters, and with `reserved` reserved
+/// characters too.
+#[allow(dead_code)]
+fn percent_encode(text: &str, reserved: bool) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric()
+            || b"-._~".contains(&byte)
+            || (reserved && b":/?#[]@!$&'()*+,;=".contains(&byte))
+        {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Percent-decodes `input`, and with `plus` also decodes `+` as a space.
+#[allow(dead_code)]
+fn decode(input: &[u8], plus: bool) -> String {
+    let mut bytes = Vec::new();
+    let mut index = 0;
+    while index < input.len() {
+        let escaped = input
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (input[index], escaped) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                index += 3;
+                continue;
+            }
+            (b'+', _) if plus => bytes.push(b' '),
+            (byte, _) => bytes.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The decoded pairs of a query string or form.
+#[allow(dead_code)]
+fn form_pairs(input: &[u8]) -> Vec<(String, String)> {
+    input
+        .split(|byte| *byte == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut split = pair.splitn(2, |byte| *byte == b'=');
+            let key = decode(split.next().unwrap_or_default(), true);
+            let value = decode(split.next().unwrap_or_default(), true);
+            (key, value)
+        })
+        .collect()
+}
+
+/// Deserializes strings as such if possible, and otherwise as JSON.
+#[allow(dead_code)]
+fn from_strings<T: serde::de::DeserializeOwned>(
+    value: impl Fn(&dyn Fn(&str) -> serde_json::Value) -> serde_json::Value,
+) -> Result<T, serde_json::Error> {
+    serde_json::from_value(value(&|item| serde_json::Value::String(item.into()))).or_else(|_| {
+        serde_json::from_value(value(&|item| {
+            serde_json::from_str(item).unwrap_or_else(|_| serde_json::Value::String(item.into()))
+        }))
+    })
+}
+
+/// Deserializes the pairs of a form as the properties of an object.
+#[allow(dead_code)]
+fn from_form<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, serde_json::Error> {
+    let pairs = form_pairs(body);
+    from_strings(|item| {
+        serde_json::Value::Object(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), item(value)))
+                .collect(),
+        )
+    })
+}
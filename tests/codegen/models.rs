@@ -0,0 +1,35 @@
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    pub owner: Option<String>,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub bark: bool,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Pet {
+    Cat(Cat),
+    Dog(Dog),
+}
+
+impl<'de> serde::Deserialize<'de> for Pet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        match value.get("petType").and_then(serde_json::Value::as_str) {
+            Some("cat") => serde_json::from_value(value).map(Pet::Cat),
+            Some("Dog") => serde_json::from_value(value).map(Pet::Dog),
+            Some(tag) => return Err(D::Error::unknown_variant(tag, &["cat", "Dog"])),
+            None => return Err(D::Error::missing_field("petType")),
+        }
+        .map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,669 @@
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    pub owner: Option<String>,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub bark: bool,
+    #[serde(rename = "petType")]
+    pub pet_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Pet {
+    Cat(Cat),
+    Dog(Dog),
+}
+
+impl<'de> serde::Deserialize<'de> for Pet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        match value.get("petType").and_then(serde_json::Value::as_str) {
+            Some("cat") => serde_json::from_value(value).map(Pet::Cat),
+            Some("Dog") => serde_json::from_value(value).map(Pet::Dog),
+            Some(tag) => return Err(D::Error::unknown_variant(tag, &["cat", "Dog"])),
+            None => return Err(D::Error::missing_field("petType")),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetPetsFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// The parameters and body of `create_pet`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatePetRequest {
+    pub body: Dog,
+}
+
+impl CreatePetRequest {
+    #[allow(unused_variables)]
+    fn parse(
+        parts: &http::request::Parts,
+        path_values: &[String],
+        body: &[u8],
+    ) -> Result<Self, String> {
+        Ok(CreatePetRequest {
+            body: form_body(body)?,
+        })
+    }
+}
+
+/// The responses of `create_pet`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreatePetResponse {
+    /// The pet
+    Created(Dog),
+}
+
+impl From<CreatePetResponse> for http::Response<Vec<u8>> {
+    fn from(response: CreatePetResponse) -> Self {
+        match response {
+            CreatePetResponse::Created(body) => form_response(201, "application/x-www-form-urlencoded", &body),
+        }
+    }
+}
+
+/// The parameters and body of `get_pets`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GetPetsRequest {
+    pub ids: Vec<i64>,
+    pub tags: Option<Vec<String>>,
+    pub colors: Option<Vec<String>>,
+    pub filter: Option<GetPetsFilter>,
+    pub x_trace: Option<Vec<String>>,
+    pub session: Option<String>,
+}
+
+impl GetPetsRequest {
+    #[allow(unused_variables)]
+    fn parse(
+        parts: &http::request::Parts,
+        path_values: &[String],
+        body: &[u8],
+    ) -> Result<Self, String> {
+        Ok(GetPetsRequest {
+            ids: required(
+                "ids",
+                parameter(Some(&path_values[0]), "ids", "path", "label", false, "array"),
+            )?,
+            tags: optional(
+                "tags",
+                parameter(Some(parts.uri.query().unwrap_or_default()), "tags", "query", "form", false, "array"),
+            )?,
+            colors: optional(
+                "colors",
+                parameter(Some(parts.uri.query().unwrap_or_default()), "colors", "query", "pipeDelimited", true, "array"),
+            )?,
+            filter: optional(
+                "filter",
+                parameter(Some(parts.uri.query().unwrap_or_default()), "filter", "query", "deepObject", false, "object"),
+            )?,
+            x_trace: optional(
+                "X-Trace",
+                parameter(header(parts, "X-Trace", ",").as_deref(), "X-Trace", "header", "simple", false, "array"),
+            )?,
+            session: optional(
+                "session",
+                parameter(header(parts, "cookie", "; ").as_deref(), "session", "cookie", "form", true, "primitive"),
+            )?,
+        })
+    }
+}
+
+/// The responses of `get_pets`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GetPetsResponse {
+    /// The pets
+    Ok(Vec<Pet>),
+}
+
+impl From<GetPetsResponse> for http::Response<Vec<u8>> {
+    fn from(response: GetPetsResponse) -> Self {
+        match response {
+            GetPetsResponse::Ok(body) => json_response(200, "application/json", &body),
+        }
+    }
+}
+
+/// The parameters and body of `search`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchRequest {
+    pub query2: String,
+    pub url2: Option<String>,
+    pub status2: Option<i64>,
+    pub cookies2: Option<String>,
+}
+
+impl SearchRequest {
+    #[allow(unused_variables)]
+    fn parse(
+        parts: &http::request::Parts,
+        path_values: &[String],
+        body: &[u8],
+    ) -> Result<Self, String> {
+        Ok(SearchRequest {
+            query2: required(
+                "query",
+                parameter(Some(parts.uri.query().unwrap_or_default()), "query", "query", "form", true, "primitive"),
+            )?,
+            url2: optional(
+                "url",
+                parameter(Some(parts.uri.query().unwrap_or_default()), "url", "query", "form", true, "primitive"),
+            )?,
+            status2: optional(
+                "status",
+                parameter(header(parts, "status", ",").as_deref(), "status", "header", "simple", false, "primitive"),
+            )?,
+            cookies2: optional(
+                "cookies",
+                parameter(header(parts, "cookie", "; ").as_deref(), "cookies", "cookie", "form", true, "primitive"),
+            )?,
+        })
+    }
+}
+
+/// The responses of `search`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchResponse {
+    /// Searched
+    NoContent,
+}
+
+impl From<SearchResponse> for http::Response<Vec<u8>> {
+    fn from(response: SearchResponse) -> Self {
+        match response {
+            SearchResponse::NoContent => respond(204, None, Vec::new()),
+        }
+    }
+}
+
+/// The operations of the Pets API.
+pub trait Api {
+    fn create_pet(
+        &self,
+        request: CreatePetRequest,
+    ) -> impl std::future::Future<Output = CreatePetResponse> + Send;
+
+    fn get_pets(
+        &self,
+        request: GetPetsRequest,
+    ) -> impl std::future::Future<Output = GetPetsResponse> + Send;
+
+    fn search(
+        &self,
+        request: SearchRequest,
+    ) -> impl std::future::Future<Output = SearchResponse> + Send;
+}
+
+/// Routes a request to the operation of `api` whose path and method it matches.
+///
+/// Responds `404 Not Found` if no path matches, `405 Method Not Allowed` if no method
+/// does, and `400 Bad Request` if the parameters or body are invalid.
+pub async fn dispatch<A: Api>(
+    api: &A,
+    request: http::Request<Vec<u8>>,
+) -> http::Response<Vec<u8>> {
+    let (parts, body) = request.into_parts();
+    #[allow(unused_mut)]
+    let mut path_matched = false;
+    if let Some(path_values) = match_template("/pets", parts.uri.path()) {
+        path_matched = true;
+        if parts.method == http::Method::POST {
+            return match CreatePetRequest::parse(&parts, &path_values, &body) {
+                Ok(request) => api.create_pet(request).await.into(),
+                Err(message) => respond(400, Some("text/plain"), message.into_bytes()),
+            };
+        }
+    }
+    if let Some(path_values) = match_template("/search", parts.uri.path()) {
+        path_matched = true;
+        if parts.method == http::Method::GET {
+            return match SearchRequest::parse(&parts, &path_values, &body) {
+                Ok(request) => api.search(request).await.into(),
+                Err(message) => respond(400, Some("text/plain"), message.into_bytes()),
+            };
+        }
+    }
+    if let Some(path_values) = match_template("/pets/{ids}", parts.uri.path()) {
+        path_matched = true;
+        if parts.method == http::Method::GET {
+            return match GetPetsRequest::parse(&parts, &path_values, &body) {
+                Ok(request) => api.get_pets(request).await.into(),
+                Err(message) => respond(400, Some("text/plain"), message.into_bytes()),
+            };
+        }
+    }
+    if path_matched {
+        respond(405, None, Vec::new())
+    } else {
+        respond(404, None, Vec::new())
+    }
+}
+
+/// The still encoded values of the variables of `template` in `path`, if it matches.
+#[allow(dead_code)]
+fn match_template(template: &str, path: &str) -> Option<Vec<String>> {
+    let templates = template.split('/').collect::<Vec<_>>();
+    let segments = path.split('/').collect::<Vec<_>>();
+    if templates.len() != segments.len() {
+        return None;
+    }
+    let mut values = Vec::new();
+    for (template, segment) in templates.into_iter().zip(segments) {
+        let mut pieces = template.split('{');
+        let mut rest = segment.strip_prefix(pieces.next().unwrap_or_default())?;
+        let pieces = pieces.collect::<Vec<_>>();
+        for (index, piece) in pieces.iter().enumerate() {
+            let (_, literal) = piece.split_once('}')?;
+            let end = match (literal.is_empty(), index + 1 == pieces.len()) {
+                (true, true) => rest.len(),
+                (true, false) => return None,
+                (false, _) => rest.find(literal)?,
+            };
+            if end == 0 {
+                return None;
+            }
+            values.push(rest[..end].to_string());
+            rest = &rest[end + literal.len()..];
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(values)
+}
+
+/// The values of a header, joined by `separator`, or `None` if it is missing.
+#[allow(dead_code)]
+fn header(parts: &http::request::Parts, name: &str, separator: &str) -> Option<String> {
+    let values = parts
+        .headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(separator))
+    }
+}
+
+/// The strings the value of a parameter is made of.
+#[allow(dead_code)]
+enum Strings {
+    One(String),
+    Many(Vec<String>),
+    Map(Vec<(String, String)>),
+}
+
+/// Splits the value of a parameter serialized in its `style` into its strings. `raw` is the
+/// text matched in the path, the query string, the header's values or the `Cookie` header,
+/// and `kind` is `array`, `object` or `primitive`. `None` if the parameter is missing.
+#[allow(dead_code)]
+fn parameter(
+    raw: Option<&str>,
+    name: &str,
+    location: &str,
+    style: &str,
+    explode: bool,
+    kind: &str,
+) -> Result<Option<Strings>, String> {
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
+    let invalid = |reason: String| format!("invalid parameter `{}`: {}", name, reason);
+    let text = |text: &str| match location {
+        "path" | "query" => decode(text.as_bytes(), location == "query"),
+        _ => text.to_string(),
+    };
+    let entry = |entry: &str| {
+        let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+        (text(key), text(value))
+    };
+    let split = |raw: &str, delimiter: &str| -> Vec<String> {
+        if raw.is_empty() {
+            return Vec::new();
+        }
+        // delimiters may also arrive percent-encoded
+        let delimiters = match delimiter {
+            " " => vec![" ", "+", "%20"],
+            "|" => vec!["|", "%7C", "%7c"],
+            delimiter => vec![delimiter],
+        };
+        delimiters
+            .into_iter()
+            .fold(vec![raw.to_string()], |values, delimiter| {
+                values
+                    .iter()
+                    .flat_map(|value| value.split(delimiter))
+                    .map(String::from)
+                    .collect()
+            })
+    };
+    let delimited = |raw: &str, delimiter: &str, explode: bool| -> Result<Strings, String> {
+        Ok(match kind {
+            "array" => Strings::Many(split(raw, delimiter).iter().map(|item| text(item)).collect()),
+            "object" if explode => {
+                Strings::Map(split(raw, delimiter).iter().map(|item| entry(item)).collect())
+            }
+            "object" => {
+                let items = split(raw, delimiter)
+                    .iter()
+                    .map(|item| text(item))
+                    .collect::<Vec<_>>();
+                if items.len() % 2 != 0 {
+                    return Err(invalid(format!(
+                        "expected `{}` to hold pairs of keys and values",
+                        raw
+                    )));
+                }
+                Strings::Map(
+                    items
+                        .chunks(2)
+                        .map(|pair| (pair[0].clone(), pair[1].clone()))
+                        .collect(),
+                )
+            }
+            _ => Strings::One(text(raw)),
+        })
+    };
+    let pairs = || {
+        raw.split(if location == "cookie" { ';' } else { '&' })
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .collect::<Vec<_>>()
+    };
+    // exploded delimited values are written as repeated pairs, as with `form`
+    let style = match style {
+        "spaceDelimited" | "pipeDelimited" if explode => "form",
+        style => style,
+    };
+    match style {
+        "simple" => delimited(raw, ",", explode).map(Some),
+        "label" => match raw.strip_prefix('.') {
+            Some(raw) => delimited(raw, if explode { "." } else { "," }, explode).map(Some),
+            None => Err(invalid(format!("expected `{}` to start with `.`", raw))),
+        },
+        "matrix" => {
+            let raw = raw
+                .strip_prefix(';')
+                .ok_or_else(|| invalid(format!("expected `{}` to start with `;`", raw)))?;
+            if explode && kind != "primitive" {
+                let entries = raw.split(';').map(entry).collect::<Vec<_>>();
+                return Ok(Some(if kind == "array" {
+                    Strings::Many(
+                        entries
+                            .into_iter()
+                            .filter(|(key, _)| key == name)
+                            .map(|(_, value)| value)
+                            .collect(),
+                    )
+                } else {
+                    Strings::Map(entries)
+                }));
+            }
+            let (key, value) = raw.split_once('=').unwrap_or((raw, ""));
+            if text(key) != name {
+                return Ok(None);
+            }
+            delimited(value, ",", false).map(Some)
+        }
+        "form" if explode => {
+            let pairs = pairs()
+                .into_iter()
+                .map(|(key, value)| (text(key), text(value)));
+            if kind == "object" {
+                let entries = pairs.collect::<Vec<_>>();
+                return Ok(if entries.is_empty() {
+                    None
+                } else {
+                    Some(Strings::Map(entries))
+                });
+            }
+            let mut values = pairs
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            Ok(match (values.is_empty(), kind) {
+                (true, _) => None,
+                (false, "array") => Some(Strings::Many(values)),
+                (false, _) => Some(Strings::One(values.remove(0))),
+            })
+        }
+        "form" | "spaceDelimited" | "pipeDelimited" => {
+            let delimiter = match style {
+                "spaceDelimited" => " ",
+                "pipeDelimited" => "|",
+                _ => ",",
+            };
+            match pairs().into_iter().find(|(key, _)| text(key) == name) {
+                Some((_, value)) => delimited(value, delimiter, false).map(Some),
+                None => Ok(None),
+            }
+        }
+        _ => {
+            let prefix = format!("{}[", name);
+            let entries = pairs()
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    let key = text(key);
+                    let key = key.strip_prefix(&prefix)?.strip_suffix(']')?.to_string();
+                    Some((key, text(value)))
+                })
+                .collect::<Vec<_>>();
+            Ok(if entries.is_empty() {
+                None
+            } else {
+                Some(Strings::Map(entries))
+            })
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn required<T: serde::de::DeserializeOwned>(
+    name: &str,
+    strings: Result<Option<Strings>, String>,
+) -> Result<T, String> {
+    optional(name, strings)?.ok_or_else(|| format!("missing parameter `{}`", name))
+}
+
+#[allow(dead_code)]
+fn optional<T: serde::de::DeserializeOwned>(
+    name: &str,
+    strings: Result<Option<Strings>, String>,
+) -> Result<Option<T>, String> {
+    let strings = match strings? {
+        Some(strings) => strings,
+        None => return Ok(None),
+    };
+    from_strings(|item| match &strings {
+        Strings::One(value) => item(value),
+        Strings::Many(values) => {
+            serde_json::Value::Array(values.iter().map(|value| item(value)).collect())
+        }
+        Strings::Map(entries) => serde_json::Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), item(value)))
+                .collect(),
+        ),
+    })
+    .map(Some)
+    .map_err(|error| format!("invalid parameter `{}`: {}", name, error))
+}
+
+#[allow(dead_code)]
+fn json_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(body).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn form_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, String> {
+    from_form(body).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn text_body(body: &[u8]) -> Result<String, String> {
+    String::from_utf8(body.to_vec()).map_err(|error| format!("invalid body: {}", error))
+}
+
+#[allow(dead_code)]
+fn respond(
+    status: u16,
+    content_type: Option<&'static str>,
+    body: Vec<u8>,
+) -> http::Response<Vec<u8>> {
+    let mut response = http::Response::new(body);
+    *response.status_mut() =
+        http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = content_type {
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(content_type),
+        );
+    }
+    response
+}
+
+#[allow(dead_code)]
+fn form_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match to_form(body) {
+        Ok(body) => respond(status, Some(content_type), body.into_bytes()),
+        Err(error) => respond(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+
+/// Serializes the properties of an object as the pairs of a form, writing values which
+/// are not strings as JSON and leaving out `null`s.
+#[allow(dead_code)]
+fn to_form<T: serde::Serialize>(body: &T) -> Result<String, serde_json::Error> {
+    let entries = match serde_json::to_value(body)? {
+        serde_json::Value::Object(entries) => entries,
+        _ => {
+            return Err(<serde_json::Error as serde::ser::Error>::custom(
+                "only objects can be serialized as forms",
+            ))
+        }
+    };
+    let encode = |text: &str| {
+        let mut encoded = String::new();
+        for byte in text.bytes() {
+            match byte {
+                b' ' => encoded.push('+'),
+                byte if byte.is_ascii_alphanumeric() || b"*-._".contains(&byte) => {
+                    encoded.push(byte as char)
+                }
+                byte => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    };
+    Ok(entries
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            format!("{}={}", encode(key), encode(&value))
+        })
+        .collect::<Vec<_>>()
+        .join("&"))
+}
+
+#[allow(dead_code)]
+fn json_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match serde_json::to_vec(body) {
+        Ok(body) => respond(status, Some(content_type), body),
+        Err(error) => respond(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+
+/// Percent-decodes `input`, and with `plus` also decodes `+` as a space.
+#[allow(dead_code)]
+fn decode(input: &[u8], plus: bool) -> String {
+    let mut bytes = Vec::new();
+    let mut index = 0;
+    while index < input.len() {
+        let escaped = input
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (input[index], escaped) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                index += 3;
+                continue;
+            }
+            (b'+', _) if plus => bytes.push(b' '),
+            (byte, _) => bytes.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The decoded pairs of a query string or form.
+#[allow(dead_code)]
+fn form_pairs(input: &[u8]) -> Vec<(String, String)> {
+    input
+        .split(|byte| *byte == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut split = pair.splitn(2, |byte| *byte == b'=');
+            let key = decode(split.next().unwrap_or_default(), true);
+            let value = decode(split.next().unwrap_or_default(), true);
+            (key, value)
+        })
+        .collect()
+}
+
+/// Deserializes strings as such if possible, and otherwise as JSON.
+#[allow(dead_code)]
+fn from_strings<T: serde::de::DeserializeOwned>(
+    value: impl Fn(&dyn Fn(&str) -> serde_json::Value) -> serde_json::Value,
+) -> Result<T, serde_json::Error> {
+    serde_json::from_value(value(&|item| serde_json::Value::String(item.into()))).or_else(|_| {
+        serde_json::from_value(value(&|item| {
+            serde_json::from_str(item).unwrap_or_else(|_| serde_json::Value::String(item.into()))
+        }))
+    })
+}
+
+/// Deserializes the pairs of a form as the properties of an object.
+#[allow(dead_code)]
+fn from_form<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, serde_json::Error> {
+    let pairs = form_pairs(body);
+    from_strings(|item| {
+        serde_json::Value::Object(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), item(value)))
+                .collect(),
+        )
+    })
+}